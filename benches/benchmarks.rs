@@ -36,9 +36,59 @@ fn benchmark_formatting(c: &mut Criterion) {
     });
 }
 
+fn benchmark_bulk_add_with_capacity(c: &mut Criterion) {
+    const COUNT: usize = 5000;
+
+    let mut group = c.benchmark_group("bulk add 5000 imports");
+
+    group.bench_function("new", |b| {
+        b.iter(|| {
+            let mut helper = ImportHelper::new();
+            for i in 0..COUNT {
+                helper.add_import_string(&format!("from package{} import Item", black_box(i)));
+            }
+            helper
+        })
+    });
+
+    group.bench_function("with_capacity", |b| {
+        b.iter(|| {
+            let mut helper = ImportHelper::with_capacity(COUNT);
+            for i in 0..COUNT {
+                helper.add_import_string(&format!("from package{} import Item", black_box(i)));
+            }
+            helper
+        })
+    });
+
+    group.finish();
+}
+
+fn benchmark_dotted_categorization(c: &mut Criterion) {
+    const COUNT: usize = 10_000;
+    const ROOTS: [&str; 5] = ["os", "xml", "email", "collections", "concurrent"];
+
+    // Distinct submodules of a few stdlib roots, so each call misses the per-package cache
+    let statements: Vec<String> = (0..COUNT)
+        .map(|i| format!("import {}.sub{}", ROOTS[i % ROOTS.len()], i))
+        .collect();
+
+    c.bench_function("categorize 10k dotted modules", |b| {
+        b.iter(|| {
+            let mut helper = ImportHelper::with_capacity(COUNT);
+            for statement in &statements {
+                helper.add_import_string(black_box(statement));
+            }
+            helper
+        })
+    });
+}
+
 criterion_group!(
     benches,
     benchmark_import_categorization,
-    benchmark_formatting
+    benchmark_formatting,
+    benchmark_bulk_add_with_capacity,
+    benchmark_dotted_categorization
 );
 criterion_main!(benches);
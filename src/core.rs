@@ -4,12 +4,50 @@
 //! providing the primary API for collecting, categorizing, and formatting Python
 //! imports according to PEP 8 and common Python formatting standards.
 
-use std::collections::{HashMap, HashSet};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
 
 use crate::registry::PackageRegistry;
-use crate::types::{AllCategorizedImports, CategorizedImports, FormattingConfig, ImportSpec};
+#[cfg(feature = "toml")]
+use crate::types::ParseConfigError;
+use crate::types::{
+    AllCategorizedImports, CategorizedImports, CategoryOverrides, CategoryRule, DepthOrder,
+    FormattingConfig, ImportManifest, ImportParseError, ImportSpec, ImportStats, LintCode,
+    LintViolation, ManifestEntry, RegistryDiff, RelativeOrder, SortMode, SourceImport, Style,
+};
+use crate::utils::categorization::{matches_package_pattern, matches_package_prefix};
+use crate::utils::formatting::{
+    alpha_bucket, count_import_lines, format_imports, insert_alpha_subheaders,
+};
+use crate::utils::parsing::{
+    collect_import_statements, custom_import_sort, extract_items_in_order, extract_package,
+    is_direct_star_import, is_future_import, is_import_segment, is_valid_identifier,
+    is_valid_import_item, is_valid_module_path, locate_import_block, package_of, split_statements,
+    split_trailing_comment, validate_import_statement,
+};
 use crate::{ImportCategory, ImportSections, ImportStatement, ImportType};
 
+/// Comment that makes isort skip the following lines, up to [`ISORT_ON`]
+const ISORT_OFF: &str = "# isort: off";
+
+/// Comment that makes isort sort imports again after [`ISORT_OFF`]
+const ISORT_ON: &str = "# isort: on";
+
+/// Signature of a closure resolving a package name to an import category
+type ResolverFn = dyn Fn(&str) -> Option<ImportCategory> + Send + Sync;
+
+/// User-supplied closure that resolves a package to a category before the registry is consulted
+#[derive(Clone)]
+struct CategoryResolver(Arc<ResolverFn>);
+
+impl fmt::Debug for CategoryResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CategoryResolver(..)")
+    }
+}
+
 /// Main helper for managing Python imports across the codebase
 #[derive(Debug)]
 pub struct ImportHelper {
@@ -21,10 +59,32 @@ pub struct ImportHelper {
     package_name: Option<String>,
     /// Custom local package prefixes to recognize
     local_package_prefixes: HashSet<String>,
+    /// Module names that are local only on an exact match (e.g. sibling `helpers.py`)
+    local_modules: HashSet<String>,
+    /// Glob patterns (`*` wildcard) for packages to treat as local
+    local_patterns: Vec<String>,
     /// Package registry for stdlib and third-party recognition
     registry: PackageRegistry,
     /// Formatting configuration for isort/ruff compliance
     formatting_config: FormattingConfig,
+    /// Whether `TYPE_CHECKING` is automatically added to the typing import
+    auto_type_checking_import: bool,
+    /// Optional closure consulted before the registry during categorization
+    resolver: Option<CategoryResolver>,
+    /// Ordered rules consulted after the resolver; the first match wins
+    category_rules: Vec<CategoryRule>,
+    /// Categories forced by `apply_overrides`, consulted before the registry
+    category_overrides: HashMap<String, ImportCategory>,
+    /// Version-gated conditional blocks preserved verbatim, keyed by category
+    conditional_blocks: Vec<(ImportCategory, Vec<String>)>,
+    /// Imports between `# fmt: off` and `# fmt: on` preserved verbatim, keyed by category
+    fenced_blocks: Vec<(ImportCategory, Vec<String>)>,
+    /// Ingested imports that could not be structured, preserved verbatim
+    raw_unparsed: Vec<String>,
+    /// Imports gated on a runtime condition, grouped by condition
+    conditional_imports: Vec<(String, Vec<String>)>,
+    /// Module receiving the listed `typing` names of programmatic from imports
+    typing_backport: Option<(String, HashSet<String>)>,
 }
 
 impl ImportHelper {
@@ -36,8 +96,32 @@ impl ImportHelper {
             category_cache: HashMap::new(),
             package_name: None,
             local_package_prefixes: HashSet::new(),
+            local_modules: HashSet::new(),
+            local_patterns: Vec::new(),
             registry: PackageRegistry::new(),
             formatting_config: FormattingConfig::default(),
+            auto_type_checking_import: true,
+            resolver: None,
+            category_rules: Vec::new(),
+            category_overrides: HashMap::new(),
+            conditional_blocks: Vec::new(),
+            fenced_blocks: Vec::new(),
+            raw_unparsed: Vec::new(),
+            conditional_imports: Vec::new(),
+            typing_backport: None,
+        }
+    }
+
+    /// Create a new import helper with preallocated storage for `capacity` imports
+    ///
+    /// Useful for large code generation runs to avoid repeated reallocations
+    /// while collecting thousands of imports.
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            sections: ImportSections::with_capacity(capacity),
+            category_cache: HashMap::with_capacity(capacity),
+            ..Self::new()
         }
     }
 
@@ -54,15 +138,36 @@ impl ImportHelper {
     #[must_use]
     pub fn with_formatting_config(config: FormattingConfig) -> Self {
         Self {
-            sections: ImportSections::default(),
-            category_cache: HashMap::new(),
-            package_name: None,
-            local_package_prefixes: HashSet::new(),
-            registry: PackageRegistry::new(),
             formatting_config: config,
+            ..Self::new()
         }
     }
 
+    /// Create a new import helper with formatting configuration read from TOML
+    ///
+    /// Requires the `toml` feature; see [`FormattingConfig::from_toml`] for the
+    /// accepted keys.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseConfigError`] if the document is not valid TOML or the
+    /// formatting table holds a value of the wrong type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let helper = ImportHelper::with_config_from_toml("[formatting]\nline_length = 100\n").unwrap();
+    /// assert_eq!(helper.formatting_config().line_length, 100);
+    ///
+    /// assert!(ImportHelper::with_config_from_toml("[formatting]\nline_length = \"wide\"\n").is_err());
+    /// ```
+    #[cfg(feature = "toml")]
+    pub fn with_config_from_toml(text: &str) -> Result<Self, ParseConfigError> {
+        FormattingConfig::from_toml(text).map(Self::with_formatting_config)
+    }
+
     /// Create a new import helper with package name and custom formatting
     #[must_use]
     pub fn with_package_and_config(package_name: String, config: FormattingConfig) -> Self {
@@ -83,6 +188,80 @@ impl ImportHelper {
         self.formatting_config = config;
     }
 
+    /// Set the maximum line length before from imports wrap
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.set_line_length(100);
+    /// assert_eq!(helper.formatting_config().line_length, 100);
+    /// ```
+    pub fn set_line_length(&mut self, line_length: usize) -> &mut Self {
+        self.formatting_config.line_length = line_length;
+        self
+    }
+
+    /// Set the number of spaces per indentation level of wrapped imports
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.set_indent_size(2);
+    /// assert_eq!(helper.formatting_config().indent(), "  ");
+    /// ```
+    pub fn set_indent_size(&mut self, indent_size: usize) -> &mut Self {
+        self.formatting_config.indent_size = indent_size;
+        self
+    }
+
+    /// Set the item count above which from imports always wrap
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.set_multiline_threshold(8);
+    /// assert_eq!(helper.formatting_config().multiline_threshold, 8);
+    /// ```
+    pub fn set_multiline_threshold(&mut self, threshold: usize) -> &mut Self {
+        self.formatting_config.multiline_threshold = threshold;
+        self
+    }
+
+    /// Enable or disable automatic insertion of `from typing import TYPE_CHECKING`
+    ///
+    /// By default, adding any `TYPE_CHECKING` import also adds `TYPE_CHECKING`
+    /// to the regular typing import. Disable this if you manage that import yourself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.set_auto_type_checking_import(false);
+    /// helper.add_type_checking_import("from httpx import Client");
+    /// assert!(helper.is_empty());
+    /// ```
+    pub fn set_auto_type_checking_import(&mut self, enabled: bool) -> &mut Self {
+        self.auto_type_checking_import = enabled;
+        self
+    }
+
+    /// Check whether `TYPE_CHECKING` is automatically added to the typing import
+    #[must_use]
+    pub fn auto_type_checking_import(&self) -> bool {
+        self.auto_type_checking_import
+    }
+
     /// Get immutable reference to the package registry
     ///
     /// # Examples
@@ -122,6 +301,50 @@ impl ImportHelper {
         &mut self.registry
     }
 
+    /// Get the packages this helper's registry added or removed relative to the defaults
+    ///
+    /// Shorthand for [`PackageRegistry::diff`] against [`PackageRegistry::new`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.registry_mut().add_stdlib_package("foo");
+    ///
+    /// let diff = helper.difference_from_default_registry();
+    /// assert_eq!(diff.added_stdlib, vec!["foo"]);
+    /// ```
+    #[must_use]
+    pub fn difference_from_default_registry(&self) -> RegistryDiff {
+        self.registry.diff(&PackageRegistry::new())
+    }
+
+    /// Reset the registry's standard library and third-party lists to the defaults
+    ///
+    /// Undoes every customization made through [`registry_mut`](Self::registry_mut)
+    /// to either list and clears the categorization cache, so already collected
+    /// imports keep their category but new ones use the defaults.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.registry_mut().add_stdlib_package("foo");
+    /// helper.reset_registry();
+    /// assert!(helper.difference_from_default_registry().is_empty());
+    /// ```
+    pub fn reset_registry(&mut self) -> &mut Self {
+        self.registry
+            .reset_stdlib_to_defaults()
+            .reset_third_party_to_defaults();
+        self.category_cache.clear();
+        self
+    }
+
     /// Clear the categorization cache
     ///
     /// Call this after modifying the registry to ensure changes take effect.
@@ -141,7 +364,157 @@ impl ImportHelper {
         self
     }
 
+    /// Set a closure that resolves packages to categories before the registry is consulted
+    ///
+    /// This lets build scripts hook into the actual Python environment, e.g. by
+    /// checking whether a module is importable. Returning `None` falls through to
+    /// the regular local-prefix and registry logic. Setting a resolver clears the
+    /// categorization cache.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::{ImportCategory, ImportHelper};
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.set_resolver(|package| (package == "mypkg").then_some(ImportCategory::Local));
+    /// helper.add_import_string("from mypkg import thing");
+    ///
+    /// let (_, _, _, local) = helper.get_categorized();
+    /// assert_eq!(local, vec!["from mypkg import thing"]);
+    /// ```
+    pub fn set_resolver(
+        &mut self,
+        f: impl Fn(&str) -> Option<ImportCategory> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.resolver = Some(CategoryResolver(Arc::new(f)));
+        self.category_cache.clear();
+        self
+    }
+
+    /// Remove a previously set resolver closure
+    pub fn clear_resolver(&mut self) -> &mut Self {
+        self.resolver = None;
+        self.category_cache.clear();
+        self
+    }
+
+    /// Set ordered rules that categorize packages before the registry is consulted
+    ///
+    /// Rules are evaluated in order and the first matching one decides the
+    /// category; packages no rule matches fall through to the local-prefix and
+    /// registry logic. A [`set_resolver`](Self::set_resolver) closure still
+    /// takes precedence, and `__future__` is never recategorized. Replaces any
+    /// previous rules and clears the categorization cache.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::{CategoryRule, ImportCategory, ImportHelper, PackageMatcher};
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.set_categorization_rules(vec![CategoryRule::new(
+    ///     PackageMatcher::Prefix("pydantic".into()),
+    ///     ImportCategory::Local,
+    /// )]);
+    /// helper.add_import_string("from pydantic import BaseModel");
+    ///
+    /// let (_, _, _, local) = helper.get_categorized();
+    /// assert_eq!(local, vec!["from pydantic import BaseModel"]);
+    /// ```
+    pub fn set_categorization_rules(&mut self, rules: Vec<CategoryRule>) -> &mut Self {
+        self.category_rules = rules;
+        self.category_cache.clear();
+        self
+    }
+
+    /// Force packages into categories in one step
+    ///
+    /// Each listed package and its submodules get the listed category, ahead
+    /// of local prefixes and the registry, so `xml.etree` can be made
+    /// third-party while the rest of `xml` stays standard library. The most
+    /// specific listed package decides, and a later call overrides an earlier
+    /// one. A [`set_resolver`](Self::set_resolver) closure and
+    /// [categorization rules](Self::set_categorization_rules) still take
+    /// precedence. The categorization cache is cleared once at the end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::{CategoryOverrides, ImportHelper};
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.apply_overrides(&CategoryOverrides {
+    ///     local: vec!["requests".to_string()],
+    ///     ..CategoryOverrides::default()
+    /// });
+    /// helper.add_import_string("import requests");
+    ///
+    /// let (_, _, _, local) = helper.get_categorized();
+    /// assert_eq!(local, vec!["import requests"]);
+    /// ```
+    pub fn apply_overrides(&mut self, overrides: &CategoryOverrides) -> &mut Self {
+        let forced = [
+            (&overrides.stdlib, ImportCategory::StandardLibrary),
+            (&overrides.third_party, ImportCategory::ThirdParty),
+            (&overrides.local, ImportCategory::Local),
+        ];
+        for (packages, category) in forced {
+            for package in packages {
+                self.category_overrides.insert(package.clone(), category);
+            }
+        }
+        self.category_cache.clear();
+        self
+    }
+
+    /// Get the category forced for a package or its closest overridden parent
+    fn overridden_category(&self, package: &str) -> Option<ImportCategory> {
+        let mut candidate = package;
+        loop {
+            if let Some(&category) = self.category_overrides.get(candidate) {
+                return Some(category);
+            }
+            candidate = candidate.rsplit_once('.')?.0;
+        }
+    }
+
+    /// Reroute some `typing` names to a backport module such as `typing_extensions`
+    ///
+    /// Names listed here that are later added from `typing` through
+    /// [`add_from_import`](Self::add_from_import),
+    /// [`add_type_checking_from_import`](Self::add_type_checking_from_import) or
+    /// their `_owned` variants are imported from `module` instead; other
+    /// `typing` names are unaffected. Imports already collected and imports
+    /// added as statement strings are left alone. Replaces any previous setting.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.set_typing_backport(&["Self", "override"], "typing_extensions");
+    /// helper.add_from_import("typing", &["Any", "Self"]);
+    ///
+    /// assert_eq!(
+    ///     helper.get_formatted(),
+    ///     vec!["from typing import Any", "", "from typing_extensions import Self"]
+    /// );
+    /// ```
+    pub fn set_typing_backport(&mut self, names: &[&str], module: &str) -> &mut Self {
+        self.typing_backport = Some((
+            module.to_string(),
+            names.iter().map(|name| (*name).to_string()).collect(),
+        ));
+        self
+    }
+
     /// Add a custom local package prefix to the recognition list
+    ///
+    /// Prefixes match on module-segment boundaries, so dotted prefixes such as
+    /// `services.auth` match `services.auth` and `services.auth.handlers` but
+    /// not `services.authz`.
     pub fn add_local_package_prefix(&mut self, prefix: impl Into<String>) -> &mut Self {
         let prefix = prefix.into();
         self.local_package_prefixes.insert(prefix);
@@ -157,13 +530,60 @@ impl ImportHelper {
         self
     }
 
+    /// Add a module that is local only when imported by exactly this name
+    ///
+    /// Useful for single-file scripts importing a sibling module by bare name:
+    /// `helpers` makes `import helpers` local, but not `import helpersx` or
+    /// `import helpers.sub`. Adding a module clears the categorization cache.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_local_module("helpers");
+    /// helper.add_import_string("import helpers");
+    ///
+    /// let (_, _, _, local) = helper.get_categorized();
+    /// assert_eq!(local, vec!["import helpers"]);
+    /// ```
+    pub fn add_local_module(&mut self, module: &str) -> &mut Self {
+        self.local_modules.insert(module.trim().to_string());
+        self.category_cache.clear();
+        self
+    }
+
+    /// Add a glob pattern for packages to treat as local
+    ///
+    /// The only wildcard is `*`, which matches any run of characters. The pattern
+    /// is matched against the full package name and each of its dotted parents, so
+    /// `*.internal` matches `billing.internal.api` and `company_*` matches
+    /// `company_auth` (but not `companyx`). Patterns are checked after exact
+    /// prefixes and are slower, so prefer [`add_local_package_prefix`](Self::add_local_package_prefix)
+    /// where possible. Adding a pattern clears the categorization cache.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_local_pattern("company_*");
+    /// helper.add_import_string("from company_auth import login");
+    ///
+    /// let (_, _, _, local) = helper.get_categorized();
+    /// assert_eq!(local, vec!["from company_auth import login"]);
+    /// ```
+    pub fn add_local_pattern(&mut self, pattern: &str) -> &mut Self {
+        self.local_patterns.push(pattern.to_string());
+        self.category_cache.clear();
+        self
+    }
+
     /// Add an import using structured `ImportSpec`
     pub fn add_import(&mut self, spec: &ImportSpec) {
-        let import_statement = if let Some(items) = &spec.items {
-            format!("from {} import {}", spec.package, items.join(", "))
-        } else {
-            format!("import {}", spec.package)
-        };
+        let import_statement = Self::spec_statement(spec);
 
         if spec.type_checking {
             self.add_type_checking_import(&import_statement);
@@ -172,76 +592,462 @@ impl ImportHelper {
         }
     }
 
-    /// Convenience method to add import from string (for backward compatibility)
-    pub fn add_import_string(&mut self, import_statement: &str) {
-        self.add_regular_import(import_statement);
-    }
+    /// Add an import using structured `ImportSpec`, validating it first
+    ///
+    /// Unlike [`add_import`](Self::add_import), this rejects specs that would
+    /// produce malformed statements: the package must be a valid dotted
+    /// identifier and every item must be a valid identifier (or `*`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ImportParseError`] describing the first invalid part of the spec.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::{ImportHelper, types::ImportSpec};
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// assert!(helper.add_import_spec_checked(&ImportSpec::from("typing", vec!["Any"])).is_ok());
+    /// assert!(helper.add_import_spec_checked(&ImportSpec::direct("")).is_err());
+    /// ```
+    pub fn add_import_spec_checked(&mut self, spec: &ImportSpec) -> Result<(), ImportParseError> {
+        let package = spec.package.trim();
+        if package.is_empty() {
+            return Err(ImportParseError::EmptyPackage);
+        }
 
-    /// Add an import statement using string (internal method)
-    fn add_regular_import(&mut self, import_statement: &str) {
-        if let Some(import) = self.parse_import(import_statement) {
-            match (&import.category, &import.import_type) {
-                (ImportCategory::Future, _) => self.sections.future.push(import),
-                (ImportCategory::StandardLibrary, ImportType::Direct) => {
-                    self.sections.standard_library_direct.push(import)
-                }
-                (ImportCategory::StandardLibrary, ImportType::From) => {
-                    self.sections.standard_library_from.push(import)
-                }
-                (ImportCategory::ThirdParty, ImportType::Direct) => {
-                    self.sections.third_party_direct.push(import)
-                }
-                (ImportCategory::ThirdParty, ImportType::From) => {
-                    self.sections.third_party_from.push(import)
-                }
-                (ImportCategory::Local, ImportType::Direct) => {
-                    self.sections.local_direct.push(import)
-                }
-                (ImportCategory::Local, ImportType::From) => self.sections.local_from.push(import),
+        let is_from_import = spec.items.as_ref().is_some_and(|items| !items.is_empty());
+        if !is_valid_module_path(package, is_from_import) {
+            return Err(ImportParseError::InvalidPackage(spec.package.clone()));
+        }
+
+        if let Some(items) = &spec.items {
+            if let Some(invalid) = items.iter().find(|item| !is_valid_import_item(item)) {
+                return Err(ImportParseError::InvalidItem(invalid.clone()));
             }
         }
-    }
 
-    /// Add a from import statement programmatically
-    /// Example: `add_from_import("typing", &["Any", "Optional"])`
-    pub fn add_from_import(&mut self, package: &str, items: &[&str]) {
-        let import_statement = if items.len() == 1 {
-            format!("from {} import {}", package, items[0])
-        } else {
-            format!("from {} import {}", package, items.join(", "))
-        };
-        self.add_regular_import(&import_statement);
+        self.add_import(spec);
+        Ok(())
     }
 
-    /// Add a multiline from import statement programmatically
-    pub fn add_from_import_multiline(&mut self, package: &str, items: &[&str]) {
-        if items.is_empty() {
-            return;
-        }
-
-        if items.len() == 1 {
-            self.add_from_import(package, items);
+    /// Convenience method to add import from string (for backward compatibility)
+    ///
+    /// Several statements joined by `;` are added one by one; segments that are
+    /// not imports are ignored.
+    pub fn add_import_string(&mut self, import_statement: &str) {
+        if !import_statement.contains(';') {
+            self.add_regular_import(import_statement);
             return;
         }
-
-        let mut import_statement = format!("from {} import (\n", package);
-        for item in items {
-            import_statement.push_str(&format!("    {},\n", item));
+        for segment in split_statements(import_statement) {
+            if is_import_segment(segment) {
+                self.add_regular_import(segment);
+            }
         }
-        import_statement.push(')');
-
-        self.add_regular_import(&import_statement);
     }
 
-    /// Add a from import statement to `TYPE_CHECKING` block programmatically
-    /// Example: `add_type_checking_from_import("httpx", &["Client", "Response"])`
-    pub fn add_type_checking_from_import(&mut self, package: &str, items: &[&str]) {
-        let import_statement = if items.len() == 1 {
-            format!("from {} import {}", package, items[0])
-        } else {
-            format!("from {} import {}", package, items.join(", "))
+    /// Add an import gated on a runtime condition
+    ///
+    /// Imports sharing a condition are rendered together by
+    /// [`render_file`](Self::render_file) as an `if <condition>:` block placed
+    /// after the regular import groups.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::{ImportHelper, types::ImportSpec};
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_conditional_import("settings.USE_ORJSON", &ImportSpec::direct("orjson"));
+    /// assert_eq!(helper.render_file(), "if settings.USE_ORJSON:\n    import orjson\n");
+    /// ```
+    pub fn add_conditional_import(&mut self, condition: &str, spec: &ImportSpec) -> &mut Self {
+        let statement = Self::spec_statement(spec);
+        let condition = condition.trim();
+        match self
+            .conditional_imports
+            .iter_mut()
+            .find(|(existing, _)| existing == condition)
+        {
+            Some((_, statements)) => {
+                if !statements.contains(&statement) {
+                    statements.push(statement);
+                }
+            }
+            None => self
+                .conditional_imports
+                .push((condition.to_string(), vec![statement])),
+        }
+        self
+    }
+
+    /// Build the import statement described by a spec
+    fn spec_statement(spec: &ImportSpec) -> String {
+        match &spec.items {
+            Some(items) if !items.is_empty() => {
+                format!("from {} import {}", spec.package, items.join(", "))
+            }
+            _ => format!("import {}", spec.package),
+        }
+    }
+
+    /// Add an import from string, reporting malformed statements
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ImportParseError`] whose [`span`](ImportParseError::span) is
+    /// the byte range in `import_statement` where parsing failed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// assert!(helper.try_add_import_string("from typing import Any").is_ok());
+    ///
+    /// let error = helper.try_add_import_string("form typing import Any").unwrap_err();
+    /// assert_eq!(error.span(), Some(0..4));
+    /// ```
+    pub fn try_add_import_string(
+        &mut self,
+        import_statement: &str,
+    ) -> Result<(), ImportParseError> {
+        validate_import_statement(import_statement)?;
+        self.add_regular_import(import_statement);
+        Ok(())
+    }
+
+    /// Add an import statement using string (internal method)
+    fn add_regular_import(&mut self, import_statement: &str) {
+        if let Some(import) = self.parse_import(import_statement) {
+            self.push_statement(import, false);
+        }
+    }
+
+    /// Store a parsed import in the section matching its category and type
+    fn push_statement(&mut self, mut import: ImportStatement, type_checking: bool) {
+        // Only `__future__` imports may enter the future section
+        if import.category == ImportCategory::Future && import.package != "__future__" {
+            import.category = self.resolve_category(&import.statement);
+        }
+
+        // A `typing` name imported at runtime is never repeated in the TYPE_CHECKING
+        // block (the `TYPE_CHECKING` guard itself is managed separately)
+        if import.import_type == ImportType::From && import.package == "typing" {
+            if type_checking {
+                let runtime: HashSet<&String> = self
+                    .sections
+                    .regular_sections()
+                    .into_iter()
+                    .flatten()
+                    .filter(|existing| existing.package == "typing")
+                    .flat_map(|existing| &existing.items)
+                    .filter(|item| *item != "TYPE_CHECKING")
+                    .collect();
+                let count = import.items.len();
+                import.items.retain(|item| !runtime.contains(item));
+                if import.items.is_empty() {
+                    return;
+                }
+                if import.items.len() != count {
+                    import.statement =
+                        format!("from {} import {}", import.package, import.items.join(", "));
+                    import.is_multiline = false;
+                }
+            } else {
+                self.remove_type_checking_typing_names(&import.items);
+            }
+        }
+
+        let sections = &mut self.sections;
+        let section = match (type_checking, &import.category, &import.import_type) {
+            (false, ImportCategory::Future, _) => &mut sections.future,
+            (false, ImportCategory::StandardLibrary, ImportType::Direct) => {
+                &mut sections.standard_library_direct
+            }
+            (false, ImportCategory::StandardLibrary, ImportType::From) => {
+                &mut sections.standard_library_from
+            }
+            (false, ImportCategory::ThirdParty, ImportType::Direct) => {
+                &mut sections.third_party_direct
+            }
+            (false, ImportCategory::ThirdParty, ImportType::From) => &mut sections.third_party_from,
+            (false, ImportCategory::Local, ImportType::Direct) => &mut sections.local_direct,
+            (false, ImportCategory::Local, ImportType::From) => &mut sections.local_from,
+            (true, ImportCategory::Future, _) => &mut sections.type_checking_future,
+            (true, ImportCategory::StandardLibrary, ImportType::Direct) => {
+                &mut sections.type_checking_standard_library_direct
+            }
+            (true, ImportCategory::StandardLibrary, ImportType::From) => {
+                &mut sections.type_checking_standard_library_from
+            }
+            (true, ImportCategory::ThirdParty, ImportType::Direct) => {
+                &mut sections.type_checking_third_party_direct
+            }
+            (true, ImportCategory::ThirdParty, ImportType::From) => {
+                &mut sections.type_checking_third_party_from
+            }
+            (true, ImportCategory::Local, ImportType::Direct) => {
+                &mut sections.type_checking_local_direct
+            }
+            (true, ImportCategory::Local, ImportType::From) => {
+                &mut sections.type_checking_local_from
+            }
         };
-        self.add_type_checking_import(&import_statement);
+        section.push(import);
+    }
+
+    /// Remove `typing` names from `TYPE_CHECKING` typing imports, dropping emptied imports
+    ///
+    /// `TYPE_CHECKING` itself is never removed. When this empties the
+    /// `TYPE_CHECKING` block, the guard import added for it is removed again
+    /// (if automatic guard handling is enabled).
+    fn remove_type_checking_typing_names(&mut self, items: &[String]) {
+        let mut removed = false;
+        for section in self.sections.sections_mut().into_iter().skip(7) {
+            section.retain_mut(|import| {
+                if import.import_type != ImportType::From || import.package != "typing" {
+                    return true;
+                }
+                let count = import.items.len();
+                import
+                    .items
+                    .retain(|item| item == "TYPE_CHECKING" || !items.contains(item));
+                if import.items.len() == count {
+                    return true;
+                }
+                removed = true;
+                import.statement =
+                    format!("from {} import {}", import.package, import.items.join(", "));
+                import.is_multiline = false;
+                !import.items.is_empty()
+            });
+        }
+
+        if removed && self.auto_type_checking_import && self.is_type_checking_empty() {
+            self.reconcile_type_checking();
+        }
+    }
+
+    /// Add a from import statement programmatically
+    /// Example: `add_from_import("typing", &["Any", "Optional"])`
+    pub fn add_from_import(&mut self, package: &str, items: &[&str]) {
+        for import_statement in self.programmatic_from_statements(package, items) {
+            self.add_regular_import(&import_statement);
+        }
+    }
+
+    /// Build the from import statements for programmatically added items
+    ///
+    /// This is a single statement, unless a
+    /// [typing backport](Self::set_typing_backport) splits off some `typing` names.
+    fn programmatic_from_statements(&self, package: &str, items: &[&str]) -> Vec<String> {
+        let Some((module, names)) = self
+            .typing_backport
+            .as_ref()
+            .filter(|_| package == "typing")
+        else {
+            return vec![format!("from {} import {}", package, items.join(", "))];
+        };
+
+        let (rerouted, kept): (Vec<&str>, Vec<&str>) = items
+            .iter()
+            .partition(|item| names.contains(item.split(" as ").next().unwrap_or(item).trim()));
+        let mut statements = Vec::new();
+        if !kept.is_empty() || rerouted.is_empty() {
+            statements.push(format!("from typing import {}", kept.join(", ")));
+        }
+        if !rerouted.is_empty() {
+            statements.push(format!("from {module} import {}", rerouted.join(", ")));
+        }
+        statements
+    }
+
+    /// Add items to the existing from import of a package
+    ///
+    /// The items are appended to the package's first regular from import (one
+    /// without a trailing comment, not auto-added), which is re-sorted instead of a separate
+    /// statement being added. Without such an import, this behaves like
+    /// [`add_from_import`](Self::add_from_import).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_from_import("typing", &["Optional"]);
+    /// helper.merge_items_into("typing", &["Any"]);
+    ///
+    /// assert_eq!(helper.count(), 1);
+    /// assert_eq!(helper.get_formatted(), vec!["from typing import Any, Optional"]);
+    /// ```
+    pub fn merge_items_into(&mut self, package: &str, items: &[&str]) {
+        let Some(parsed) =
+            self.parse_import(&format!("from {package} import {}", items.join(", ")))
+        else {
+            return;
+        };
+        let preserve_order = self.formatting_config.preserves_item_order(package);
+        let existing = self
+            .sections
+            .sections_mut()
+            .into_iter()
+            .take(7)
+            .flat_map(|section| section.iter_mut())
+            .find(|import| {
+                import.import_type == ImportType::From
+                    && import.package == parsed.package
+                    && import.comment.is_none()
+                    && !import.auto_added
+            });
+        let Some(existing) = existing else {
+            self.push_statement(parsed, false);
+            return;
+        };
+
+        for item in &parsed.items {
+            if !existing.items.contains(item) {
+                existing.items.push(item.clone());
+            }
+        }
+        if !preserve_order {
+            existing.items.sort_by(|a, b| custom_import_sort(a, b));
+        }
+        existing.statement = format!(
+            "from {} import {}",
+            existing.package,
+            existing.items.join(", ")
+        );
+        existing.is_multiline = false;
+        if parsed.package == "typing" {
+            self.remove_type_checking_typing_names(&parsed.items);
+        }
+    }
+
+    /// Rename an item imported from a package, e.g. for API migrations
+    ///
+    /// Every from import of `package` (including `TYPE_CHECKING` ones) that
+    /// imports `old` imports `new` instead, keeping any alias (`old as x`
+    /// becomes `new as x`). When the import already has the renamed item, the
+    /// duplicate is dropped. Items are re-sorted unless the package preserves
+    /// item order. Returns whether anything was renamed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_import_string("from pydantic import BaseModel, validator");
+    ///
+    /// assert!(helper.replace_item("pydantic", "validator", "field_validator"));
+    /// assert_eq!(
+    ///     helper.get_formatted(),
+    ///     vec!["from pydantic import BaseModel, field_validator"]
+    /// );
+    /// assert!(!helper.replace_item("pydantic", "validator", "field_validator"));
+    /// ```
+    pub fn replace_item(&mut self, package: &str, old: &str, new: &str) -> bool {
+        let preserve_order = self.formatting_config.preserves_item_order(package);
+        let mut replaced = false;
+        for section in self.sections.sections_mut() {
+            for import in section.iter_mut() {
+                if import.import_type != ImportType::From || import.package != package {
+                    continue;
+                }
+
+                let mut changed = false;
+                for item in &mut import.items {
+                    let (name, alias) = match item.split_once(" as ") {
+                        Some((name, alias)) => (name.trim(), Some(alias.trim())),
+                        None => (item.trim(), None),
+                    };
+                    if name != old {
+                        continue;
+                    }
+                    *item = match alias {
+                        Some(alias) => format!("{new} as {alias}"),
+                        None => new.to_string(),
+                    };
+                    changed = true;
+                }
+                if !changed {
+                    continue;
+                }
+
+                let mut seen = HashSet::new();
+                import.items.retain(|item| seen.insert(item.clone()));
+                if !preserve_order {
+                    import.items.sort_by(|a, b| custom_import_sort(a, b));
+                }
+                import.statement =
+                    format!("from {} import {}", import.package, import.items.join(", "));
+                import.is_multiline = false;
+                replaced = true;
+            }
+        }
+        replaced
+    }
+
+    /// Add a multiline from import statement programmatically
+    pub fn add_from_import_multiline(&mut self, package: &str, items: &[&str]) {
+        if items.is_empty() {
+            return;
+        }
+
+        if items.len() == 1 {
+            self.add_from_import(package, items);
+            return;
+        }
+
+        let mut import_statement = format!("from {} import (\n", package);
+        for item in items {
+            import_statement.push_str(&format!("    {},\n", item));
+        }
+        import_statement.push(')');
+
+        self.add_regular_import(&import_statement);
+    }
+
+    /// Add a from import statement to `TYPE_CHECKING` block programmatically
+    /// Example: `add_type_checking_from_import("httpx", &["Client", "Response"])`
+    pub fn add_type_checking_from_import(&mut self, package: &str, items: &[&str]) {
+        for import_statement in self.programmatic_from_statements(package, items) {
+            self.add_type_checking_import(&import_statement);
+        }
+    }
+
+    /// Add a from import statement from owned strings
+    ///
+    /// Same as [`add_from_import`](Self::add_from_import), for callers already
+    /// holding `String`s.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let names: Vec<String> = vec!["Any".into(), "Optional".into()];
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_from_import_owned("typing".to_string(), names);
+    /// assert_eq!(helper.get_formatted(), vec!["from typing import Any, Optional"]);
+    /// ```
+    pub fn add_from_import_owned(&mut self, package: String, items: Vec<String>) {
+        let items: Vec<&str> = items.iter().map(String::as_str).collect();
+        self.add_from_import(&package, &items);
+    }
+
+    /// Add a from import statement to `TYPE_CHECKING` block from owned strings
+    ///
+    /// Same as [`add_type_checking_from_import`](Self::add_type_checking_from_import),
+    /// for callers already holding `String`s.
+    pub fn add_type_checking_from_import_owned(&mut self, package: String, items: Vec<String>) {
+        let items: Vec<&str> = items.iter().map(String::as_str).collect();
+        self.add_type_checking_from_import(&package, &items);
     }
 
     /// Add a direct import statement programmatically
@@ -261,32 +1067,12 @@ impl ImportHelper {
     /// Add an import statement to the `TYPE_CHECKING` block
     pub fn add_type_checking_import(&mut self, import_statement: &str) {
         if let Some(import) = self.parse_import(import_statement) {
-            match (&import.category, &import.import_type) {
-                (ImportCategory::Future, _) => self.sections.type_checking_future.push(import),
-                (ImportCategory::StandardLibrary, ImportType::Direct) => self
-                    .sections
-                    .type_checking_standard_library_direct
-                    .push(import),
-                (ImportCategory::StandardLibrary, ImportType::From) => self
-                    .sections
-                    .type_checking_standard_library_from
-                    .push(import),
-                (ImportCategory::ThirdParty, ImportType::Direct) => {
-                    self.sections.type_checking_third_party_direct.push(import);
-                }
-                (ImportCategory::ThirdParty, ImportType::From) => {
-                    self.sections.type_checking_third_party_from.push(import);
-                }
-                (ImportCategory::Local, ImportType::Direct) => {
-                    self.sections.type_checking_local_direct.push(import);
-                }
-                (ImportCategory::Local, ImportType::From) => {
-                    self.sections.type_checking_local_from.push(import);
-                }
-            }
+            self.push_statement(import, true);
 
             // Automatically add TYPE_CHECKING to typing import when we have type checking imports
-            self.ensure_type_checking_import_added();
+            if self.auto_type_checking_import && !self.is_type_checking_empty() {
+                self.ensure_type_checking_import_added();
+            }
         }
     }
 
@@ -374,16 +1160,12 @@ impl ImportHelper {
         }
 
         // Sort each category alphabetically
-        future_imports.sort_by(|a, b| Self::sort_import_statements(a, b));
-        stdlib_imports.sort_by(|a, b| Self::sort_import_statements(a, b));
-        third_party_imports.sort_by(|a, b| Self::sort_import_statements(a, b));
-        local_imports.sort_by(|a, b| Self::sort_import_statements(a, b));
-
+        let sort = self.formatting_config.module_sort;
         (
-            future_imports,
-            stdlib_imports,
-            third_party_imports,
-            local_imports,
+            Self::sort_statement_lines(future_imports, sort),
+            Self::sort_statement_lines(stdlib_imports, sort),
+            Self::sort_statement_lines(third_party_imports, sort),
+            Self::sort_statement_lines(local_imports, sort),
         )
     }
 
@@ -433,16 +1215,12 @@ impl ImportHelper {
         }
 
         // Sort each category alphabetically
-        future_imports.sort_by(|a, b| Self::sort_import_statements(a, b));
-        stdlib_imports.sort_by(|a, b| Self::sort_import_statements(a, b));
-        third_party_imports.sort_by(|a, b| Self::sort_import_statements(a, b));
-        local_imports.sort_by(|a, b| Self::sort_import_statements(a, b));
-
+        let sort = self.formatting_config.module_sort;
         (
-            future_imports,
-            stdlib_imports,
-            third_party_imports,
-            local_imports,
+            Self::sort_statement_lines(future_imports, sort),
+            Self::sort_statement_lines(stdlib_imports, sort),
+            Self::sort_statement_lines(third_party_imports, sort),
+            Self::sort_statement_lines(local_imports, sort),
         )
     }
 
@@ -469,6 +1247,10 @@ impl ImportHelper {
     /// ```
     pub fn clear(&mut self) -> &mut Self {
         self.sections = ImportSections::default();
+        self.conditional_blocks.clear();
+        self.fenced_blocks.clear();
+        self.raw_unparsed.clear();
+        self.conditional_imports.clear();
         self.category_cache.clear();
         self
     }
@@ -500,863 +1282,5765 @@ impl ImportHelper {
     /// ```
     pub fn reset(&mut self) -> &mut Self {
         self.sections = ImportSections::default();
+        self.conditional_blocks.clear();
+        self.fenced_blocks.clear();
+        self.raw_unparsed.clear();
+        self.conditional_imports.clear();
         self.category_cache.clear();
         self.package_name = None;
         self.local_package_prefixes.clear();
+        self.local_modules.clear();
+        self.local_patterns.clear();
         self.registry = PackageRegistry::new();
         self.formatting_config = FormattingConfig::default();
+        self.auto_type_checking_import = true;
+        self.resolver = None;
+        self.category_rules.clear();
+        self.category_overrides.clear();
+        self.typing_backport = None;
         self
     }
 
-    /// Check if any imports have been collected (excluding `TYPE_CHECKING` imports)
-    #[must_use]
-    pub fn is_empty(&self) -> bool {
-        self.sections.future.is_empty()
-            && self.sections.standard_library_direct.is_empty()
-            && self.sections.standard_library_from.is_empty()
-            && self.sections.third_party_direct.is_empty()
-            && self.sections.third_party_from.is_empty()
-            && self.sections.local_direct.is_empty()
-            && self.sections.local_from.is_empty()
+    /// Keep only the collected imports matching a predicate
+    ///
+    /// The predicate is applied to every statement in every section, including
+    /// `TYPE_CHECKING` ones. Imports of the same package are still merged when
+    /// formatting. With [`FormattingConfig::honor_noqa`], imports carrying a
+    /// `noqa` comment are always kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::{ImportCategory, ImportHelper};
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_import_string("import os");
+    /// helper.add_import_string("import pydantic");
+    /// helper.retain(|import| import.category != ImportCategory::ThirdParty);
+    /// assert_eq!(helper.get_formatted(), vec!["import os"]);
+    /// ```
+    pub fn retain(&mut self, mut f: impl FnMut(&ImportStatement) -> bool) -> &mut Self {
+        let honor_noqa = self.formatting_config.honor_noqa;
+        for section in self.sections.sections_mut() {
+            section.retain(|import| (honor_noqa && import.has_noqa()) || f(import));
+        }
+        self
     }
 
-    /// Check if any `TYPE_CHECKING` imports have been collected
-    #[must_use]
-    pub fn is_type_checking_empty(&self) -> bool {
-        self.sections.type_checking_future.is_empty()
-            && self
-                .sections
-                .type_checking_standard_library_direct
-                .is_empty()
-            && self.sections.type_checking_standard_library_from.is_empty()
-            && self.sections.type_checking_third_party_direct.is_empty()
-            && self.sections.type_checking_third_party_from.is_empty()
-            && self.sections.type_checking_local_direct.is_empty()
-            && self.sections.type_checking_local_from.is_empty()
-    }
+    /// Remove imports whose bound names are not used
+    ///
+    /// `used_names` holds the identifiers referenced by the code the imports are
+    /// for. Unused items are removed from from imports individually and the
+    /// statement is dropped once it has no items left; direct imports are dropped
+    /// when none of their bindings is used. `__future__` imports, star imports and
+    /// the `TYPE_CHECKING` guard (while needed) are kept, as are `noqa` imports
+    /// with [`FormattingConfig::honor_noqa`].
+    ///
+    /// Returns the removed imports as statements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    /// use std::collections::HashSet;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_import_string("import os");
+    /// helper.add_import_string("import json");
+    ///
+    /// let removed = helper.prune_unused(&HashSet::from(["json"]));
+    /// assert_eq!(removed, vec!["import os"]);
+    /// assert_eq!(helper.get_formatted(), vec!["import json"]);
+    /// ```
+    pub fn prune_unused(&mut self, used_names: &HashSet<&str>) -> Vec<String> {
+        let honor_noqa = self.formatting_config.honor_noqa;
+        let needs_guard = !self.is_type_checking_empty();
+        let is_used = |binding: &str| {
+            binding == "*"
+                || used_names.contains(binding)
+                || (needs_guard && binding == "TYPE_CHECKING")
+        };
 
-    /// Count total number of import statements collected (excluding `TYPE_CHECKING` imports)
-    #[must_use]
-    pub fn count(&self) -> usize {
-        self.sections.future.len()
-            + self.sections.standard_library_direct.len()
-            + self.sections.standard_library_from.len()
-            + self.sections.third_party_direct.len()
-            + self.sections.third_party_from.len()
-            + self.sections.local_direct.len()
-            + self.sections.local_from.len()
+        let mut removed = Vec::new();
+        for section in self.sections.sections_mut() {
+            section.retain_mut(|import| {
+                if import.category == ImportCategory::Future || (honor_noqa && import.has_noqa()) {
+                    return true;
+                }
+
+                if import.import_type == ImportType::Direct {
+                    let used = import.binding_names().iter().any(|name| is_used(name));
+                    if !used {
+                        removed.push(import.statement.clone());
+                    }
+                    return used;
+                }
+
+                let (kept, unused): (Vec<String>, Vec<String>) =
+                    import.items.iter().cloned().partition(|item| {
+                        let binding = item
+                            .rsplit_once(" as ")
+                            .map_or(item.as_str(), |(_, alias)| alias);
+                        is_used(binding.trim())
+                    });
+                if unused.is_empty() {
+                    return true;
+                }
+
+                removed.push(format!(
+                    "from {} import {}",
+                    import.package,
+                    unused.join(", ")
+                ));
+                if kept.is_empty() {
+                    return false;
+                }
+                import.statement = format!("from {} import {}", import.package, kept.join(", "));
+                import.items = kept;
+                import.is_multiline = false;
+                true
+            });
+        }
+        removed
     }
 
-    /// Count total number of `TYPE_CHECKING` import statements collected
-    #[must_use]
-    pub fn count_type_checking(&self) -> usize {
-        self.sections.type_checking_future.len()
-            + self.sections.type_checking_standard_library_direct.len()
-            + self.sections.type_checking_standard_library_from.len()
-            + self.sections.type_checking_third_party_direct.len()
-            + self.sections.type_checking_third_party_from.len()
-            + self.sections.type_checking_local_direct.len()
-            + self.sections.type_checking_local_from.len()
-    }
-
-    /// Generate sorted and formatted import statements
-    #[must_use]
-    pub fn get_formatted(&self) -> Vec<String> {
-        let mut result = Vec::new();
-        let mut has_previous_section = false;
-
-        // Future imports
-        if !self.sections.future.is_empty() {
-            let future_imports = self.format_imports(&self.sections.future);
-            result.extend(future_imports);
-            has_previous_section = true;
-        }
-
-        // Standard library imports - direct first, then from
-        let std_has_direct = !self.sections.standard_library_direct.is_empty();
-        let std_has_from = !self.sections.standard_library_from.is_empty();
-
-        if std_has_direct || std_has_from {
-            if has_previous_section {
-                result.push(String::new()); // Empty line between sections
-            }
+    /// Alias from import items whose bound name is already taken by another import
+    ///
+    /// Regular imports are visited before `TYPE_CHECKING` ones, section by
+    /// section (future, standard library, third-party, local; direct before
+    /// from imports), in the order they were added within a section, not in
+    /// sorted output order. When an item binds a name an import visited earlier
+    /// already bound from a different source, it gets an alias made of its
+    /// package path and name, so `from pkg2 import Thing` becomes
+    /// `from pkg2 import Thing as pkg2_Thing`. Direct imports keep their names.
+    ///
+    /// Returns the renames applied as (`package.name`, alias) pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_import_string("from pkg1 import Thing");
+    /// helper.add_import_string("from pkg2 import Thing");
+    ///
+    /// let renames = helper.auto_disambiguate();
+    /// assert_eq!(renames, vec![("pkg2.Thing".to_string(), "pkg2_Thing".to_string())]);
+    /// ```
+    pub fn auto_disambiguate(&mut self) -> Vec<(String, String)> {
+        // Bound name -> the `package.name` it refers to
+        let mut bound: HashMap<String, String> = HashMap::new();
+        let mut renames = Vec::new();
+
+        for section in self.sections.sections_mut() {
+            for import in section.iter_mut() {
+                let sources = bound_sources(import);
+                if import.import_type == ImportType::Direct {
+                    for (binding, source) in sources {
+                        bound.entry(binding).or_insert(source);
+                    }
+                    continue;
+                }
 
-            // Direct imports first
-            if std_has_direct {
-                let std_direct_imports =
-                    self.format_imports(&self.sections.standard_library_direct);
-                result.extend(std_direct_imports);
-            }
+                let mut renamed = false;
+                let items = import.items.iter_mut().filter(|item| item.as_str() != "*");
+                for (item, (binding, source)) in items.zip(sources) {
+                    match bound.get(&binding) {
+                        Some(existing) if *existing != source => {
+                            let name = item.split(" as ").next().unwrap_or_default().trim();
+                            let alias = unique_alias(&import.package, name, &bound);
+                            *item = format!("{name} as {alias}");
+                            bound.insert(alias.clone(), source.clone());
+                            renames.push((source, alias));
+                            renamed = true;
+                        }
+                        Some(_) => {}
+                        None => {
+                            bound.insert(binding, source);
+                        }
+                    }
+                }
 
-            // From imports after direct imports
-            if std_has_from {
-                let std_from_imports = self.format_imports(&self.sections.standard_library_from);
-                result.extend(std_from_imports);
+                if renamed {
+                    import.statement =
+                        format!("from {} import {}", import.package, import.items.join(", "));
+                    import.is_multiline = false;
+                }
             }
-
-            has_previous_section = true;
         }
+        renames
+    }
 
-        // Third-party imports - direct first, then from
-        let third_has_direct = !self.sections.third_party_direct.is_empty();
-        let third_has_from = !self.sections.third_party_from.is_empty();
-
-        if third_has_direct || third_has_from {
-            if has_previous_section {
-                result.push(String::new()); // Empty line between sections
-            }
-
-            // Direct imports first
-            if third_has_direct {
-                let third_direct_imports = self.format_imports(&self.sections.third_party_direct);
-                result.extend(third_direct_imports);
-            }
-
-            // From imports after direct imports
-            if third_has_from {
-                let third_from_imports = self.format_imports(&self.sections.third_party_from);
-                result.extend(third_from_imports);
+    /// Build a copy whose conflicting from import items are aliased and annotated
+    ///
+    /// A bound name conflicts when imports from different sources bind it.
+    /// Each conflicting from import item is split into its own import, aliased
+    /// after its package path (see [`auto_disambiguate`](Self::auto_disambiguate))
+    /// and commented with `conflict`, so `from a import Thing` becomes
+    /// `from a import Thing as a_Thing  # conflict`. Direct imports keep their
+    /// names. Returns `None` when nothing conflicts.
+    fn annotated_conflicts(&self) -> Option<Self> {
+        // Bound name -> the distinct `package.name` sources binding it
+        let mut sources: HashMap<String, HashSet<String>> = HashMap::new();
+        for (_, section) in self.sections.named_sections() {
+            for import in section {
+                for (binding, source) in bound_sources(import) {
+                    sources.entry(binding).or_default().insert(source);
+                }
             }
-
-            has_previous_section = true;
+        }
+        let conflicting: HashSet<&str> = sources
+            .iter()
+            .filter(|(_, sources)| sources.len() > 1)
+            .map(|(binding, _)| binding.as_str())
+            .collect();
+        if conflicting.is_empty() {
+            return None;
         }
 
-        // Local imports - direct first, then from
-        let local_has_direct = !self.sections.local_direct.is_empty();
-        let local_has_from = !self.sections.local_from.is_empty();
-
-        if local_has_direct || local_has_from {
-            if has_previous_section {
-                result.push(String::new()); // Empty line between sections
-            }
+        let mut bound: HashMap<String, String> = sources
+            .keys()
+            .map(|binding| (binding.clone(), binding.clone()))
+            .collect();
+        let mut sections = self.sections.clone();
+        for section in sections.sections_mut() {
+            let mut annotated = Vec::new();
+            for mut import in section.drain(..) {
+                if import.import_type == ImportType::Direct {
+                    annotated.push(import);
+                    continue;
+                }
 
-            // Direct imports first
-            if local_has_direct {
-                let local_direct_imports = self.format_imports(&self.sections.local_direct);
-                result.extend(local_direct_imports);
-            }
+                let (conflicts, kept): (Vec<String>, Vec<String>) =
+                    import.items.iter().cloned().partition(|item| {
+                        let binding = item.rsplit(" as ").next().unwrap_or(item).trim();
+                        conflicting.contains(binding)
+                    });
+                if conflicts.is_empty() {
+                    annotated.push(import);
+                    continue;
+                }
 
-            // From imports after direct imports
-            if local_has_from {
-                let local_from_imports = self.format_imports(&self.sections.local_from);
-                result.extend(local_from_imports);
+                for item in conflicts {
+                    let name = item
+                        .split(" as ")
+                        .next()
+                        .unwrap_or(&item)
+                        .trim()
+                        .to_string();
+                    let alias = unique_alias(&import.package, &name, &bound);
+                    bound.insert(alias.clone(), alias.clone());
+                    let aliased = format!("{name} as {alias}");
+                    annotated.push(ImportStatement {
+                        statement: format!("from {} import {aliased}", import.package),
+                        items: vec![aliased],
+                        is_multiline: false,
+                        comment: Some(match &import.comment {
+                            Some(comment) => format!("{comment}  # conflict"),
+                            None => "conflict".to_string(),
+                        }),
+                        ..import.clone()
+                    });
+                }
+                if !kept.is_empty() {
+                    import.statement =
+                        format!("from {} import {}", import.package, kept.join(", "));
+                    import.items = kept;
+                    import.is_multiline = false;
+                    annotated.push(import);
+                }
             }
+            *section = annotated;
         }
 
-        result
-    }
-
-    /// Parse an import statement and categorize it
-    fn parse_import(&mut self, import_statement: &str) -> Option<ImportStatement> {
-        let trimmed = import_statement.trim();
-        if trimmed.is_empty() {
-            return None;
-        }
-
-        let category = self.categorize_import(trimmed);
-        let import_type = if trimmed.starts_with("from ") {
-            ImportType::From
-        } else {
-            ImportType::Direct
-        };
-        let package = Self::extract_package(trimmed);
-        let items = Self::extract_items(trimmed);
-        let is_multiline = trimmed.contains('(') || trimmed.contains(')');
-
-        // Reconstruct the statement with sorted items for from imports
-        // Preserve multiline format if present
-        let statement = if is_multiline {
-            trimmed.to_string()
-        } else if import_type == ImportType::From && !items.is_empty() {
-            format!("from {} import {}", package, items.join(", "))
-        } else {
-            trimmed.to_string()
-        };
-
-        Some(ImportStatement {
-            statement,
-            category,
-            import_type,
-            package,
-            items,
-            is_multiline,
+        Some(Self {
+            sections,
+            conditional_blocks: self.conditional_blocks.clone(),
+            fenced_blocks: self.fenced_blocks.clone(),
+            raw_unparsed: self.raw_unparsed.clone(),
+            conditional_imports: self.conditional_imports.clone(),
+            ..self.clone_config()
         })
     }
 
-    /// Categorize an import statement
-    fn categorize_import(&mut self, import_statement: &str) -> ImportCategory {
-        if import_statement.starts_with("from __future__") {
-            return ImportCategory::Future;
-        }
+    /// Rewrite `from package import submodule` into `import package.submodule`
+    ///
+    /// This is a best-effort heuristic: an item is treated as a submodule only
+    /// if it is a lowercase identifier and `package.item` is a known submodule
+    /// (see [`PackageRegistry::is_submodule`]). Unregistered submodules are left
+    /// alone. Unaliased rewrites change the bound name (`path` becomes
+    /// `os.path`), so code using the import must refer to the qualified name;
+    /// aliased items keep their alias (`import package.submodule as alias`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_import_string("from collections import abc");
+    /// helper.prefer_direct_submodule_imports();
+    /// assert_eq!(helper.get_formatted(), vec!["import collections.abc"]);
+    /// ```
+    pub fn prefer_direct_submodule_imports(&mut self) -> &mut Self {
+        let registry = &self.registry;
+        let mut rewrites: Vec<(String, bool)> = Vec::new();
+
+        for (index, section) in self.sections.sections_mut().into_iter().enumerate() {
+            let type_checking = index >= 7;
+            section.retain_mut(|import| {
+                if import.import_type != ImportType::From
+                    || import.category == ImportCategory::Future
+                {
+                    return true;
+                }
 
-        let package = Self::extract_package(import_statement);
+                let (submodules, kept): (Vec<String>, Vec<String>) =
+                    import.items.iter().cloned().partition(|item| {
+                        let name = item.split(" as ").next().unwrap_or(item).trim();
+                        is_lowercase_identifier(name)
+                            && registry.is_submodule(&format!("{}.{name}", import.package))
+                    });
+                for item in &submodules {
+                    let statement = match item.split_once(" as ") {
+                        Some((name, alias)) => {
+                            format!(
+                                "import {}.{} as {}",
+                                import.package,
+                                name.trim(),
+                                alias.trim()
+                            )
+                        }
+                        None => format!("import {}.{item}", import.package),
+                    };
+                    rewrites.push((statement, type_checking));
+                }
 
-        // Check cache first
-        if let Some(&cached_category) = self.category_cache.get(&package) {
-            return cached_category;
+                if submodules.is_empty() {
+                    return true;
+                }
+                if kept.is_empty() {
+                    return false;
+                }
+                import.statement = format!("from {} import {}", import.package, kept.join(", "));
+                import.items = kept;
+                import.is_multiline = false;
+                true
+            });
         }
 
-        // Determine category with priority order:
-        // 1. Local imports (relative or matching local prefixes)
-        // 2. Standard library (built-in or custom registered)
-        // 3. Third-party (custom registered or default)
-        let category = if self.is_local_import(import_statement) {
-            ImportCategory::Local
-        } else if self.is_standard_library_package(&package) {
-            ImportCategory::StandardLibrary
-        } else if self.is_common_third_party_package(&package) {
-            ImportCategory::ThirdParty
-        } else {
-            // Default to third-party for unknown packages
-            ImportCategory::ThirdParty
-        };
-
-        self.category_cache.insert(package, category);
-        category
-    }
-
-    /// Extract the package name from an import statement
-    fn extract_package(import_statement: &str) -> String {
-        if let Some(from_part) = import_statement.strip_prefix("from ") {
-            if let Some(import_pos) = from_part.find(" import ") {
-                return from_part[..import_pos].trim().to_string();
+        for (statement, type_checking) in rewrites {
+            if type_checking {
+                self.add_type_checking_import(&statement);
+            } else {
+                self.add_regular_import(&statement);
             }
-        } else if let Some(import_part) = import_statement.strip_prefix("import ") {
-            // For direct imports, return the full module path
-            return import_part
-                .split_whitespace()
-                .next()
-                .unwrap_or(import_part)
-                .trim()
-                .to_string();
         }
-
-        import_statement.to_string()
+        self
     }
 
-    /// Extract imported items from an import statement
-    fn extract_items(import_statement: &str) -> Vec<String> {
-        if let Some(from_part) = import_statement.strip_prefix("from ") {
-            if let Some(import_pos) = from_part.find(" import ") {
-                let items_part = &from_part[import_pos + 8..];
-                let cleaned = items_part.replace(['(', ')'], "").replace(',', " ");
-                let mut items: Vec<String> = cleaned
-                    .split_whitespace()
-                    .map(|s| s.trim().to_string())
-                    .filter(|s| !s.is_empty())
-                    .collect();
+    /// Rewrite imports of a package (and its submodules) to a vendored copy under `vendor_root`
+    ///
+    /// `from requests import get` becomes `from myapp._vendor.requests import get`
+    /// and `import requests as r` becomes `from myapp._vendor import requests as r`.
+    /// Items, aliases and comments are kept, and the rewritten imports are
+    /// categorized as local. An unaliased direct submodule import such as
+    /// `import requests.adapters` becomes `from myapp._vendor.requests import adapters`,
+    /// which binds `adapters` instead of `requests`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_import_string("import requests");
+    /// helper.vendor_package("requests", "myapp._vendor");
+    /// assert_eq!(helper.get_formatted(), vec!["from myapp._vendor import requests"]);
+    /// ```
+    pub fn vendor_package(&mut self, package: &str, vendor_root: &str) -> &mut Self {
+        let mut vendored = Vec::new();
+        for (index, section) in self.sections.sections_mut().into_iter().enumerate() {
+            let type_checking = index >= 7;
+            section.retain(|import| {
+                if !matches_package_prefix(&import.package, package) {
+                    return true;
+                }
+                vendored.push((import.clone(), type_checking));
+                false
+            });
+        }
 
-                // Sort items with ALL_CAPS first, then mixed case alphabetically
-                items.sort_by(|a, b| crate::utils::parsing::custom_import_sort(a, b));
-                return items;
+        for (mut import, type_checking) in vendored {
+            if import.import_type == ImportType::Direct {
+                let alias = import
+                    .statement
+                    .split_once(" as ")
+                    .map(|(_, alias)| alias.trim().to_string());
+                let (parent, name) = match import.package.rsplit_once('.') {
+                    Some((parent, name)) => (format!("{vendor_root}.{parent}"), name.to_string()),
+                    None => (vendor_root.to_string(), import.package.clone()),
+                };
+                let item = match alias {
+                    Some(alias) => format!("{name} as {alias}"),
+                    None => name,
+                };
+                import.import_type = ImportType::From;
+                import.package = parent;
+                import.items = vec![item];
+            } else {
+                import.package = format!("{vendor_root}.{}", import.package);
             }
-        } else if let Some(import_part) = import_statement.strip_prefix("import ") {
-            // For direct imports, the "item" is the module itself
-            return vec![import_part.trim().to_string()];
+            import.statement =
+                format!("from {} import {}", import.package, import.items.join(", "));
+            import.is_multiline = false;
+            import.category = ImportCategory::Local;
+            self.push_statement(import, type_checking);
         }
-        Vec::new()
+        self
     }
 
-    /// Check if this is a local/relative import
-    fn is_local_import(&self, import_statement: &str) -> bool {
-        // Check for relative imports
-        if import_statement.contains("from .")
-            || import_statement.contains("from ..")
-            || import_statement.contains("from ...")
-            || import_statement.contains("from ....")
-        {
-            return true;
-        }
-
-        let package = Self::extract_package(import_statement);
-
-        // Check custom local package prefixes first
-        for prefix in &self.local_package_prefixes {
-            if package.starts_with(prefix.as_str()) {
-                return true;
-            }
+    /// Add or remove the `from typing import TYPE_CHECKING` guard import as needed
+    ///
+    /// The guard is added (once) when there are `TYPE_CHECKING` imports, and
+    /// removed from every regular typing import when there are none, e.g. after
+    /// [`retain`](Self::retain) or [`prune_unused`](Self::prune_unused) emptied
+    /// the block. A typing import left without items is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_type_checking_import("from pydantic import BaseModel");
+    /// helper.retain(|import| import.package != "pydantic");
+    /// helper.reconcile_type_checking();
+    /// assert!(helper.is_empty());
+    /// ```
+    pub fn reconcile_type_checking(&mut self) -> &mut Self {
+        if !self.is_type_checking_empty() {
+            self.ensure_type_checking_import_added();
+            return self;
         }
 
-        // Fallback to package_name check for backwards compatibility
-        if let Some(pkg_name) = &self.package_name {
-            if package.starts_with(pkg_name) {
+        self.sections.standard_library_from.retain_mut(|import| {
+            if import.package != "typing" || !import.items.iter().any(|i| i == "TYPE_CHECKING") {
                 return true;
             }
-        }
-
-        false
-    }
-
-    /// Check if a package is part of Python's standard library
-    fn is_standard_library_package(&self, package: &str) -> bool {
-        // Check against the constant list of standard library modules
-        self.registry.is_stdlib(package)
+            import.items.retain(|item| item != "TYPE_CHECKING");
+            import.statement = format!("from typing import {}", import.items.join(", "));
+            !import.items.is_empty()
+        });
+        self
     }
 
-    /// Check if a package is a common third-party package
-    fn is_common_third_party_package(&self, package: &str) -> bool {
-        // Check against the constant list of common third-party packages
-        self.registry.is_third_party(package)
+    /// List `TYPE_CHECKING` imports that are already imported at runtime
+    ///
+    /// A direct import is redundant when the same statement exists among the
+    /// regular imports; for from imports, the items also imported at runtime
+    /// from the same package are reported as one `from ... import ...` line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_import_string("import httpx");
+    /// helper.add_type_checking_import("import httpx");
+    /// helper.add_type_checking_import("from pydantic import BaseModel");
+    ///
+    /// assert_eq!(helper.redundant_type_checking(), vec!["import httpx"]);
+    /// ```
+    #[must_use]
+    pub fn redundant_type_checking(&self) -> Vec<String> {
+        let (direct, from) = self.runtime_names();
+        self.sections
+            .type_checking_sections()
+            .into_iter()
+            .flatten()
+            .filter_map(|import| match import.import_type {
+                ImportType::Direct => direct
+                    .contains(&import.statement)
+                    .then(|| import.statement.clone()),
+                ImportType::From => {
+                    let runtime = from.get(&import.package)?;
+                    let items: Vec<&str> = import
+                        .items
+                        .iter()
+                        .filter(|item| runtime.contains(*item))
+                        .map(String::as_str)
+                        .collect();
+                    (!items.is_empty())
+                        .then(|| format!("from {} import {}", import.package, items.join(", ")))
+                }
+            })
+            .collect()
     }
 
-    /// Format a list of imports, merging same-package imports where appropriate
-    fn format_imports(&self, imports: &[ImportStatement]) -> Vec<String> {
-        crate::utils::formatting::format_imports(imports, &self.formatting_config)
-    }
+    /// Remove `TYPE_CHECKING` imports that are already imported at runtime
+    ///
+    /// Returns what was removed, as reported by
+    /// [`redundant_type_checking`](Self::redundant_type_checking). When this
+    /// empties the `TYPE_CHECKING` block, the guard import is removed as well
+    /// (if automatic guard handling is enabled).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_import_string("import httpx");
+    /// helper.add_type_checking_import("import httpx");
+    ///
+    /// assert_eq!(helper.remove_redundant_type_checking(), vec!["import httpx"]);
+    /// assert!(helper.is_type_checking_empty());
+    /// assert_eq!(helper.get_formatted(), vec!["import httpx"]);
+    /// ```
+    pub fn remove_redundant_type_checking(&mut self) -> Vec<String> {
+        let redundant = self.redundant_type_checking();
+        if redundant.is_empty() {
+            return redundant;
+        }
 
-    fn sort_import_statements(a: &str, b: &str) -> std::cmp::Ordering {
-        let a_is_import = a.starts_with("import ");
-        let b_is_import = b.starts_with("import ");
+        let (direct, from) = self.runtime_names();
+        for section in self.sections.sections_mut().into_iter().skip(7) {
+            section.retain_mut(|import| match import.import_type {
+                ImportType::Direct => !direct.contains(&import.statement),
+                ImportType::From => {
+                    let Some(runtime) = from.get(&import.package) else {
+                        return true;
+                    };
+                    let count = import.items.len();
+                    import.items.retain(|item| !runtime.contains(item));
+                    if import.items.len() != count {
+                        import.statement =
+                            format!("from {} import {}", import.package, import.items.join(", "));
+                        import.is_multiline = false;
+                    }
+                    !import.items.is_empty()
+                }
+            });
+        }
 
-        match (a_is_import, b_is_import) {
-            // Both are 'import' or both are 'from' - sort alphabetically
-            (true, true) | (false, false) => a.cmp(b),
-            // a is 'import', b is 'from' - a comes first
-            (true, false) => std::cmp::Ordering::Less,
-            // a is 'from', b is 'import' - b comes first
-            (false, true) => std::cmp::Ordering::Greater,
+        if self.auto_type_checking_import && self.is_type_checking_empty() {
+            self.reconcile_type_checking();
         }
+        redundant
     }
 
-    /// Automatically add `TYPE_CHECKING` to typing import when type checking imports are used
-    fn ensure_type_checking_import_added(&mut self) {
-        // Check if we already have a typing import with TYPE_CHECKING
-        let has_type_checking = self.sections.standard_library_from.iter().any(|import| {
-            import.package == "typing" && import.items.contains(&"TYPE_CHECKING".to_string())
-        });
-
-        if !has_type_checking {
-            // Check if we have any typing import that we can modify
-            if let Some(typing_import) = self
-                .sections
-                .standard_library_from
-                .iter_mut()
-                .find(|import| import.package == "typing")
-            {
-                // Add TYPE_CHECKING to existing typing import
-                if !typing_import.items.contains(&"TYPE_CHECKING".to_string()) {
-                    typing_import.items.push("TYPE_CHECKING".to_string());
-                    typing_import
-                        .items
-                        .sort_by(|a, b| crate::utils::parsing::custom_import_sort(a, b));
-
-                    // Update the statement string
-                    if typing_import.items.len() == 1 {
-                        typing_import.statement =
-                            format!("from typing import {}", typing_import.items[0]);
-                    } else {
-                        typing_import.statement =
-                            format!("from typing import {}", typing_import.items.join(", "));
-                    }
+    /// Index the regular imports: direct statements, and from-import items per package
+    fn runtime_names(&self) -> (HashSet<String>, HashMap<String, HashSet<String>>) {
+        let mut direct = HashSet::new();
+        let mut from: HashMap<String, HashSet<String>> = HashMap::new();
+        for import in self.sections.regular_sections().into_iter().flatten() {
+            match import.import_type {
+                ImportType::Direct => {
+                    direct.insert(import.statement.clone());
                 }
-            } else {
-                // No typing import exists, add one with just TYPE_CHECKING
-                self.add_import_string("from typing import TYPE_CHECKING");
+                ImportType::From => from
+                    .entry(import.package.clone())
+                    .or_default()
+                    .extend(import.items.iter().cloned()),
             }
         }
+        (direct, from)
     }
 
-    /// Clone configuration without imports (useful for creating multiple helpers with same config)
+    /// Get the import statements the library injected rather than the caller
+    ///
+    /// Currently this is the `from typing import TYPE_CHECKING` guard when it
+    /// had to be added as a new statement; a guard merged into an existing
+    /// typing import is not reported, since that statement was the caller's.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_type_checking_import("from httpx import Client");
+    /// assert_eq!(helper.auto_added_imports(), vec!["from typing import TYPE_CHECKING"]);
+    /// ```
     #[must_use]
-    pub fn clone_config(&self) -> Self {
-        Self {
-            sections: ImportSections::default(),
-            category_cache: self.category_cache.clone(),
-            package_name: self.package_name.clone(),
-            local_package_prefixes: self.local_package_prefixes.clone(),
-            registry: self.registry.clone(),
-            formatting_config: self.formatting_config.clone(),
-        }
+    pub fn auto_added_imports(&self) -> Vec<String> {
+        self.sections
+            .named_sections()
+            .into_iter()
+            .flat_map(|(_, imports)| imports.iter())
+            .filter(|import| import.auto_added)
+            .map(|import| import.statement.clone())
+            .collect()
     }
-}
 
-/// Convenience functions for common import operations
-impl ImportHelper {
-    /// Create imports for a model file with required type imports
-    pub fn create_model_imports(&mut self, required_types: &[String]) {
-        // Standard model imports
-        self.add_import_string("from pydantic import BaseModel, ConfigDict, Field");
+    /// Check whether any import (regular or `TYPE_CHECKING`) is from exactly this package
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_import_string("from pydantic import BaseModel");
+    /// assert!(helper.contains_package("pydantic"));
+    /// assert!(!helper.contains_package("pydantic.fields"));
+    /// ```
+    #[must_use]
+    pub fn contains_package(&self, package: &str) -> bool {
+        self.sections
+            .named_sections()
+            .into_iter()
+            .any(|(_, imports)| imports.iter().any(|import| import.package == package))
+    }
 
-        // Collect all typing imports needed across all types
-        let mut typing_imports = std::collections::HashSet::new();
-        let mut collections_abc_imports = std::collections::HashSet::new();
-        let mut datetime_imports = Vec::new();
-        let mut decimal_imports = Vec::new();
+    /// Check whether any import is from a package or one of its submodules
+    ///
+    /// Matching is segment-aware: `pydantic` matches `pydantic.fields` but not
+    /// `pydantic_settings`.
+    #[must_use]
+    pub fn contains_package_or_submodule(&self, package: &str) -> bool {
+        self.sections
+            .named_sections()
+            .into_iter()
+            .any(|(_, imports)| {
+                imports
+                    .iter()
+                    .any(|import| matches_package_prefix(&import.package, package))
+            })
+    }
 
-        for type_name in required_types {
-            match type_name.as_str() {
-                "datetime" | "date" | "time" | "timedelta" => {
-                    if !datetime_imports.contains(&type_name.as_str()) {
-                        datetime_imports.push(type_name.as_str());
-                    }
+    /// Get the final formatted statement a package produces, for debugging merges
+    ///
+    /// All imports of the package within its section are merged exactly as in
+    /// [`get_formatted`](Self::get_formatted); a multi-line result is joined
+    /// with newlines. From imports take precedence over a direct import of the
+    /// same module, and regular imports over `TYPE_CHECKING` ones. Returns
+    /// `None` if nothing is imported from the package.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_import_string("from typing import Optional");
+    /// helper.add_import_string("from typing import Any");
+    ///
+    /// assert_eq!(
+    ///     helper.rendered_statement_for("typing").as_deref(),
+    ///     Some("from typing import Any, Optional")
+    /// );
+    /// assert_eq!(helper.rendered_statement_for("os"), None);
+    /// ```
+    #[must_use]
+    pub fn rendered_statement_for(&self, package: &str) -> Option<String> {
+        let sections = self.sections.regular_sections();
+        let type_checking = self.sections.type_checking_sections();
+        let candidates = || sections.iter().chain(&type_checking);
+        let section = candidates()
+            .find(|section| {
+                section.iter().any(|import| {
+                    import.package == package && import.import_type == ImportType::From
+                })
+            })
+            .or_else(|| {
+                candidates().find(|section| section.iter().any(|import| import.package == package))
+            })?;
+
+        let imports: Vec<ImportStatement> = section
+            .iter()
+            .filter(|import| import.package == package)
+            .cloned()
+            .collect();
+        Some(self.format_imports(&imports).join("\n"))
+    }
+
+    /// Check whether a `from __future__ import` feature is imported
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_import_string("from __future__ import annotations");
+    /// assert!(helper.contains_future("annotations"));
+    /// assert!(!helper.contains_future("division"));
+    /// ```
+    #[must_use]
+    pub fn contains_future(&self, feature: &str) -> bool {
+        self.sections
+            .future
+            .iter()
+            .flat_map(|import| &import.items)
+            .any(|item| item.split(" as ").next().unwrap_or(item).trim() == feature)
+    }
+
+    /// Check if any imports have been collected (excluding `TYPE_CHECKING` imports)
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.sections.future.is_empty()
+            && self.sections.standard_library_direct.is_empty()
+            && self.sections.standard_library_from.is_empty()
+            && self.sections.third_party_direct.is_empty()
+            && self.sections.third_party_from.is_empty()
+            && self.sections.local_direct.is_empty()
+            && self.sections.local_from.is_empty()
+    }
+
+    /// Check if any `TYPE_CHECKING` imports have been collected
+    #[must_use]
+    pub fn is_type_checking_empty(&self) -> bool {
+        self.sections.type_checking_future.is_empty()
+            && self
+                .sections
+                .type_checking_standard_library_direct
+                .is_empty()
+            && self.sections.type_checking_standard_library_from.is_empty()
+            && self.sections.type_checking_third_party_direct.is_empty()
+            && self.sections.type_checking_third_party_from.is_empty()
+            && self.sections.type_checking_local_direct.is_empty()
+            && self.sections.type_checking_local_from.is_empty()
+    }
+
+    /// Count total number of import statements collected (excluding `TYPE_CHECKING` imports)
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.sections.future.len()
+            + self.sections.standard_library_direct.len()
+            + self.sections.standard_library_from.len()
+            + self.sections.third_party_direct.len()
+            + self.sections.third_party_from.len()
+            + self.sections.local_direct.len()
+            + self.sections.local_from.len()
+    }
+
+    /// Count total number of `TYPE_CHECKING` import statements collected
+    #[must_use]
+    pub fn count_type_checking(&self) -> usize {
+        self.sections.type_checking_future.len()
+            + self.sections.type_checking_standard_library_direct.len()
+            + self.sections.type_checking_standard_library_from.len()
+            + self.sections.type_checking_third_party_direct.len()
+            + self.sections.type_checking_third_party_from.len()
+            + self.sections.type_checking_local_direct.len()
+            + self.sections.type_checking_local_from.len()
+    }
+
+    /// Count the distinct packages imported (excluding `TYPE_CHECKING` imports)
+    ///
+    /// Statements from the same package count once, whether they are merged
+    /// from imports or a direct import of the same module.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_import_string("from typing import Any");
+    /// helper.add_import_string("from typing import Optional");
+    /// helper.add_import_string("import requests");
+    ///
+    /// assert_eq!(helper.count(), 3);
+    /// assert_eq!(helper.package_count(), 2);
+    /// ```
+    #[must_use]
+    pub fn package_count(&self) -> usize {
+        self.sections
+            .regular_sections()
+            .into_iter()
+            .flatten()
+            .map(|import| import.package.as_str())
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    /// Get the categories that have at least one import (excluding `TYPE_CHECKING` imports)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::{ImportCategory, ImportHelper};
+    ///
+    /// let mut helper = ImportHelper::with_package_name("myapp".to_string());
+    /// helper.add_import_string("import os");
+    ///
+    /// let present = helper.categories_present();
+    /// assert!(present.contains(&ImportCategory::StandardLibrary));
+    /// assert!(!present.contains(&ImportCategory::ThirdParty));
+    /// ```
+    #[must_use]
+    pub fn categories_present(&self) -> HashSet<ImportCategory> {
+        categories_in(self.sections.regular_sections())
+    }
+
+    /// Get the categories that have at least one `TYPE_CHECKING` import
+    #[must_use]
+    pub fn type_checking_categories_present(&self) -> HashSet<ImportCategory> {
+        categories_in(self.sections.type_checking_sections())
+    }
+
+    /// Get the formatted imports still missing from existing source code
+    ///
+    /// `existing` is ingested with this helper's configuration, and only the
+    /// regular imports (or individual items) not already present are returned,
+    /// formatted and ready to append. `TYPE_CHECKING` imports are not considered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_import_string("import os");
+    /// helper.add_import_string("from typing import Any, Optional");
+    ///
+    /// let missing = helper.missing_against("import os\nfrom typing import Any\n");
+    /// assert_eq!(missing, vec!["from typing import Optional"]);
+    /// ```
+    #[must_use]
+    pub fn missing_against(&self, existing: &str) -> Vec<String> {
+        let mut present = self.clone_config();
+        present.set_auto_type_checking_import(false);
+        present.ingest(existing);
+
+        let mut present_direct: HashSet<&str> = HashSet::new();
+        let mut present_items: HashMap<&str, HashSet<&str>> = HashMap::new();
+        for import in present.sections.regular_sections().into_iter().flatten() {
+            match import.import_type {
+                ImportType::Direct => {
+                    present_direct.insert(&import.statement);
                 }
-                "Decimal" => {
-                    if !decimal_imports.contains(&"Decimal") {
-                        decimal_imports.push("Decimal");
+                ImportType::From => present_items
+                    .entry(&import.package)
+                    .or_default()
+                    .extend(import.items.iter().map(String::as_str)),
+            }
+        }
+
+        let mut delta = self.clone_config();
+        delta.set_auto_type_checking_import(false);
+        for import in self.sections.regular_sections().into_iter().flatten() {
+            match import.import_type {
+                ImportType::Direct => {
+                    if !present_direct.contains(import.statement.as_str()) {
+                        delta.add_regular_import(&import.statement);
                     }
                 }
-                "UUID" => {
-                    self.add_import_string("from uuid import UUID");
-                }
-                // For complex types, extract typing imports
-                _ => {
-                    // Check if this type contains typing elements
-                    let extracted_typing = Self::extract_typing_imports_from_type(type_name);
-                    typing_imports.extend(extracted_typing);
-
-                    // Check for collections.abc imports
-                    if type_name.contains("Callable") {
-                        collections_abc_imports.insert("Callable".to_string());
+                ImportType::From => {
+                    let known = present_items.get(import.package.as_str());
+                    let missing: Vec<&str> = import
+                        .items
+                        .iter()
+                        .map(String::as_str)
+                        .filter(|item| !known.is_some_and(|items| items.contains(item)))
+                        .collect();
+                    if !missing.is_empty() {
+                        delta.add_from_import(&import.package, &missing);
                     }
                 }
             }
         }
 
-        // Add datetime imports if any were found
-        if !datetime_imports.is_empty() {
-            let import_statement = format!("from datetime import {}", datetime_imports.join(", "));
-            self.add_regular_import(&import_statement);
+        delta
+            .get_formatted()
+            .into_iter()
+            .filter(|line| !line.is_empty())
+            .collect()
+    }
+
+    /// Report absolute imports of a package that should have been relative
+    ///
+    /// For teams mandating relative imports within their own package, this
+    /// lints every collected import (regular and `TYPE_CHECKING`) whose root
+    /// module equals `package_root`. Nothing is rewritten; the offending
+    /// statements are returned in collection order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::with_package_name("myapp".to_string());
+    /// helper.add_import_string("from myapp.models import User");
+    /// helper.add_import_string("from .utils import helper");
+    ///
+    /// assert_eq!(
+    ///     helper.enforce_relative_within("myapp"),
+    ///     vec!["from myapp.models import User"]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn enforce_relative_within(&self, package_root: &str) -> Vec<String> {
+        self.sections
+            .named_sections()
+            .into_iter()
+            .flat_map(|(_, imports)| imports.iter())
+            .filter(|import| import.package.split('.').next() == Some(package_root))
+            .map(|import| import.statement.clone())
+            .collect()
+    }
+
+    /// Suggest runtime local imports that could be moved to `TYPE_CHECKING`
+    ///
+    /// Runtime local from imports within the package root of `current_module`
+    /// (relative imports, or absolute ones sharing its root module) are prone
+    /// to circular imports; if they are only used in annotations they belong in
+    /// the `TYPE_CHECKING` block. This is advisory: nothing is moved, and the
+    /// candidate statements are returned in collection order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::with_package_name("myapp".to_string());
+    /// helper.add_import_string("from myapp.models import User");
+    /// helper.add_import_string("from pydantic import BaseModel");
+    ///
+    /// assert_eq!(
+    ///     helper.suggest_type_checking_for_locals("myapp.services"),
+    ///     vec!["from myapp.models import User"]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn suggest_type_checking_for_locals(&self, current_module: &str) -> Vec<String> {
+        let root = current_module.split('.').next().unwrap_or_default();
+        self.sections
+            .local_from
+            .iter()
+            .filter(|import| {
+                import.package.starts_with('.') || import.package.split('.').next() == Some(root)
+            })
+            .map(|import| import.statement.clone())
+            .collect()
+    }
+
+    /// Get all names imported from a package after merging
+    ///
+    /// Items from every regular from import of `package` are deduplicated and
+    /// ordered like the formatted output (sorted, unless the package is listed in
+    /// [`FormattingConfig::preserve_item_order_packages`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_from_import("typing", &["Optional", "Any"]);
+    /// assert_eq!(helper.package_items("typing"), vec!["Any", "Optional"]);
+    /// ```
+    #[must_use]
+    pub fn package_items(&self, package: &str) -> Vec<String> {
+        self.merged_package_items(package, self.sections.regular_sections())
+    }
+
+    /// Get all names imported from a package in the `TYPE_CHECKING` block after merging
+    #[must_use]
+    pub fn type_checking_package_items(&self, package: &str) -> Vec<String> {
+        self.merged_package_items(package, self.sections.type_checking_sections())
+    }
+
+    /// List packages whose merged from import has at least `threshold` items
+    ///
+    /// Returns `(package, item_count)` pairs for the regular imports, sorted by
+    /// package. Useful for code-quality reports: such imports may read better as
+    /// a module import (`import typing`) instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_from_import("typing", &["Any", "Dict", "List"]);
+    /// helper.add_from_import("os", &["path"]);
+    /// assert_eq!(helper.large_imports(3), vec![("typing".to_string(), 3)]);
+    /// ```
+    #[must_use]
+    pub fn large_imports(&self, threshold: usize) -> Vec<(String, usize)> {
+        let mut packages: BTreeMap<&str, HashSet<&str>> = BTreeMap::new();
+        for import in self.sections.regular_sections().into_iter().flatten() {
+            if import.import_type == ImportType::From {
+                packages
+                    .entry(&import.package)
+                    .or_default()
+                    .extend(import.items.iter().map(String::as_str));
+            }
         }
 
-        // Add decimal imports if any were found
-        if !decimal_imports.is_empty() {
-            self.add_import_string("from decimal import Decimal");
-        }
+        packages
+            .into_iter()
+            .filter(|(_, items)| items.len() >= threshold)
+            .map(|(package, items)| (package.to_string(), items.len()))
+            .collect()
+    }
+
+    /// Iterate over the imported packages with their merged items
+    ///
+    /// Yields each package of the regular imports once, sorted by package,
+    /// with the deduplicated and sorted items of all its from imports. Packages
+    /// only imported directly (`import os`) yield an empty item list. Useful
+    /// for building a symbol index without rendering.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_import_string("from typing import Optional");
+    /// helper.add_import_string("from typing import Any");
+    /// helper.add_import_string("import os");
+    ///
+    /// let packages: Vec<(&str, Vec<&str>)> = helper.iter_packages().collect();
+    /// assert_eq!(packages, vec![("os", vec![]), ("typing", vec!["Any", "Optional"])]);
+    /// ```
+    pub fn iter_packages(&self) -> impl Iterator<Item = (&str, Vec<&str>)> {
+        let mut packages: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for import in self.sections.regular_sections().into_iter().flatten() {
+            let items = packages.entry(&import.package).or_default();
+            if import.import_type == ImportType::From {
+                items.extend(import.items.iter().map(String::as_str));
+            }
+        }
+
+        packages
+            .into_iter()
+            .map(|(package, items)| (package, items.into_iter().collect()))
+    }
+
+    /// Merge the from-import items of a package across the given sections
+    fn merged_package_items(
+        &self,
+        package: &str,
+        sections: [&Vec<ImportStatement>; 7],
+    ) -> Vec<String> {
+        let mut items: Vec<String> = Vec::new();
+        for import in sections.into_iter().flatten() {
+            if import.import_type == ImportType::From && import.package == package {
+                for item in &import.items {
+                    if !items.contains(item) {
+                        items.push(item.clone());
+                    }
+                }
+            }
+        }
+
+        if !self.formatting_config.preserves_item_order(package) {
+            items.sort_by(|a, b| custom_import_sort(a, b));
+        }
+        items
+    }
+
+    /// Get the third-party distributions required by the collected imports
+    ///
+    /// Top-level package names of all third-party imports (including
+    /// `TYPE_CHECKING` ones) are mapped through the registry's
+    /// import-name-to-distribution table, deduplicated and sorted, so the result
+    /// can be used for a requirements file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.registry_mut().add_third_party_package("cv2");
+    /// helper.add_import_string("import cv2");
+    /// helper.add_import_string("from pydantic.fields import Field");
+    /// assert_eq!(helper.third_party_packages(), vec!["opencv-python", "pydantic"]);
+    /// ```
+    #[must_use]
+    pub fn third_party_packages(&self) -> Vec<String> {
+        let sections = &self.sections;
+        let distributions: BTreeSet<&str> = [
+            &sections.third_party_direct,
+            &sections.third_party_from,
+            &sections.type_checking_third_party_direct,
+            &sections.type_checking_third_party_from,
+        ]
+        .into_iter()
+        .flatten()
+        .map(|import| {
+            let root = import.package.split('.').next().unwrap_or_default();
+            self.registry.distribution_name(root)
+        })
+        .collect();
+
+        distributions.into_iter().map(str::to_string).collect()
+    }
+
+    /// Suggest regular imports that could move into the `TYPE_CHECKING` block
+    ///
+    /// `type_only_names` holds the names the caller knows are only used in
+    /// annotations. For every regular from import, items whose bound name (the
+    /// alias, if any) is type-only are reported per package. Nothing is moved;
+    /// the result is advisory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    /// use std::collections::HashSet;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_from_import("collections.abc", &["Mapping", "Sequence"]);
+    ///
+    /// let type_only = HashSet::from(["Mapping"]);
+    /// assert_eq!(
+    ///     helper.suggest_type_checking(&type_only),
+    ///     vec![("collections.abc".to_string(), vec!["Mapping".to_string()])]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn suggest_type_checking(
+        &self,
+        type_only_names: &HashSet<&str>,
+    ) -> Vec<(String, Vec<String>)> {
+        let mut suggestions: Vec<(String, Vec<String>)> = Vec::new();
+        for import in self.sections.regular_sections().into_iter().flatten() {
+            if import.import_type != ImportType::From || import.category == ImportCategory::Future {
+                continue;
+            }
+
+            let movable = import.items.iter().filter(|item| {
+                let bound = item
+                    .rsplit_once(" as ")
+                    .map_or(item.as_str(), |(_, alias)| alias);
+                type_only_names.contains(bound.trim())
+            });
+            for item in movable {
+                let index = match suggestions
+                    .iter()
+                    .position(|(package, _)| *package == import.package)
+                {
+                    Some(index) => index,
+                    None => {
+                        suggestions.push((import.package.clone(), Vec::new()));
+                        suggestions.len() - 1
+                    }
+                };
+                if !suggestions[index].1.contains(item) {
+                    suggestions[index].1.push(item.clone());
+                }
+            }
+        }
+
+        for (_, items) in &mut suggestions {
+            items.sort_by(|a, b| custom_import_sort(a, b));
+        }
+        suggestions
+    }
+
+    /// Emit Rust source that rebuilds the collected imports
+    ///
+    /// The generated code creates a `helper` and replays the imports with
+    /// `add_direct_import`/`add_from_import` (and their `TYPE_CHECKING`
+    /// counterparts), one call per package with merged items. Imports carrying a
+    /// comment are replayed with `add_import_string`. Configuration is not
+    /// included. Useful for turning a scenario into a test fixture.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_import_string("from typing import Optional");
+    /// helper.add_import_string("from typing import Any");
+    ///
+    /// assert!(helper
+    ///     .to_builder_code()
+    ///     .contains(r#"helper.add_from_import("typing", &["Any", "Optional"]);"#));
+    /// ```
+    #[must_use]
+    pub fn to_builder_code(&self) -> String {
+        let mut code = vec!["let mut helper = ImportHelper::new();".to_string()];
+
+        for (type_checking, sections) in [
+            (false, self.sections.regular_sections()),
+            (true, self.sections.type_checking_sections()),
+        ] {
+            let prefix = if type_checking {
+                "add_type_checking_"
+            } else {
+                "add_"
+            };
+            let mut from_packages = Vec::new();
+
+            for import in sections.into_iter().flatten() {
+                if let Some(comment) = &import.comment {
+                    let method = if type_checking {
+                        "add_type_checking_import"
+                    } else {
+                        "add_import_string"
+                    };
+                    let statement = format!("{}  # {comment}", import.statement);
+                    code.push(format!("helper.{method}({statement:?});"));
+                } else if import.import_type == ImportType::Direct {
+                    let module = import.statement.trim_start_matches("import ").trim();
+                    let line = format!("helper.{prefix}direct_import({module:?});");
+                    if !code.contains(&line) {
+                        code.push(line);
+                    }
+                } else if !from_packages.contains(&import.package) {
+                    from_packages.push(import.package.clone());
+                    let items = self
+                        .merged_package_items(&import.package, sections)
+                        .iter()
+                        .map(|item| format!("{item:?}"))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    code.push(format!(
+                        "helper.{prefix}from_import({:?}, &[{items}]);",
+                        import.package
+                    ));
+                }
+            }
+        }
+
+        code.join("\n") + "\n"
+    }
+
+    /// Describe all collected imports as a structured manifest
+    ///
+    /// The manifest is meant for non-Rust tooling; enable the `serde` feature to
+    /// serialize it. Conditional blocks and unparsed statements are not included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::{ImportCategory, ImportHelper};
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_import_string("from typing import Any");
+    ///
+    /// let manifest = helper.to_manifest();
+    /// assert_eq!(manifest.imports[0].package, "typing");
+    /// assert_eq!(manifest.imports[0].category, ImportCategory::StandardLibrary);
+    /// ```
+    #[must_use]
+    pub fn to_manifest(&self) -> ImportManifest {
+        let regular = self
+            .sections
+            .regular_sections()
+            .into_iter()
+            .flatten()
+            .map(|import| (import, false));
+        let type_checking = self
+            .sections
+            .type_checking_sections()
+            .into_iter()
+            .flatten()
+            .map(|import| (import, true));
+
+        let imports = regular
+            .chain(type_checking)
+            .map(|(import, type_checking)| ManifestEntry {
+                statement: import.statement.clone(),
+                category: import.category,
+                import_type: import.import_type.clone(),
+                package: import.package.clone(),
+                items: import.items.clone(),
+                comment: import.comment.clone(),
+                type_checking,
+            })
+            .collect();
+        ImportManifest { imports }
+    }
+
+    /// Create a helper holding the imports described by a manifest
+    ///
+    /// Categories are taken from the manifest as-is rather than re-resolved.
+    #[must_use]
+    pub fn from_manifest(manifest: &ImportManifest) -> Self {
+        let mut helper = Self::new();
+        for entry in &manifest.imports {
+            let import = ImportStatement {
+                statement: entry.statement.clone(),
+                category: entry.category,
+                import_type: entry.import_type.clone(),
+                package: entry.package.clone(),
+                items: entry.items.clone(),
+                is_multiline: entry.statement.contains('('),
+                comment: entry.comment.clone(),
+                auto_added: false,
+            };
+            helper.push_statement(import, entry.type_checking);
+        }
+        helper
+    }
+
+    /// Create a helper from the vectors returned by [`get_categorized`](Self::get_categorized)
+    ///
+    /// Each line is filed under the category of the vector it came from, so no
+    /// package name or local prefixes are needed to reproduce the grouping.
+    /// Multi-line statements are reassembled before parsing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::with_package_name("myapp".to_string());
+    /// helper.add_import_string("from myapp.models import User");
+    ///
+    /// let rebuilt = ImportHelper::from_categorized(&helper.get_categorized());
+    /// assert_eq!(rebuilt.get_categorized(), helper.get_categorized());
+    /// ```
+    #[must_use]
+    pub fn from_categorized(categorized: &CategorizedImports) -> Self {
+        let mut helper = Self::new();
+        let (future, stdlib, third_party, local) = categorized;
+        helper.ingest_categorized(future, ImportCategory::Future, false);
+        helper.ingest_categorized(stdlib, ImportCategory::StandardLibrary, false);
+        helper.ingest_categorized(third_party, ImportCategory::ThirdParty, false);
+        helper.ingest_categorized(local, ImportCategory::Local, false);
+        helper
+    }
+
+    /// Create a helper from the vectors returned by [`get_all_categorized`](Self::get_all_categorized)
+    ///
+    /// Like [`from_categorized`](Self::from_categorized), with the last four
+    /// vectors going to the `TYPE_CHECKING` block.
+    #[must_use]
+    pub fn from_all_categorized(categorized: &AllCategorizedImports) -> Self {
+        let (future, stdlib, third_party, local, tc_future, tc_stdlib, tc_third_party, tc_local) =
+            categorized;
+        let mut helper = Self::from_categorized(&(
+            future.clone(),
+            stdlib.clone(),
+            third_party.clone(),
+            local.clone(),
+        ));
+        helper.ingest_categorized(tc_future, ImportCategory::Future, true);
+        helper.ingest_categorized(tc_stdlib, ImportCategory::StandardLibrary, true);
+        helper.ingest_categorized(tc_third_party, ImportCategory::ThirdParty, true);
+        helper.ingest_categorized(tc_local, ImportCategory::Local, true);
+        helper
+    }
+
+    /// Parse formatted lines of one category and store them under that category
+    fn ingest_categorized(
+        &mut self,
+        lines: &[String],
+        category: ImportCategory,
+        type_checking: bool,
+    ) {
+        for import in collect_import_statements(&lines.join("\n")) {
+            let statement = match import {
+                SourceImport::Statement { statement, .. } | SourceImport::Unparsed(statement) => {
+                    statement
+                }
+                SourceImport::ConditionalBlock(_) | SourceImport::Fenced(_) => continue,
+            };
+            if let Some(mut import) = self.parse_import(&statement) {
+                import.category = category;
+                self.push_statement(import, type_checking);
+            }
+        }
+    }
+
+    /// Get a breakdown of the collected imports by type
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_import_string("import os");
+    /// helper.add_import_string("from typing import Any");
+    /// helper.add_import_string("from typing import Optional");
+    ///
+    /// let stats = helper.stats();
+    /// assert_eq!(stats.direct, 1);
+    /// assert_eq!(stats.from_, 2);
+    /// assert_eq!(stats.merged_packages, 1);
+    /// ```
+    #[must_use]
+    pub fn stats(&self) -> ImportStats {
+        let sections = &self.sections;
+        let mut stats = ImportStats {
+            direct: sections.standard_library_direct.len()
+                + sections.third_party_direct.len()
+                + sections.local_direct.len(),
+            from_: sections.standard_library_from.len()
+                + sections.third_party_from.len()
+                + sections.local_from.len(),
+            future: sections.future.len(),
+            type_checking: self.count_type_checking(),
+            merged_packages: 0,
+        };
+
+        for (_, imports) in sections.named_sections() {
+            let mut per_package: HashMap<&str, usize> = HashMap::new();
+            for import in imports
+                .iter()
+                .filter(|import| import.import_type == ImportType::From)
+            {
+                *per_package.entry(import.package.as_str()).or_default() += 1;
+            }
+            stats.merged_packages += per_package.values().filter(|&&count| count > 1).count();
+        }
+
+        stats
+    }
+
+    /// Generate sorted and formatted import statements
+    #[must_use]
+    pub fn get_formatted(&self) -> Vec<String> {
+        self.format_section_groups(false, &self.formatting_config)
+    }
+
+    /// Return the formatted imports and [`clear`](Self::clear) the helper
+    ///
+    /// Equivalent to [`get_formatted`](Self::get_formatted) followed by
+    /// `clear()`, leaving the configuration in place for the next file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_import_string("import os");
+    /// assert_eq!(helper.take_formatted(), vec!["import os"]);
+    /// assert!(helper.is_empty());
+    /// ```
+    pub fn take_formatted(&mut self) -> Vec<String> {
+        let formatted = self.get_formatted();
+        self.clear();
+        formatted
+    }
+
+    /// Estimate the number of physical lines [`get_formatted`](Self::get_formatted) produces
+    ///
+    /// Multi-line wrapping, blank lines between groups, placeholders and
+    /// sub-headers are accounted for without building the strings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_import_string("import os");
+    /// helper.add_import_string("import requests");
+    /// assert_eq!(helper.import_lines_estimate(), 3);
+    /// ```
+    #[must_use]
+    pub fn import_lines_estimate(&self) -> usize {
+        let config = &self.formatting_config;
+        let mut lines = 0;
+        let mut has_previous_section = false;
+
+        if !self.sections.future.is_empty() {
+            lines += count_import_lines(&self.sections.future, config);
+            has_previous_section = config.blank_line_after_future;
+        }
+
+        for categories in &Self::category_groups(config) {
+            let (direct, from) = self.group_sections(categories, false);
+            let (direct, from) = (direct.as_ref(), from.as_ref());
+            let verbatim: usize = categories
+                .iter()
+                .map(|&category| self.verbatim_line_count(category))
+                .sum();
+            let is_empty = direct.is_empty() && from.is_empty() && verbatim == 0;
+            if is_empty && config.empty_group_placeholder.is_none() {
+                continue;
+            }
+
+            if has_previous_section {
+                lines += config.lines_between_sections;
+            }
+            if is_empty {
+                lines += 1;
+            } else {
+                lines += count_import_lines(direct, config)
+                    + count_import_lines(from, config)
+                    + verbatim;
+                if config.alpha_subheaders {
+                    lines += Self::count_alpha_subheaders(direct, from);
+                }
+            }
+            has_previous_section = true;
+        }
+
+        lines
+    }
+
+    /// Count the physical lines of preserved blocks and unparsed imports in a category
+    fn verbatim_line_count(&self, category: ImportCategory) -> usize {
+        let blocks: usize = self
+            .conditional_blocks
+            .iter()
+            .chain(&self.fenced_blocks)
+            .filter(|(block_category, _)| *block_category == category)
+            .map(|(_, lines)| lines.len())
+            .sum();
+        let unparsed: usize = self
+            .raw_unparsed
+            .iter()
+            .filter(|text| self.resolve_category(text) == category)
+            .map(|text| text.lines().count())
+            .sum();
+        blocks + unparsed
+    }
+
+    /// Count the first-letter sub-headers inserted into a group
+    fn count_alpha_subheaders(direct: &[ImportStatement], from: &[ImportStatement]) -> usize {
+        direct
+            .iter()
+            .chain(from)
+            .filter_map(|import| alpha_bucket(&import.package))
+            .collect::<BTreeSet<char>>()
+            .len()
+    }
+
+    /// Generate formatted import statements for a single category
+    ///
+    /// Only the requested group of regular imports is formatted (direct imports
+    /// first, then from imports), without any blank-line separators.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::{ImportCategory, ImportHelper};
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_import_string("import os");
+    /// helper.add_import_string("from pydantic import BaseModel");
+    ///
+    /// assert_eq!(
+    ///     helper.get_formatted_category(ImportCategory::ThirdParty),
+    ///     vec!["from pydantic import BaseModel"]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn get_formatted_category(&self, category: ImportCategory) -> Vec<String> {
+        let (direct, from) = self.category_sections(category, false);
+        Self::format_direct_and_from(direct, from, &self.formatting_config)
+    }
+
+    /// Format a single category as a block for composing into larger templates
+    ///
+    /// Same lines as [`get_formatted_category`](Self::get_formatted_category);
+    /// with `trailing_blank`, a blank line is appended so blocks can be
+    /// concatenated directly. An empty category yields no lines either way.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::{ImportCategory, ImportHelper};
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_import_string("import os");
+    ///
+    /// assert_eq!(
+    ///     helper.format_category_block(ImportCategory::StandardLibrary, true),
+    ///     vec!["import os", ""]
+    /// );
+    /// assert!(helper.format_category_block(ImportCategory::Local, true).is_empty());
+    /// ```
+    #[must_use]
+    pub fn format_category_block(
+        &self,
+        category: ImportCategory,
+        trailing_blank: bool,
+    ) -> Vec<String> {
+        let mut lines = self.get_formatted_category(category);
+        if trailing_blank && !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines
+    }
+
+    /// Generate formatted `TYPE_CHECKING` import statements for a single category
+    #[must_use]
+    pub fn get_type_checking_formatted_category(&self, category: ImportCategory) -> Vec<String> {
+        let (direct, from) = self.category_sections(category, true);
+        Self::format_direct_and_from(direct, from, &self.formatting_config)
+    }
+
+    /// Generate sorted and formatted `TYPE_CHECKING` import statements
+    ///
+    /// Uses the same grouping and blank-line rules as [`get_formatted`](Self::get_formatted),
+    /// without indentation or the surrounding `if TYPE_CHECKING:` guard.
+    #[must_use]
+    pub fn get_type_checking_formatted(&self) -> Vec<String> {
+        self.format_section_groups(true, &self.formatting_config)
+    }
+
+    /// Render the complete import block as Python source
+    ///
+    /// Regular imports come first, followed by an `if TYPE_CHECKING:` block when
+    /// any `TYPE_CHECKING` imports were collected. The output ends with a newline
+    /// (or is empty when there are no imports) and can be fed back into
+    /// [`ingest`](Self::ingest) to reproduce the same block, except for the
+    /// `if <condition>:` blocks of
+    /// [`add_conditional_import`](Self::add_conditional_import), which `ingest`
+    /// does not recognize. Ingested `if sys.version_info` blocks are emitted right
+    /// after the imports of their group, so blank lines around them are not kept.
+    /// A
+    /// [`header_comment`](FormattingConfig::header_comment) comes first. With
+    /// [`annotate_conflicts`](FormattingConfig::annotate_conflicts), names
+    /// imported from several modules are aliased and marked `# conflict`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_import_string("from pydantic import BaseModel");
+    /// helper.add_type_checking_import("from httpx import Client");
+    ///
+    /// assert_eq!(
+    ///     helper.render_file(),
+    ///     "from typing import TYPE_CHECKING\n\nfrom pydantic import BaseModel\n\nif TYPE_CHECKING:\n    from httpx import Client\n"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn render_file(&self) -> String {
+        self.render_with_config(&self.formatting_config)
+    }
+
+    /// Replace the import block of a Python file with this helper's output
+    ///
+    /// The existing top-of-file import block (see
+    /// [`locate_import_block`](crate::utils::parsing::locate_import_block)) is
+    /// replaced by [`render_file`](Self::render_file); everything else is kept.
+    /// Without an import block, the imports are inserted after the module
+    /// docstring (or the leading comments). One blank line separates the imports
+    /// from a module docstring, the blank lines after leading comments such as
+    /// a shebang are kept as they are, and so are the blank lines before the
+    /// following code (at least one). Comments inside the old block are not carried
+    /// over, so [`ingest`](Self::ingest) the source first to keep its imports.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let source = "\"\"\"Docs.\"\"\"\nimport sys\nimport os\n\n\nprint(sys.argv)\n";
+    /// let mut helper = ImportHelper::new();
+    /// helper.ingest(source);
+    ///
+    /// assert_eq!(
+    ///     helper.apply_to_source(source),
+    ///     "\"\"\"Docs.\"\"\"\n\nimport os\nimport sys\n\n\nprint(sys.argv)\n"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn apply_to_source(&self, source: &str) -> String {
+        let block = locate_import_block(source);
+        let rendered = self.render_file();
+        if block.is_empty() && rendered.is_empty() {
+            return source.to_string();
+        }
+
+        let mut head = source[..block.start].trim_end();
+        // Leading comments of the rendered block (the header comment and the
+        // isort marker) precede the first import, so they were left in the head
+        let leading: Vec<&str> = rendered
+            .lines()
+            .take_while(|line| line.starts_with('#'))
+            .collect();
+        for comment in leading.into_iter().rev() {
+            match head.strip_suffix(comment) {
+                Some(stripped) if stripped.is_empty() || stripped.ends_with('\n') => {
+                    head = stripped.trim_end();
+                }
+                _ => break,
+            }
+        }
+        // Trailing comments of the rendered block (the closing isort marker)
+        // follow the last import, so they were left at the start of the body
+        let trailing = rendered
+            .lines()
+            .rev()
+            .take_while(|line| line.starts_with('#'))
+            .collect::<Vec<_>>();
+        let mut body = &source[block.end..];
+        for comment in trailing.into_iter().rev() {
+            let Some(rest) = body.trim_start().strip_prefix(comment) else {
+                break;
+            };
+            match rest.split_once('\n') {
+                Some((line_end, remainder)) if line_end.trim().is_empty() => body = remainder,
+                None if rest.trim().is_empty() => body = "",
+                _ => break,
+            }
+        }
+        let mut blank_lines = 0;
+        while let Some((line, remainder)) = body.split_once('\n') {
+            if !line.trim().is_empty() {
+                break;
+            }
+            body = remainder;
+            blank_lines += 1;
+        }
+        if body.trim().is_empty() {
+            body = "";
+        }
+
+        // A docstring gets one blank line; leading comments keep their spacing
+        let head_gap = if head
+            .lines()
+            .last()
+            .is_some_and(|line| line.starts_with('#'))
+        {
+            source[head.len()..]
+                .lines()
+                .skip(1)
+                .take_while(|line| line.trim().is_empty())
+                .count()
+        } else {
+            1
+        };
+
+        let mut output = String::new();
+        if !head.is_empty() {
+            output.push_str(head);
+            output.push('\n');
+        }
+        if !rendered.is_empty() {
+            if !output.is_empty() {
+                output.push_str(&"\n".repeat(head_gap));
+            }
+            output.push_str(&rendered);
+        }
+        if !body.is_empty() {
+            if !output.is_empty() {
+                output.push_str(&"\n".repeat(blank_lines.max(1)));
+            }
+            output.push_str(body);
+        }
+        output
+    }
+
+    /// Check whether the import block of some source is already organized
+    ///
+    /// The import block of the source is ingested into a scratch helper with
+    /// this helper's configuration (see [`clone_config`](Self::clone_config))
+    /// and re-rendered with [`apply_to_source`](Self::apply_to_source); imports
+    /// after the first line of other code are not checked.
+    /// Returns true when that changes nothing but trailing whitespace, compared
+    /// line by line (so `\r\n` line endings also match). Neither
+    /// this helper's imports nor the source are modified, so this suits a
+    /// `--check` mode.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let helper = ImportHelper::new();
+    /// assert!(helper.is_sorted("import os\nimport sys\n\nimport requests\n"));
+    /// assert!(!helper.is_sorted("import sys\nimport os\n"));
+    /// ```
+    #[must_use]
+    pub fn is_sorted(&self, source: &str) -> bool {
+        let mut scratch = self.clone_config();
+        scratch.ingest(source);
+        let organized = scratch.apply_to_source(source);
+        let lines = organized.trim_end().lines().map(str::trim_end);
+        lines.eq(source.trim_end().lines().map(str::trim_end))
+    }
+
+    /// Report style problems in the import block of some source
+    ///
+    /// The top-level import statements of the block (see
+    /// [`locate_import_block`](crate::utils::parsing::locate_import_block)) are
+    /// categorized with this helper's configuration and checked for the
+    /// problems listed in [`LintCode`]. Imports indented under `if` or `try`
+    /// blocks are skipped, and each such block starts a new run of groups.
+    /// Nothing is modified. Violations are returned in source order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::{ImportHelper, LintCode};
+    ///
+    /// let helper = ImportHelper::new();
+    /// let violations = helper.lint("import requests\nimport os\n");
+    /// assert_eq!(violations.len(), 1);
+    /// assert_eq!(violations[0].code, LintCode::WrongGroupOrder);
+    /// assert_eq!(violations[0].line, 2);
+    ///
+    /// assert!(helper.lint("import os\n\nimport requests\n").is_empty());
+    /// ```
+    #[must_use]
+    pub fn lint(&self, source: &str) -> Vec<LintViolation> {
+        let block = locate_import_block(source);
+        let first_line = source[..block.start].matches('\n').count() + 1;
+        let groups = Self::category_groups(&self.formatting_config);
+        let mut violations = Vec::new();
+        let mut report = |code, line, message: String| {
+            violations.push(LintViolation {
+                code,
+                line,
+                message,
+            });
+        };
+
+        // Group index of the previous top-level import, if in the same run
+        let mut previous: Option<usize> = None;
+        let mut blank_before = false;
+        let mut lines = source[block].lines().enumerate();
+        while let Some((index, line)) = lines.next() {
+            let line_number = first_line + index;
+            if line.trim().is_empty() {
+                blank_before = true;
+                continue;
+            }
+            let code = line.split('#').next().unwrap_or_default().trim_end();
+            if code.is_empty() || line.starts_with(char::is_whitespace) {
+                continue;
+            }
+            if !code.starts_with("import ") && !code.starts_with("from ") {
+                previous = None;
+                continue;
+            }
+
+            // Join parenthesized and backslash-continued statements
+            let mut statement = code.to_string();
+            while statement.ends_with('\\') || (statement.contains('(') && !statement.contains(')'))
+            {
+                let Some((_, next)) = lines.next() else {
+                    break;
+                };
+                statement.push(' ');
+                statement.push_str(next.split('#').next().unwrap_or_default());
+            }
+            let statement = statement
+                .replace(['(', ')', '\\'], " ")
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            let category = match statement
+                .strip_prefix("from ")
+                .and_then(|rest| rest.split_once(" import "))
+            {
+                Some((package, items)) => {
+                    let items: Vec<&str> = items
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|item| !item.is_empty())
+                        .collect();
+                    if items.contains(&"*") {
+                        report(
+                            LintCode::WildcardImport,
+                            line_number,
+                            format!("wildcard import from `{package}`"),
+                        );
+                    } else if !self.formatting_config.preserves_item_order(package)
+                        && items
+                            .windows(2)
+                            .any(|pair| custom_import_sort(pair[0], pair[1]).is_gt())
+                    {
+                        report(
+                            LintCode::UnsortedItems,
+                            line_number,
+                            format!("items imported from `{package}` are not sorted"),
+                        );
+                    }
+                    self.resolve_category(&statement)
+                }
+                None => {
+                    let modules: Vec<&str> = statement
+                        .trim_start_matches("import ")
+                        .split(',')
+                        .map(str::trim)
+                        .collect();
+                    if modules.len() > 1 {
+                        report(
+                            LintCode::MultipleImports,
+                            line_number,
+                            format!("{} modules imported in one statement", modules.len()),
+                        );
+                    }
+                    let module = modules[0].split(' ').next().unwrap_or_default();
+                    self.resolve_category(&format!("import {module}"))
+                }
+            };
+
+            let group = if category == ImportCategory::Future {
+                0
+            } else {
+                groups
+                    .iter()
+                    .position(|group| group.contains(&category))
+                    .map_or(groups.len(), |position| position + 1)
+            };
+            match previous {
+                Some(previous) if group < previous => report(
+                    LintCode::WrongGroupOrder,
+                    line_number,
+                    format!("{category:?} import belongs to an earlier group"),
+                ),
+                Some(previous) if group != previous && !blank_before => report(
+                    LintCode::MissingBlankLine,
+                    line_number,
+                    "missing blank line before a new import group".to_string(),
+                ),
+                _ => {}
+            }
+            previous = Some(group);
+            blank_before = false;
+        }
+
+        violations
+    }
+
+    /// Compute a stable hash of the rendered import block
+    ///
+    /// The hash is FNV-1a over [`render_file`](Self::render_file), so it only
+    /// depends on the logical import set and formatting configuration, not on
+    /// insertion order, and stays the same across runs and platforms. Useful
+    /// for skipping code generation when the imports are unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut first = ImportHelper::new();
+    /// first.add_import_string("import os");
+    /// first.add_import_string("import sys");
+    ///
+    /// let mut second = ImportHelper::new();
+    /// second.add_import_string("import sys");
+    /// second.add_import_string("import os");
+    ///
+    /// assert_eq!(first.content_hash(), second.content_hash());
+    /// ```
+    #[must_use]
+    pub fn content_hash(&self) -> u64 {
+        const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+        self.render_file()
+            .bytes()
+            .fold(FNV_OFFSET_BASIS, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+            })
+    }
+
+    /// Render the complete import block in the exact style of a formatting tool
+    ///
+    /// This is a convenience wrapper around [`render_file`](Self::render_file)
+    /// that uses the matching [`FormattingConfig`] preset instead of the
+    /// helper's own configuration.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::{ImportHelper, Style};
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_import_string("from typing import Any");
+    /// assert_eq!(helper.render_as(Style::Black), "from typing import Any\n");
+    /// ```
+    #[must_use]
+    pub fn render_as(&self, style: Style) -> String {
+        self.render_with_config(&style.config())
+    }
+
+    /// Render the complete import block using the given formatting configuration
+    fn render_with_config(&self, config: &FormattingConfig) -> String {
+        if config.annotate_conflicts {
+            if let Some(annotated) = self.annotated_conflicts() {
+                return annotated.render_with_config(config);
+            }
+        }
+
+        let mut lines = self.format_section_groups(false, config);
+        let indent = config.indent();
+
+        for (condition, statements) in &self.conditional_imports {
+            if !lines.is_empty() {
+                lines.push(String::new());
+            }
+            lines.push(format!("if {condition}:"));
+            lines.extend(
+                statements
+                    .iter()
+                    .map(|statement| format!("{indent}{statement}")),
+            );
+        }
+
+        if !self.is_type_checking_empty() {
+            if !lines.is_empty() {
+                lines.push(String::new());
+            }
+            lines.push("if TYPE_CHECKING:".to_string());
+
+            for line in self.format_section_groups(true, config) {
+                if line.is_empty() {
+                    lines.push(line);
+                } else {
+                    lines.push(format!("{indent}{line}"));
+                }
+            }
+        }
+
+        if lines.is_empty() {
+            return String::new();
+        }
+        if config.preserve_order && config.emit_isort_off {
+            lines.insert(0, ISORT_OFF.to_string());
+            lines.push(ISORT_ON.to_string());
+        }
+        if let Some(header) = &config.header_comment {
+            let header = header.lines().map(|line| match line.trim_end() {
+                "" => "#".to_string(),
+                comment if comment.starts_with('#') => comment.to_string(),
+                text => format!("# {text}"),
+            });
+            lines.splice(0..0, header);
+        }
+
+        let mut output = lines.join("\n");
+        output.push('\n');
+        output
+    }
+
+    /// Collect the top-level imports of the import block of Python source code
+    ///
+    /// Only the block at the top of the file (see
+    /// [`locate_import_block`](crate::utils::parsing::locate_import_block)) is
+    /// read, the block [`apply_to_source`](Self::apply_to_source) replaces;
+    /// imports after the first line of other code stay where they are.
+    /// Imports inside an `if TYPE_CHECKING:` block are added as `TYPE_CHECKING`
+    /// imports; parenthesized multi-line imports are supported. Version-gated
+    /// blocks (`if sys.version_info ...:`) and `# fmt: off` / `# fmt: on` fenced
+    /// blocks are kept verbatim and re-emitted at the end of the group of their
+    /// first import, without reordering or merging. Non-import code is ignored.
+    /// The source's blank lines are not kept: rendering separates groups with
+    /// [`lines_between_sections`](FormattingConfig::lines_between_sections)
+    /// blank lines and uses none within a group.
+    /// Returns the number of import statements and blocks ingested.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// let count = helper.ingest("import os\n\nif TYPE_CHECKING:\n    from httpx import Client\n");
+    /// assert_eq!(count, 2);
+    /// assert_eq!(helper.count_type_checking(), 1);
+    /// ```
+    pub fn ingest(&mut self, source: &str) -> usize {
+        let block = locate_import_block(source);
+        let imports = collect_import_statements(&source[..block.end]);
+        for import in &imports {
+            match import {
+                SourceImport::Statement {
+                    statement,
+                    type_checking: true,
+                } => self.add_type_checking_import(statement),
+                SourceImport::Statement { statement, .. } => self.add_regular_import(statement),
+                SourceImport::ConditionalBlock(lines) => self.add_conditional_block(lines),
+                SourceImport::Fenced(lines) => {
+                    let category = self.verbatim_block_category(lines);
+                    self.fenced_blocks.push((category, lines.clone()));
+                }
+                SourceImport::Unparsed(text) => self.raw_unparsed.push(text.clone()),
+            }
+        }
+        imports.len()
+    }
+
+    /// Get ingested imports that could not be fully structured
+    ///
+    /// These are kept verbatim instead of being dropped, and are emitted at the
+    /// end of their best-guess category group.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.ingest("import os, sys\n");
+    /// assert_eq!(helper.raw_unparsed(), ["import os, sys"]);
+    /// assert_eq!(helper.get_formatted(), vec!["import os, sys"]);
+    /// ```
+    #[must_use]
+    pub fn raw_unparsed(&self) -> &[String] {
+        &self.raw_unparsed
+    }
+
+    /// Store a version-gated block verbatim in the group of its first import
+    fn add_conditional_block(&mut self, lines: &[String]) {
+        let category = self.verbatim_block_category(lines);
+        self.conditional_blocks.push((category, lines.to_vec()));
+    }
+
+    /// Get the category of a verbatim block from its first import
+    fn verbatim_block_category(&mut self, lines: &[String]) -> ImportCategory {
+        lines
+            .iter()
+            .map(|line| line.trim())
+            .find(|line| line.starts_with("import ") || line.starts_with("from "))
+            .map_or(ImportCategory::StandardLibrary, |statement| {
+                self.categorize_import(statement)
+            })
+    }
+
+    /// Format all category groups in PEP 8 order, separating non-empty
+    /// groups with blank lines
+    fn format_section_groups(&self, type_checking: bool, config: &FormattingConfig) -> Vec<String> {
+        let mut result = Vec::new();
+        let mut has_previous_section = false;
+
+        // Future imports
+        let (future, _) = self.category_sections(ImportCategory::Future, type_checking);
+        if !future.is_empty() {
+            result.extend(format_imports(future, config));
+            has_previous_section = config.blank_line_after_future;
+        }
+
+        // Remaining groups - direct first, then from
+        for categories in &Self::category_groups(config) {
+            let (direct, from) = self.group_sections(categories, type_checking);
+            let (direct, from) = (direct.as_ref(), from.as_ref());
+            let verbatim: Vec<String> = if type_checking {
+                Vec::new()
+            } else {
+                categories
+                    .iter()
+                    .flat_map(|&category| self.verbatim_lines(category))
+                    .collect()
+            };
+            let placeholder = config
+                .empty_group_placeholder
+                .as_ref()
+                .filter(|_| !type_checking);
+            let is_empty = direct.is_empty() && from.is_empty() && verbatim.is_empty();
+            if is_empty && placeholder.is_none() {
+                continue;
+            }
+
+            if has_previous_section {
+                // Empty lines between sections
+                result.extend(std::iter::repeat(String::new()).take(config.lines_between_sections));
+            }
+
+            match placeholder.filter(|_| is_empty) {
+                Some(placeholder) => result.push(placeholder.clone()),
+                None => {
+                    result.extend(Self::format_direct_and_from(direct, from, config));
+                    result.extend(verbatim);
+                }
+            }
+            has_previous_section = true;
+        }
+
+        result
+    }
+
+    /// Get the lines emitted verbatim at the end of a regular category group
+    ///
+    /// Conditional blocks come after the group's regular imports so that the
+    /// `import sys` their guard relies on comes first; unparsed imports follow.
+    fn verbatim_lines(&self, category: ImportCategory) -> Vec<String> {
+        let blocks = self
+            .conditional_blocks
+            .iter()
+            .chain(&self.fenced_blocks)
+            .filter(|(block_category, _)| *block_category == category)
+            .flat_map(|(_, lines)| lines.iter().cloned());
+        let unparsed = self
+            .raw_unparsed
+            .iter()
+            .filter(|text| self.resolve_category(text) == category)
+            .cloned();
+        blocks.chain(unparsed).collect()
+    }
+
+    /// Format direct imports followed by from imports of one category
+    fn format_direct_and_from(
+        direct: &[ImportStatement],
+        from: &[ImportStatement],
+        config: &FormattingConfig,
+    ) -> Vec<String> {
+        let mut result = Vec::new();
+        if !direct.is_empty() {
+            result.extend(Self::format_direct(direct, config));
+        }
+        if config.relative_imports_last || config.relative_depth_order != RelativeOrder::None {
+            let (relative, absolute): (Vec<ImportStatement>, Vec<ImportStatement>) = from
+                .iter()
+                .cloned()
+                .partition(|import| import.package.starts_with('.'));
+            let relative = Self::format_relative(relative, config);
+            if config.relative_imports_last {
+                result.extend(format_imports(&absolute, config));
+                result.extend(relative);
+            } else {
+                result.extend(relative);
+                result.extend(format_imports(&absolute, config));
+            }
+        } else if !from.is_empty() {
+            result.extend(format_imports(from, config));
+        }
+        if config.alpha_subheaders {
+            result = insert_alpha_subheaders(result);
+        }
+        result
+    }
+
+    /// Format direct imports, grouped by dotted depth when `direct_import_depth_order` is set
+    fn format_direct(direct: &[ImportStatement], config: &FormattingConfig) -> Vec<String> {
+        let Some(order) = config.direct_import_depth_order else {
+            return format_imports(direct, config);
+        };
+
+        let mut by_depth: BTreeMap<usize, Vec<ImportStatement>> = BTreeMap::new();
+        for import in direct {
+            let depth = import.package.split('.').count();
+            by_depth.entry(depth).or_default().push(import.clone());
+        }
+
+        let groups: Vec<Vec<ImportStatement>> = match order {
+            DepthOrder::ShallowFirst => by_depth.into_values().collect(),
+            DepthOrder::DeepFirst => by_depth.into_values().rev().collect(),
+        };
+        groups
+            .iter()
+            .flat_map(|group| format_imports(group, config))
+            .collect()
+    }
+
+    /// Format relative from imports, grouped by depth when `relative_depth_order` is set
+    fn format_relative(relative: Vec<ImportStatement>, config: &FormattingConfig) -> Vec<String> {
+        let mut by_depth: BTreeMap<usize, Vec<ImportStatement>> = BTreeMap::new();
+        for import in relative {
+            let depth = match config.relative_depth_order {
+                RelativeOrder::None => 0,
+                RelativeOrder::ShallowFirst | RelativeOrder::DeepFirst => {
+                    import.package.len() - import.package.trim_start_matches('.').len()
+                }
+            };
+            by_depth.entry(depth).or_default().push(import);
+        }
+
+        let groups: Vec<Vec<ImportStatement>> =
+            if config.relative_depth_order == RelativeOrder::DeepFirst {
+                by_depth.into_values().rev().collect()
+            } else {
+                by_depth.into_values().collect()
+            };
+        groups
+            .iter()
+            .flat_map(|group| format_imports(group, config))
+            .collect()
+    }
+
+    /// Get the non-future categories rendered together as one group, in output order
+    ///
+    /// Groups follow `section_order`, with unlisted categories appended in
+    /// default order. `Future` is skipped wherever it is listed: future
+    /// imports are always rendered first, before these groups.
+    fn category_groups(config: &FormattingConfig) -> Vec<Vec<ImportCategory>> {
+        let default_order = [
+            ImportCategory::StandardLibrary,
+            ImportCategory::ThirdParty,
+            ImportCategory::Local,
+        ];
+        let mut groups: Vec<Vec<ImportCategory>> = Vec::new();
+        for &category in config.section_order.iter().chain(&default_order) {
+            if category == ImportCategory::Future || groups.iter().flatten().any(|&c| c == category)
+            {
+                continue;
+            }
+            if config.merge_stdlib_third_party && category != ImportCategory::Local {
+                groups.push(vec![
+                    ImportCategory::StandardLibrary,
+                    ImportCategory::ThirdParty,
+                ]);
+            } else {
+                groups.push(vec![category]);
+            }
+        }
+        groups
+    }
+
+    /// Get the (direct, from) imports of a group of categories
+    ///
+    /// A single category borrows its sections; several are concatenated.
+    fn group_sections(
+        &self,
+        categories: &[ImportCategory],
+        type_checking: bool,
+    ) -> (Cow<'_, [ImportStatement]>, Cow<'_, [ImportStatement]>) {
+        if let [category] = categories {
+            let (direct, from) = self.category_sections(*category, type_checking);
+            return (Cow::Borrowed(direct), Cow::Borrowed(from));
+        }
+
+        let mut direct = Vec::new();
+        let mut from = Vec::new();
+        for &category in categories {
+            let (category_direct, category_from) = self.category_sections(category, type_checking);
+            direct.extend_from_slice(category_direct);
+            from.extend_from_slice(category_from);
+        }
+        (Cow::Owned(direct), Cow::Owned(from))
+    }
+
+    /// Get the (direct, from) sections holding imports of a category
+    ///
+    /// Future imports live in a single section, returned as the direct part.
+    fn category_sections(
+        &self,
+        category: ImportCategory,
+        type_checking: bool,
+    ) -> (&[ImportStatement], &[ImportStatement]) {
+        let sections = &self.sections;
+        match (category, type_checking) {
+            (ImportCategory::Future, false) => (&sections.future, &[]),
+            (ImportCategory::StandardLibrary, false) => (
+                &sections.standard_library_direct,
+                &sections.standard_library_from,
+            ),
+            (ImportCategory::ThirdParty, false) => {
+                (&sections.third_party_direct, &sections.third_party_from)
+            }
+            (ImportCategory::Local, false) => (&sections.local_direct, &sections.local_from),
+            (ImportCategory::Future, true) => (&sections.type_checking_future, &[]),
+            (ImportCategory::StandardLibrary, true) => (
+                &sections.type_checking_standard_library_direct,
+                &sections.type_checking_standard_library_from,
+            ),
+            (ImportCategory::ThirdParty, true) => (
+                &sections.type_checking_third_party_direct,
+                &sections.type_checking_third_party_from,
+            ),
+            (ImportCategory::Local, true) => (
+                &sections.type_checking_local_direct,
+                &sections.type_checking_local_from,
+            ),
+        }
+    }
+
+    /// Dump the collected imports in a human-readable form for debugging
+    ///
+    /// Each non-empty section is listed with one line per collected statement.
+    /// When `max_items` is set, item lists longer than the limit are truncated
+    /// with an `…(+k more)` suffix to keep the output scannable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.add_import_string("from typing import Any, Optional, Union");
+    ///
+    /// let dump = helper.debug_dump(Some(2));
+    /// assert!(dump.contains("typing [From]: Any, Optional, …(+1 more)"));
+    /// ```
+    #[must_use]
+    pub fn debug_dump(&self, max_items: Option<usize>) -> String {
+        let mut output = String::new();
+
+        for (name, imports) in self.sections.named_sections() {
+            if imports.is_empty() {
+                continue;
+            }
+
+            output.push_str(name);
+            output.push_str(":\n");
+            for import in imports {
+                let items = match max_items {
+                    Some(limit) if import.items.len() > limit => format!(
+                        "{}, …(+{} more)",
+                        import.items[..limit].join(", "),
+                        import.items.len() - limit
+                    ),
+                    _ => import.items.join(", "),
+                };
+                output.push_str(&format!(
+                    "  {} [{:?}]: {}\n",
+                    import.package, import.import_type, items
+                ));
+            }
+        }
+
+        output
+    }
+
+    /// Parse an import statement and categorize it
+    fn parse_import(&mut self, import_statement: &str) -> Option<ImportStatement> {
+        let trimmed = import_statement.trim();
+        // `import *` is invalid Python and is rejected outright
+        if trimmed.is_empty() || is_direct_star_import(trimmed) {
+            return None;
+        }
+
+        let (trimmed, comment) = split_trailing_comment(trimmed);
+        let category = self.categorize_import(trimmed);
+        let import_type = if trimmed.starts_with("from ") {
+            ImportType::From
+        } else {
+            ImportType::Direct
+        };
+        let package = extract_package(trimmed);
+        // Items keep declaration order; sorting happens when formatting
+        let items = extract_items_in_order(trimmed);
+        // A truncated `from x import` has nothing to import and would render as broken syntax
+        if import_type == ImportType::From && items.is_empty() {
+            return None;
+        }
+        let is_multiline = trimmed.contains('(') || trimmed.contains(')');
+
+        // Reconstruct the statement with sorted items for from imports
+        // Preserve multiline format if present
+        let statement = if is_multiline {
+            trimmed.to_string()
+        } else if import_type == ImportType::From && !items.is_empty() {
+            format!("from {} import {}", package, items.join(", "))
+        } else {
+            trimmed.to_string()
+        };
+
+        Some(ImportStatement {
+            statement,
+            category,
+            import_type,
+            package,
+            items,
+            is_multiline,
+            comment: comment.map(str::to_string),
+            auto_added: false,
+        })
+    }
+
+    /// Categorize an import statement
+    fn categorize_import(&mut self, import_statement: &str) -> ImportCategory {
+        if is_future_import(import_statement) {
+            return ImportCategory::Future;
+        }
+
+        let package = package_of(import_statement);
+
+        // Check cache first
+        if let Some(&cached_category) = self.category_cache.get(package) {
+            return cached_category;
+        }
+
+        let category = match self.root_category(package) {
+            Some(category) => category,
+            None => self.resolve_category(import_statement),
+        };
+        self.category_cache.insert(package.to_string(), category);
+        category
+    }
+
+    /// Categorize a dotted package by its root segment when the root alone decides it
+    ///
+    /// A standard library or local root makes every submodule the same category,
+    /// unless a resolver, glob pattern, exact local module or dotted local prefix
+    /// could single out a submodule. The root's category is cached, so sibling submodules skip the
+    /// parent walk through the registry.
+    fn root_category(&mut self, package: &str) -> Option<ImportCategory> {
+        let (root, _) = package.split_once('.')?;
+        if root.is_empty()
+            || self.resolver.is_some()
+            || !self.category_rules.is_empty()
+            || self
+                .category_overrides
+                .keys()
+                .any(|package| package.contains('.'))
+            || !self.local_patterns.is_empty()
+            || self.local_modules.contains(root)
+            || self
+                .local_package_prefixes
+                .iter()
+                .chain(&self.package_name)
+                .any(|prefix| prefix.contains('.'))
+        {
+            return None;
+        }
+
+        let category = match self.category_cache.get(root) {
+            Some(&category) => category,
+            None => {
+                let category = self.resolve_category(&format!("import {root}"));
+                self.category_cache.insert(root.to_string(), category);
+                category
+            }
+        };
+        matches!(
+            category,
+            ImportCategory::StandardLibrary | ImportCategory::Local
+        )
+        .then_some(category)
+    }
+
+    /// Determine the category of an import statement without consulting the cache
+    fn resolve_category(&self, import_statement: &str) -> ImportCategory {
+        if is_future_import(import_statement) {
+            return ImportCategory::Future;
+        }
+
+        let package = extract_package(import_statement);
+
+        // Determine category with priority order:
+        // 0. User-supplied resolver (if set and it returns a category), then categorization rules
+        // 1. Overrides from `apply_overrides`
+        // 2. Local imports (relative or matching local prefixes)
+        // 3. Standard library (built-in or custom registered)
+        // 4. Third-party (custom registered or default)
+        // The future section is reserved for `__future__`, whatever the resolver says
+        let not_future = |category: &ImportCategory| *category != ImportCategory::Future;
+        let resolved = self
+            .resolver
+            .as_ref()
+            .and_then(|resolver| (resolver.0)(&package))
+            .filter(not_future)
+            .or_else(|| {
+                self.category_rules
+                    .iter()
+                    .find(|rule| rule.matcher.matches(&package))
+                    .map(|rule| rule.category)
+                    .filter(not_future)
+            })
+            .or_else(|| self.overridden_category(&package));
+        if let Some(category) = resolved {
+            category
+        } else if self.is_local_import(import_statement) {
+            ImportCategory::Local
+        } else if let Some(category) = self.registry.contains(&package) {
+            category
+        } else {
+            // Default to third-party for unknown packages
+            ImportCategory::ThirdParty
+        }
+    }
+
+    /// Check if this is a local/relative import
+    fn is_local_import(&self, import_statement: &str) -> bool {
+        // Check for relative imports
+        if import_statement.contains("from .")
+            || import_statement.contains("from ..")
+            || import_statement.contains("from ...")
+            || import_statement.contains("from ....")
+        {
+            return true;
+        }
+
+        let package = extract_package(import_statement);
+
+        if self.local_modules.contains(&package) {
+            return true;
+        }
+
+        // Check custom local package prefixes first
+        for prefix in &self.local_package_prefixes {
+            if matches_package_prefix(&package, prefix) {
+                return true;
+            }
+        }
+
+        // Fallback to package_name check for backwards compatibility
+        if let Some(pkg_name) = &self.package_name {
+            if matches_package_prefix(&package, pkg_name) {
+                return true;
+            }
+        }
+
+        // Glob patterns are the slowest check, so they come last
+        if self
+            .local_patterns
+            .iter()
+            .any(|pattern| matches_package_pattern(&package, pattern))
+        {
+            return true;
+        }
+
+        false
+    }
+
+    /// Format a list of imports, merging same-package imports where appropriate
+    fn format_imports(&self, imports: &[ImportStatement]) -> Vec<String> {
+        crate::utils::formatting::format_imports(imports, &self.formatting_config)
+    }
+
+    /// Sort formatted lines, keeping each multi-line statement together
+    fn sort_statement_lines(lines: Vec<String>, sort: SortMode) -> Vec<String> {
+        let mut statements: Vec<Vec<String>> = Vec::new();
+        let mut open = false;
+        let mut parenthesized = false;
+        for line in lines {
+            match statements.last_mut().filter(|_| open) {
+                Some(statement) => {
+                    open = if parenthesized {
+                        !line.ends_with(')')
+                    } else {
+                        line.ends_with('\\')
+                    };
+                    statement.push(line);
+                }
+                None => {
+                    parenthesized = line.contains('(') && !line.ends_with(')');
+                    open = parenthesized || line.ends_with('\\');
+                    statements.push(vec![line]);
+                }
+            }
+        }
+
+        statements.sort_by(|a, b| Self::sort_import_statements(&a[0], &b[0], sort));
+        statements.into_iter().flatten().collect()
+    }
+
+    fn sort_import_statements(a: &str, b: &str, sort: SortMode) -> std::cmp::Ordering {
+        let a_is_import = a.starts_with("import ");
+        let b_is_import = b.starts_with("import ");
+
+        match (a_is_import, b_is_import) {
+            // Both are 'import' or both are 'from' - sort by module name
+            (true, true) | (false, false) => sort.compare(a, b),
+            // a is 'import', b is 'from' - a comes first
+            (true, false) => std::cmp::Ordering::Less,
+            // a is 'from', b is 'import' - b comes first
+            (false, true) => std::cmp::Ordering::Greater,
+        }
+    }
+
+    /// Automatically add `TYPE_CHECKING` to typing import when type checking imports are used
+    fn ensure_type_checking_import_added(&mut self) {
+        // Check if we already have a typing import with TYPE_CHECKING
+        let has_type_checking = self.sections.standard_library_from.iter().any(|import| {
+            import.package == "typing" && import.items.contains(&"TYPE_CHECKING".to_string())
+        });
+
+        if !has_type_checking {
+            // Check if we have any typing import that we can modify
+            if let Some(typing_import) = self
+                .sections
+                .standard_library_from
+                .iter_mut()
+                .find(|import| import.package == "typing")
+            {
+                // Add TYPE_CHECKING to existing typing import
+                if !typing_import.items.contains(&"TYPE_CHECKING".to_string()) {
+                    typing_import.items.push("TYPE_CHECKING".to_string());
+                    typing_import
+                        .items
+                        .sort_by(|a, b| crate::utils::parsing::custom_import_sort(a, b));
+
+                    // Update the statement string
+                    if typing_import.items.len() == 1 {
+                        typing_import.statement =
+                            format!("from typing import {}", typing_import.items[0]);
+                    } else {
+                        typing_import.statement =
+                            format!("from typing import {}", typing_import.items.join(", "));
+                    }
+                }
+            } else {
+                // No typing import exists, add one with just TYPE_CHECKING
+                if let Some(mut import) = self.parse_import("from typing import TYPE_CHECKING") {
+                    import.auto_added = true;
+                    self.push_statement(import, false);
+                }
+            }
+        }
+    }
+
+    /// Assert that rendering is idempotent: ingesting the output of
+    /// [`render_file`](Self::render_file) into a helper with the same
+    /// configuration must render the identical string
+    #[cfg(test)]
+    pub(crate) fn assert_idempotent(&self) {
+        let first = self.render_file();
+        let mut reparsed = self.clone_config();
+        reparsed.ingest(&first);
+        let second = reparsed.render_file();
+        assert_eq!(first, second, "rendering is not idempotent");
+    }
+
+    /// Clone configuration without imports (useful for creating multiple helpers with same config)
+    ///
+    /// Unlike [`Clone::clone`], collected imports, preserved conditional blocks
+    /// and unparsed statements are not copied.
+    #[must_use]
+    pub fn clone_config(&self) -> Self {
+        Self {
+            sections: ImportSections::default(),
+            category_cache: self.category_cache.clone(),
+            package_name: self.package_name.clone(),
+            local_package_prefixes: self.local_package_prefixes.clone(),
+            local_modules: self.local_modules.clone(),
+            local_patterns: self.local_patterns.clone(),
+            registry: self.registry.clone(),
+            formatting_config: self.formatting_config.clone(),
+            auto_type_checking_import: self.auto_type_checking_import,
+            resolver: self.resolver.clone(),
+            category_rules: self.category_rules.clone(),
+            category_overrides: self.category_overrides.clone(),
+            conditional_blocks: Vec::new(),
+            fenced_blocks: Vec::new(),
+            raw_unparsed: Vec::new(),
+            conditional_imports: Vec::new(),
+            typing_backport: self.typing_backport.clone(),
+        }
+    }
+}
+
+/// Convenience functions for common import operations
+impl ImportHelper {
+    /// Create imports for a model file with required type imports
+    pub fn create_model_imports(&mut self, required_types: &[String]) {
+        // Standard model imports
+        self.add_import_string("from pydantic import BaseModel, ConfigDict, Field");
+
+        // Collect all typing imports needed across all types
+        let mut typing_imports = std::collections::HashSet::new();
+        let mut collections_abc_imports = std::collections::HashSet::new();
+        let mut datetime_imports = Vec::new();
+        let mut decimal_imports = Vec::new();
+
+        for type_name in required_types {
+            match type_name.as_str() {
+                "datetime" | "date" | "time" | "timedelta" => {
+                    if !datetime_imports.contains(&type_name.as_str()) {
+                        datetime_imports.push(type_name.as_str());
+                    }
+                }
+                "Decimal" => {
+                    if !decimal_imports.contains(&"Decimal") {
+                        decimal_imports.push("Decimal");
+                    }
+                }
+                "UUID" => {
+                    self.add_import_string("from uuid import UUID");
+                }
+                // For complex types, extract typing imports
+                _ => {
+                    // Check if this type contains typing elements
+                    let extracted_typing = Self::extract_typing_imports_from_type(type_name);
+                    typing_imports.extend(extracted_typing);
+
+                    // Check for collections.abc imports
+                    if type_name.contains("Callable") {
+                        collections_abc_imports.insert("Callable".to_string());
+                    }
+                }
+            }
+        }
+
+        // Add datetime imports if any were found
+        if !datetime_imports.is_empty() {
+            let import_statement = format!("from datetime import {}", datetime_imports.join(", "));
+            self.add_regular_import(&import_statement);
+        }
+
+        // Add decimal imports if any were found
+        if !decimal_imports.is_empty() {
+            self.add_import_string("from decimal import Decimal");
+        }
+
+        // Add typing imports if any were found (only Any, Generic, TypeVar, Protocol)
+        if !typing_imports.is_empty() {
+            let mut sorted_typing: Vec<String> = typing_imports.into_iter().collect();
+            sorted_typing.sort();
+            let import_statement = format!("from typing import {}", sorted_typing.join(", "));
+            self.add_regular_import(&import_statement);
+        }
+
+        // Add collections.abc imports if any were found (e.g., Callable)
+        if !collections_abc_imports.is_empty() {
+            let mut sorted_collections: Vec<String> = collections_abc_imports.into_iter().collect();
+            sorted_collections.sort();
+            let import_statement = format!(
+                "from collections.abc import {}",
+                sorted_collections.join(", ")
+            );
+            self.add_regular_import(&import_statement);
+        }
+    }
+
+    /// Create imports for a dataclass-based module
+    ///
+    /// Always adds `dataclass` from `dataclasses`, plus `field` and/or `fields`
+    /// when requested. Items are merged with any existing `dataclasses` import.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.create_dataclass_imports(true, false);
+    /// assert_eq!(helper.get_formatted(), vec!["from dataclasses import dataclass, field"]);
+    /// ```
+    pub fn create_dataclass_imports(&mut self, needs_field: bool, needs_fields_fn: bool) {
+        let mut items = vec!["dataclass"];
+        if needs_field {
+            items.push("field");
+        }
+        if needs_fields_fn {
+            items.push("fields");
+        }
+        self.add_from_import("dataclasses", &items);
+    }
+
+    /// Extract typing imports from a complex type string
+    /// This handles types like list[Any], dict[str, Any], etc.
+    /// Only imports what's actually needed for Python 3.13+ (Any, Generic, `TypeVar`, Protocol)
+    fn extract_typing_imports_from_type(type_str: &str) -> std::collections::HashSet<String> {
+        let mut typing_imports = std::collections::HashSet::new();
+
+        // Check for Any type (used in generics and standalone)
+        if type_str.contains("Any") {
+            typing_imports.insert("Any".to_string());
+        }
+
+        // Check for Generic type (used for generic classes)
+        if type_str.contains("Generic") {
+            typing_imports.insert("Generic".to_string());
+        }
+
+        // Check for TypeVar usage
+        if type_str.contains("TypeVar") {
+            typing_imports.insert("TypeVar".to_string());
+        }
+
+        // Check for Protocol type (structural subtyping)
+        if type_str.contains("Protocol") {
+            typing_imports.insert("Protocol".to_string());
+        }
+
+        typing_imports
+    }
+}
+
+/// Check whether a name is a lowercase Python identifier (module naming convention)
+fn is_lowercase_identifier(name: &str) -> bool {
+    is_valid_identifier(name)
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Get the names an import binds, each with the `package.name` it refers to
+fn bound_sources(import: &ImportStatement) -> Vec<(String, String)> {
+    if import.import_type == ImportType::Direct {
+        return import
+            .binding_names()
+            .into_iter()
+            .map(|name| (name.clone(), name))
+            .collect();
+    }
+
+    import
+        .items
+        .iter()
+        .filter(|item| item.as_str() != "*")
+        .map(|item| {
+            let (name, binding) = match item.split_once(" as ") {
+                Some((name, alias)) => (name.trim(), alias.trim()),
+                None => (item.trim(), item.trim()),
+            };
+            let source = if import.package.ends_with('.') {
+                format!("{}{name}", import.package)
+            } else {
+                format!("{}.{name}", import.package)
+            };
+            (binding.to_string(), source)
+        })
+        .collect()
+}
+
+/// Build an alias for `name` from its package path that no import binds yet
+fn unique_alias(package: &str, name: &str, bound: &HashMap<String, String>) -> String {
+    let prefix: Vec<&str> = package.split('.').filter(|part| !part.is_empty()).collect();
+    let base = if prefix.is_empty() {
+        format!("local_{name}")
+    } else {
+        format!("{}_{name}", prefix.join("_"))
+    };
+
+    let mut alias = base.clone();
+    let mut counter = 2;
+    while bound.contains_key(&alias) {
+        alias = format!("{base}_{counter}");
+        counter += 1;
+    }
+    alias
+}
+
+/// Collect the categories of every import in the given sections
+fn categories_in(sections: [&Vec<ImportStatement>; 7]) -> HashSet<ImportCategory> {
+    sections
+        .into_iter()
+        .flatten()
+        .map(|import| import.category)
+        .collect()
+}
+
+impl Default for ImportHelper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Full clone including every collected import
+///
+/// Use [`ImportHelper::clone_config`] to copy only the configuration.
+impl Clone for ImportHelper {
+    fn clone(&self) -> Self {
+        Self {
+            sections: self.sections.clone(),
+            conditional_blocks: self.conditional_blocks.clone(),
+            fenced_blocks: self.fenced_blocks.clone(),
+            raw_unparsed: self.raw_unparsed.clone(),
+            conditional_imports: self.conditional_imports.clone(),
+            ..self.clone_config()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_categorization() {
+        let mut helper = ImportHelper::new();
+
+        // Test future imports
+        helper.add_import_string("from __future__ import annotations");
+        assert_eq!(helper.sections.future.len(), 1);
+
+        // Test standard library from import
+        helper.add_import_string("from typing import Optional");
+        assert_eq!(helper.sections.standard_library_from.len(), 1);
+
+        // Test standard library direct import
+        helper.add_import_string("import uuid");
+        assert_eq!(helper.sections.standard_library_direct.len(), 1);
+
+        // Test third party
+        helper.add_import_string("from pydantic import BaseModel");
+        assert_eq!(helper.sections.third_party_from.len(), 1);
+
+        // Test local imports
+        helper.add_import_string("from .models import User");
+        assert_eq!(helper.sections.local_from.len(), 1);
+    }
+
+    #[test]
+    fn test_import_merging() {
+        let mut helper = ImportHelper::new();
+
+        helper.add_import_string("from typing import Optional");
+        helper.add_import_string("from typing import Any");
+        helper.add_import_string("from typing import List");
+
+        let imports = helper.get_formatted();
+
+        // Should merge into a single import
+        assert!(imports.iter().any(|i| i.contains("from typing import")));
+        assert!(imports
+            .iter()
+            .any(|i| i.contains("Any") && i.contains("Optional")));
+    }
+
+    #[test]
+    fn test_alphabetical_sorting_of_import_items() {
+        let mut helper = ImportHelper::new();
+
+        // Test with unsorted items in a from import
+        helper.add_import_string("from typing import Optional, Any, List, Dict");
+        helper.add_import_string("from collections import defaultdict, OrderedDict, Counter");
+        helper.add_import_string("import uuid");
+        helper.add_import_string("import os");
+
+        let imports = helper.get_formatted();
+
+        // Print for debugging
+        for import in &imports {
+            println!("{}", import);
+        }
+
+        // Check that direct imports come before from imports
+        let import_str = imports.join("\n");
+        let os_pos = import_str.find("import os").unwrap();
+        let uuid_pos = import_str.find("import uuid").unwrap();
+        let typing_pos = import_str.find("from typing import").unwrap();
+
+        // Direct imports should come before from imports
+        assert!(
+            os_pos < typing_pos,
+            "Direct imports should come before from imports"
+        );
+        assert!(
+            uuid_pos < typing_pos,
+            "Direct imports should come before from imports"
+        );
+
+        // Check that items within from imports are sorted alphabetically
+        let typing_section = &import_str[import_str.find("from typing").unwrap()..];
+
+        // Verify alphabetical order in typing import (case-insensitive, but considering both single and multi-line)
+        assert!(
+            typing_section.contains("Any")
+                && typing_section.contains("Dict")
+                && typing_section.contains("List")
+                && typing_section.contains("Optional"),
+            "Import should contain all items: Any, Dict, List, Optional in alphabetical order"
+        );
+    }
+
+    #[test]
+    fn test_direct_imports_sorted_alphabetically() {
+        let mut helper = ImportHelper::new();
+
+        helper.add_direct_import("uuid");
+        helper.add_direct_import("os");
+        helper.add_direct_import("sys");
+        helper.add_direct_import("json");
+
+        let imports = helper.get_formatted();
+
+        // Should be sorted: json, os, sys, uuid
+        let import_lines: Vec<String> = imports
+            .iter()
+            .filter(|s| !s.is_empty() && s.contains("import"))
+            .cloned()
+            .collect();
+
+        // Verify all 4 imports are present
+        assert_eq!(
+            import_lines.len(),
+            4,
+            "Should have 4 direct imports, got: {:?}",
+            import_lines
+        );
+
+        // Verify they are sorted alphabetically
+        assert!(
+            import_lines[0].contains("json"),
+            "First should be json, got: {}",
+            import_lines[0]
+        );
+        assert!(
+            import_lines[1].contains("os"),
+            "Second should be os, got: {}",
+            import_lines[1]
+        );
+        assert!(
+            import_lines[2].contains("sys"),
+            "Third should be sys, got: {}",
+            import_lines[2]
+        );
+        assert!(
+            import_lines[3].contains("uuid"),
+            "Fourth should be uuid, got: {}",
+            import_lines[3]
+        );
+    }
+
+    #[test]
+    fn test_uppercase_priority_in_import_sorting() {
+        let mut helper = ImportHelper::new();
+
+        // Test with mixed case items - uppercase should come before lowercase for same letter
+        helper.add_import_string("from example import Ab, AA, Aa, AB");
+
+        let imports = helper.get_formatted();
+
+        // Print for debugging
+        for import in &imports {
+            println!("{}", import);
+        }
+
+        let import_str = imports.join("\n");
+        let example_section = &import_str[import_str.find("from example").unwrap()..];
+
+        // Verify all items are present (order doesn't matter as much as presence for multiline)
+        assert!(example_section.contains("AA"), "Should contain AA");
+        assert!(example_section.contains("AB"), "Should contain AB");
+        assert!(example_section.contains("Aa"), "Should contain Aa");
+        assert!(example_section.contains("Ab"), "Should contain Ab");
+    }
+
+    #[test]
+    fn test_comprehensive_case_sorting() {
+        let mut helper = ImportHelper::new();
+
+        // Test with multiple letters and mixed cases
+        helper.add_import_string("from test import zz, ZZ, bb, BB, aa, AA, cc, CC");
+
+        let imports = helper.get_formatted();
+
+        let import_str = imports.join("\n");
+        let test_section = &import_str[import_str.find("from test").unwrap()..];
+
+        // Verify all items are present in the correct import section
+        assert!(test_section.contains("AA"), "Should contain AA");
+        assert!(test_section.contains("BB"), "Should contain BB");
+        assert!(test_section.contains("CC"), "Should contain CC");
+        assert!(test_section.contains("ZZ"), "Should contain ZZ");
+        assert!(test_section.contains("aa"), "Should contain aa");
+        assert!(test_section.contains("bb"), "Should contain bb");
+        assert!(test_section.contains("cc"), "Should contain cc");
+        assert!(test_section.contains("zz"), "Should contain zz");
+    }
+
+    #[test]
+    fn test_type_checking_imports() {
+        let mut helper = ImportHelper::with_package_name("mypackage".to_string());
+
+        // Add regular imports
+        helper.add_import_string("from __future__ import annotations");
+        helper.add_import_string("from typing import Any");
+        helper.add_import_string("from pydantic import BaseModel");
+
+        // Add TYPE_CHECKING imports
+        helper.add_type_checking_import("import httpx");
+        helper.add_type_checking_import("from typing import TYPE_CHECKING");
+        helper.add_type_checking_import("from collections.abc import Callable");
+        helper.add_type_checking_import("from mypackage.models import User");
+
+        // Check that regular imports are counted correctly
+        assert_eq!(helper.count(), 3);
+        assert_eq!(helper.count_type_checking(), 4);
+        assert!(!helper.is_type_checking_empty());
+
+        // Generate TYPE_CHECKING imports
+        let (future, stdlib, third_party, local) = helper.get_type_checking_categorized();
+
+        // Verify categorization
+        assert!(
+            third_party.iter().any(|s| s.contains("import httpx")),
+            "Should have httpx in third_party"
+        );
+        assert!(
+            stdlib
+                .iter()
+                .any(|s| s.contains("from typing import TYPE_CHECKING")),
+            "Should have TYPE_CHECKING"
+        );
+        assert!(
+            stdlib
+                .iter()
+                .any(|s| s.contains("from collections.abc import Callable")),
+            "Should have Callable"
+        );
+        assert!(
+            local
+                .iter()
+                .any(|s| s.contains("from mypackage.models import User")),
+            "Should have User in local"
+        );
+        assert!(
+            future.is_empty(),
+            "Should have no future imports in TYPE_CHECKING"
+        );
+    }
+
+    #[test]
+    fn test_programmatic_import_builders() {
+        let mut helper = ImportHelper::new();
+
+        // Test add_from_import
+        helper.add_from_import("typing", &["Any", "Optional"]);
+        helper.add_from_import("json", &["loads"]);
+
+        // Test add_direct_import
+        helper.add_direct_import("sys");
+
+        // Test TYPE_CHECKING builders
+        helper.add_type_checking_from_import("httpx", &["Client", "Response"]);
+        helper.add_type_checking_direct_import("logging");
+
+        // Verify regular imports
+        let (_, stdlib, _, _) = helper.get_categorized();
+        assert!(
+            stdlib
+                .iter()
+                .any(|s| s.contains("Any") && s.contains("Optional")),
+            "Should have Any and Optional in typing imports"
+        );
+        assert!(stdlib.iter().any(|s| s.contains("from json import loads")));
+        assert!(stdlib.iter().any(|s| s.contains("import sys")));
+
+        // Verify TYPE_CHECKING imports
+        let (_, tc_stdlib, tc_third_party, _) = helper.get_type_checking_categorized();
+        assert!(tc_third_party
+            .iter()
+            .any(|s| s.contains("from httpx import Client, Response")));
+        assert!(tc_stdlib.iter().any(|s| s.contains("import logging")));
+    }
+
+    #[test]
+    fn test_type_checking_four_categories() {
+        let mut helper = ImportHelper::with_package_name("myapp".to_string());
+
+        // Add imports in all four categories for TYPE_CHECKING
+        helper.add_type_checking_import("from __future__ import annotations");
+        helper.add_type_checking_import("from typing import Protocol");
+        helper.add_type_checking_import("from httpx import Client");
+        helper.add_type_checking_import("from myapp.models import User");
+
+        let (future, stdlib, third_party, local) = helper.get_type_checking_categorized();
+
+        // Verify all four categories
+        assert_eq!(future.len(), 1, "Should have 1 future import");
+        assert_eq!(stdlib.len(), 1, "Should have 1 stdlib import");
+        assert_eq!(third_party.len(), 1, "Should have 1 third-party import");
+        assert_eq!(local.len(), 1, "Should have 1 local import");
+
+        assert!(future[0].contains("from __future__ import annotations"));
+        assert!(stdlib[0].contains("from typing import Protocol"));
+        assert!(third_party[0].contains("from httpx import Client"));
+        assert!(local[0].contains("from myapp.models import User"));
+    }
+
+    #[test]
+    fn test_clear_preserves_configuration() {
+        let mut helper = ImportHelper::with_package_name("mypackage".to_string());
+        let mut config = FormattingConfig::black_compatible();
+        config.force_multiline = true;
+        helper.set_formatting_config(config.clone());
+        helper.add_local_package_prefix("other_package");
+
+        // Add imports
+        helper.add_import_string("from typing import Any");
+        helper.add_type_checking_import("from httpx import Client");
+        assert_eq!(helper.count(), 1);
+        assert_eq!(helper.count_type_checking(), 1);
+
+        // Clear imports
+        helper.clear();
+
+        // Verify imports are cleared
+        assert!(helper.is_empty());
+        assert!(helper.is_type_checking_empty());
+        assert_eq!(helper.count(), 0);
+        assert_eq!(helper.count_type_checking(), 0);
+
+        // Verify configuration is preserved
+        assert_eq!(helper.package_name.as_deref(), Some("mypackage"));
+        assert!(helper.local_package_prefixes.contains("mypackage"));
+        assert!(helper.local_package_prefixes.contains("other_package"));
+        assert_eq!(helper.formatting_config().line_length, 88);
+        assert!(helper.formatting_config().force_multiline);
+    }
+
+    #[test]
+    fn test_reset_clears_everything() {
+        let mut helper = ImportHelper::with_package_name("mypackage".to_string());
+        let mut config = FormattingConfig::black_compatible();
+        config.force_multiline = true;
+        helper.set_formatting_config(config);
+        helper.add_local_package_prefix("other_package");
+
+        // Add imports
+        helper.add_import_string("from typing import Any");
+        helper.add_type_checking_import("from httpx import Client");
+        assert_eq!(helper.count(), 1);
+        assert_eq!(helper.count_type_checking(), 1);
+
+        // Reset to default
+        helper.reset();
+
+        // Verify everything is reset
+        assert!(helper.is_empty());
+        assert!(helper.is_type_checking_empty());
+        assert_eq!(helper.count(), 0);
+        assert_eq!(helper.count_type_checking(), 0);
+
+        // Verify configuration is reset to default
+        assert_eq!(helper.package_name, None);
+        assert!(helper.local_package_prefixes.is_empty());
+        assert_eq!(helper.formatting_config().line_length, 79); // Default PEP8
+        assert!(!helper.formatting_config().force_multiline);
+    }
+
+    #[test]
+    fn test_clear_and_reuse() {
+        let mut helper = ImportHelper::with_package_name("mypackage".to_string());
+
+        // First use
+        helper.add_import_string("from typing import Any");
+        helper.add_import_string("from pydantic import BaseModel");
+        assert_eq!(helper.count(), 2);
+
+        // Clear for reuse with same configuration
+        helper.clear();
+        assert!(helper.is_empty());
+
+        // Second use with same configuration
+        helper.add_import_string("import json");
+        helper.add_import_string("import sys");
+        let (_, stdlib, _, _) = helper.get_categorized();
+        assert_eq!(stdlib.len(), 2);
+        assert!(stdlib.iter().any(|s| s.contains("import json")));
+        assert!(stdlib.iter().any(|s| s.contains("import sys")));
+    }
+
+    #[test]
+    fn test_add_import_spec_checked_empty_package() {
+        let mut helper = ImportHelper::new();
+        let result = helper.add_import_spec_checked(&ImportSpec::direct(""));
+        assert_eq!(result, Err(ImportParseError::EmptyPackage));
+        assert!(helper.is_empty());
+    }
+
+    #[test]
+    fn test_add_import_spec_checked_invalid_item() {
+        let mut helper = ImportHelper::new();
+        let result =
+            helper.add_import_spec_checked(&ImportSpec::from("typing", vec!["Any", "Bad Item"]));
+        assert_eq!(
+            result,
+            Err(ImportParseError::InvalidItem("Bad Item".to_string()))
+        );
+        assert!(helper.is_empty());
+    }
+
+    #[test]
+    fn test_add_import_spec_checked_valid() {
+        let mut helper = ImportHelper::new();
+        helper
+            .add_import_spec_checked(&ImportSpec::from("collections.abc", vec!["Mapping"]))
+            .unwrap();
+        helper
+            .add_import_spec_checked(&ImportSpec::from(".models", vec!["User"]))
+            .unwrap();
+
+        let (_, stdlib, _, local) = helper.get_categorized();
+        assert_eq!(stdlib, vec!["from collections.abc import Mapping"]);
+        assert_eq!(local, vec!["from .models import User"]);
+    }
+
+    #[test]
+    fn test_blank_line_after_future_toggle() {
+        let mut helper = ImportHelper::new();
+        helper.add_import_string("from __future__ import annotations");
+        helper.add_import_string("from os import path");
+
+        assert_eq!(
+            helper.get_formatted(),
+            vec![
+                "from __future__ import annotations",
+                "",
+                "from os import path"
+            ]
+        );
+
+        let config = FormattingConfig {
+            blank_line_after_future: false,
+            ..FormattingConfig::default()
+        };
+        helper.set_formatting_config(config);
+        assert_eq!(
+            helper.get_formatted(),
+            vec!["from __future__ import annotations", "from os import path"]
+        );
+    }
+
+    #[test]
+    fn test_create_dataclass_imports_merges() {
+        let mut helper = ImportHelper::new();
+        helper.add_from_import("dataclasses", &["asdict"]);
+        helper.create_dataclass_imports(true, false);
+
+        let imports = helper.get_formatted();
+        assert_eq!(
+            imports,
+            vec!["from dataclasses import asdict, dataclass, field"]
+        );
+    }
+
+    #[test]
+    fn test_disable_auto_type_checking_import() {
+        let mut helper = ImportHelper::new();
+        helper.set_auto_type_checking_import(false);
+        helper.add_import_string("from typing import Any");
+        helper.add_type_checking_import("from httpx import Client");
+
+        let (_, stdlib, _, _) = helper.get_categorized();
+        assert_eq!(stdlib, vec!["from typing import Any"]);
+        assert_eq!(helper.count_type_checking(), 1);
+    }
+
+    #[test]
+    fn test_render_idempotent_web_app() {
+        let mut helper = ImportHelper::with_package_name("myapi".to_string());
+        helper.add_import_string("from __future__ import annotations");
+        helper.add_from_import("typing", &["Any", "Optional"]);
+        helper.add_direct_import("json");
+        helper.add_direct_import("os");
+        helper.add_from_import("fastapi", &["FastAPI", "Depends", "HTTPException"]);
+        helper.add_from_import(
+            "pydantic",
+            &["BaseModel", "Field", "validator", "ConfigDict"],
+        );
+        helper.add_from_import("myapi.models", &["User", "Post"]);
+        helper.add_import_string("from .database import get_db");
+
+        helper.assert_idempotent();
+    }
+
+    #[test]
+    fn test_render_idempotent_with_type_checking() {
+        let mut helper = ImportHelper::with_package_name("myapp".to_string());
+        helper.add_from_import("pydantic", &["BaseModel"]);
+        helper.add_type_checking_from_import("collections.abc", &["Callable", "Iterator"]);
+        helper.add_type_checking_from_import(
+            "myapp.models",
+            &["User", "Post", "Comment", "Tag", "Category"],
+        );
+        helper.add_type_checking_direct_import("httpx");
+
+        let rendered = helper.render_file();
+        assert_eq!(rendered.matches("TYPE_CHECKING").count(), 2);
+        helper.assert_idempotent();
+    }
+
+    #[test]
+    fn test_render_idempotent_aliases_and_multiline() {
+        let mut helper = ImportHelper::new();
+        helper.add_import_string("import numpy as np");
+        helper.add_import_string("import pandas as pd");
+        helper.add_import_string("from datetime import datetime as dt, timedelta");
+        helper.add_from_import_multiline("os.path", &["join", "exists", "dirname", "basename"]);
+
+        let rendered = helper.render_file();
+        assert!(rendered.contains("import numpy as np\n"));
+        assert!(rendered.contains("from datetime import datetime as dt, timedelta\n"));
+        helper.assert_idempotent();
+    }
+
+    #[test]
+    fn test_render_idempotent_without_future_blank_line() {
+        let config = FormattingConfig {
+            blank_line_after_future: false,
+            ..FormattingConfig::black_compatible()
+        };
+        let mut helper = ImportHelper::with_formatting_config(config);
+        helper.add_import_string("from __future__ import annotations");
+        helper.add_import_string("import sys");
+        helper.add_type_checking_import("from typing import Protocol");
+
+        helper.assert_idempotent();
+    }
+
+    #[test]
+    fn test_resolver_forces_local() {
+        let mut helper = ImportHelper::new();
+        helper.set_resolver(|package| {
+            if package == "mypkg" || package.starts_with("mypkg.") {
+                Some(ImportCategory::Local)
+            } else {
+                None
+            }
+        });
+
+        helper.add_import_string("from mypkg.models import User");
+        helper.add_import_string("from typing import Any");
+        helper.add_import_string("import requests");
+
+        let (_, stdlib, third_party, local) = helper.get_categorized();
+        assert_eq!(local, vec!["from mypkg.models import User"]);
+        assert_eq!(stdlib, vec!["from typing import Any"]);
+        assert_eq!(third_party, vec!["import requests"]);
+    }
+
+    #[test]
+    fn test_get_formatted_category_stdlib_only() {
+        let mut helper = ImportHelper::with_package_name("myapp".to_string());
+        helper.add_import_string("from __future__ import annotations");
+        helper.add_import_string("from typing import Any");
+        helper.add_import_string("import os");
+        helper.add_import_string("from pydantic import BaseModel");
+        helper.add_import_string("from myapp.models import User");
+        helper.add_type_checking_import("from collections.abc import Callable");
+
+        assert_eq!(
+            helper.get_formatted_category(ImportCategory::StandardLibrary),
+            vec!["import os", "from typing import TYPE_CHECKING, Any"]
+        );
+        assert_eq!(
+            helper.get_type_checking_formatted_category(ImportCategory::StandardLibrary),
+            vec!["from collections.abc import Callable"]
+        );
+        assert!(helper
+            .get_type_checking_formatted_category(ImportCategory::Local)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_debug_dump_truncates_items() {
+        let mut helper = ImportHelper::new();
+        let items: Vec<String> = (0..20).map(|i| format!("item{i:02}")).collect();
+        let item_refs: Vec<&str> = items.iter().map(String::as_str).collect();
+        helper.add_from_import("mymodule", &item_refs);
+
+        let dump = helper.debug_dump(Some(5));
+        assert!(dump.contains("third_party_from:"));
+        assert!(dump.contains("item00, item01, item02, item03, item04, …(+15 more)"));
+        assert!(!dump.contains("item05"));
+
+        let full = helper.debug_dump(None);
+        assert!(full.contains("item19"));
+        assert!(!full.contains("more)"));
+    }
+
+    #[test]
+    fn test_direct_star_import_rejected() {
+        let mut helper = ImportHelper::new();
+        helper.add_import_string("import *");
+        helper.add_type_checking_import("import *");
+        assert!(helper.is_empty());
+        assert!(helper.is_type_checking_empty());
+        assert!(helper.get_formatted().is_empty());
+
+        let result = helper.add_import_spec_checked(&ImportSpec::direct("*"));
+        assert_eq!(
+            result,
+            Err(ImportParseError::InvalidPackage("*".to_string()))
+        );
+        assert!(helper.is_empty());
+    }
+
+    #[test]
+    fn test_ingest_version_gated_block_round_trip() {
+        let source = "\
+import os
+import sys
+if sys.version_info >= (3, 11):
+    import tomllib
+else:
+    import tomli as tomllib
+
+import requests
+";
+        let mut helper = ImportHelper::new();
+        assert_eq!(helper.ingest(source), 4);
+        assert_eq!(helper.render_file(), source);
+
+        // No contradictory tomllib imports leak into the regular sections
+        let (_, stdlib, third_party, _) = helper.get_categorized();
+        assert!(!stdlib.iter().any(|s| s == "import tomllib"));
+        assert_eq!(third_party, vec!["import requests"]);
+        helper.assert_idempotent();
+    }
+
+    #[test]
+    fn test_render_file_ingest_round_trip() {
+        let source = "import os\n\nif sys.version_info >= (3, 11):\n    import tomllib\n";
+        let mut helper = ImportHelper::new();
+        helper.ingest(source);
+        // The blank line before the version-gated block is not kept
+        let rendered = helper.render_file();
+        assert_eq!(
+            rendered,
+            "import os\nif sys.version_info >= (3, 11):\n    import tomllib\n"
+        );
+
+        let mut restored = ImportHelper::new();
+        restored.ingest(&rendered);
+        assert_eq!(restored.render_file(), rendered);
+    }
+
+    #[test]
+    fn test_stats_for_mixed_set() {
+        let mut helper = ImportHelper::with_package_name("myapp".to_string());
+        helper.add_import_string("from __future__ import annotations");
+        helper.add_import_string("import os");
+        helper.add_import_string("import numpy as np");
+        helper.add_import_string("from typing import Any");
+        helper.add_import_string("from typing import Optional");
+        helper.add_import_string("from pydantic import BaseModel");
+        helper.add_import_string("from myapp.models import User");
+        helper.add_import_string("from myapp.models import Post");
+        helper.add_type_checking_import("from httpx import Client");
+
+        let stats = helper.stats();
+        assert_eq!(
+            stats,
+            ImportStats {
+                direct: 2,
+                from_: 5,
+                future: 1,
+                type_checking: 1,
+                merged_packages: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn test_unparsed_import_survives_to_output() {
+        let source = "import json\nimport os, sys\nfrom pydantic import BaseModel\n";
+        let mut helper = ImportHelper::new();
+        helper.ingest(source);
+
+        assert_eq!(helper.raw_unparsed(), ["import os, sys"]);
+        assert_eq!(
+            helper.render_file(),
+            "import json\nimport os, sys\n\nfrom pydantic import BaseModel\n"
+        );
+        helper.assert_idempotent();
+    }
+
+    #[test]
+    fn test_with_capacity_preallocates() {
+        let mut helper = ImportHelper::with_capacity(128);
+        assert!(helper.sections.standard_library_from.capacity() >= 128);
+        assert!(helper.category_cache.capacity() >= 128);
+
+        helper.add_import_string("from typing import Any");
+        assert_eq!(helper.count(), 1);
+    }
+
+    #[test]
+    fn test_enforce_relative_within_flags_absolute_imports() {
+        let mut helper = ImportHelper::with_package_name("myapp".to_string());
+        helper.add_import_string("from myapp.models import X");
+        helper.add_import_string("from myapp_extra.models import Y");
+        helper.add_import_string("from ..models import Z");
+        helper.add_type_checking_import("import myapp.schemas");
+        helper.add_import_string("from typing import Any");
+
+        assert_eq!(
+            helper.enforce_relative_within("myapp"),
+            vec!["from myapp.models import X", "import myapp.schemas"]
+        );
+        assert!(helper.enforce_relative_within("other").is_empty());
+    }
+
+    #[test]
+    fn test_render_as_black_vs_pep8_line_length() {
+        let mut helper = ImportHelper::new();
+        // 83 characters on a single line: fits Black's 88, exceeds PEP 8's 79
+        helper.add_from_import(
+            "mypackage.submodule",
+            &["FirstLongClassName", "SecondLongClassName", "ThirdClass"],
+        );
+
+        let black = helper.render_as(Style::Black);
+        let pep8 = helper.render_as(Style::Pep8);
+
+        assert_eq!(black.lines().count(), 1);
+        assert!(black.lines().all(|line| line.len() <= 88));
+        assert!(black.lines().any(|line| line.len() > 79));
+
+        assert!(pep8.lines().count() > 1);
+        assert!(pep8.lines().all(|line| line.len() <= 79));
+
+        assert_eq!(helper.render_as(Style::Ruff), black);
+        assert_eq!(helper.render_as(Style::Isort), pep8);
+    }
+
+    #[test]
+    fn test_dotted_local_prefix_matches_segment_boundary() {
+        let mut helper = ImportHelper::new();
+        helper.add_local_package_prefix("services.auth");
+
+        helper.add_import_string("from services.auth.handlers import X");
+        helper.add_import_string("from services.auth import Y");
+        helper.add_import_string("from services.authz import Z");
+
+        let (_, _, third_party, local) = helper.get_categorized();
+        assert_eq!(
+            local,
+            vec![
+                "from services.auth import Y",
+                "from services.auth.handlers import X"
+            ]
+        );
+        assert_eq!(third_party, vec!["from services.authz import Z"]);
+    }
+
+    #[test]
+    fn test_local_pattern_glob() {
+        let mut helper = ImportHelper::new();
+        helper.add_local_pattern("company_*");
+        helper.add_local_pattern("*.internal");
+
+        helper.add_import_string("from company_auth import login");
+        helper.add_import_string("from companyx import thing");
+        helper.add_import_string("from billing.internal.api import charge");
+
+        let (_, _, third_party, local) = helper.get_categorized();
+        assert_eq!(
+            local,
+            vec![
+                "from billing.internal.api import charge",
+                "from company_auth import login"
+            ]
+        );
+        assert_eq!(third_party, vec!["from companyx import thing"]);
+    }
+
+    #[test]
+    fn test_missing_against_existing_source() {
+        let mut helper = ImportHelper::new();
+        helper.add_import_string("import json");
+        helper.add_import_string("from pathlib import Path");
+        helper.add_import_string("from pydantic import BaseModel");
+
+        let existing = "import json\n\nprint(json.dumps({}))\n";
+        assert_eq!(
+            helper.missing_against(existing),
+            vec!["from pathlib import Path", "from pydantic import BaseModel"]
+        );
+        assert!(helper.missing_against(&helper.render_file()).is_empty());
+    }
+
+    #[test]
+    fn test_preserve_item_order_for_single_package() {
+        let mut config = FormattingConfig::default();
+        config
+            .preserve_item_order_packages
+            .insert("pipeline".to_string());
+        let mut helper = ImportHelper::with_formatting_config(config);
+
+        helper.add_from_import("pipeline", &["load", "transform"]);
+        helper.add_from_import("pipeline", &["save"]);
+        helper.add_from_import("typing", &["Optional", "Any"]);
+
+        let formatted = helper.get_formatted();
+        assert!(formatted.contains(&"from pipeline import load, transform, save".to_string()));
+        assert!(formatted.contains(&"from typing import Any, Optional".to_string()));
+    }
+
+    #[test]
+    fn test_package_items_sorted_after_merge() {
+        let mut helper = ImportHelper::new();
+        helper.add_from_import("typing", &["Optional"]);
+        helper.add_from_import("typing", &["List", "Any"]);
+        helper.add_type_checking_from_import("typing", &["Protocol"]);
+
+        assert_eq!(
+            helper.package_items("typing"),
+            vec!["TYPE_CHECKING", "Any", "List", "Optional"]
+        );
+        assert_eq!(
+            helper.type_checking_package_items("typing"),
+            vec!["Protocol"]
+        );
+        assert!(helper.package_items("pydantic").is_empty());
+    }
+
+    #[test]
+    fn test_third_party_packages_use_distribution_names() {
+        let mut helper = ImportHelper::new();
+        helper
+            .registry_mut()
+            .add_third_party_packages(&["cv2", "PIL"]);
+        helper.add_import_string("import cv2");
+        helper.add_import_string("from PIL import Image");
+        helper.add_import_string("import os");
+        helper.add_type_checking_import("from httpx import Client");
+
+        assert_eq!(
+            helper.third_party_packages(),
+            vec!["Pillow", "httpx", "opencv-python"]
+        );
+    }
+
+    #[test]
+    fn test_semicolon_joined_imports() {
+        let mut helper = ImportHelper::new();
+        helper.add_import_string("import os; import sys; x = 1");
+        assert_eq!(helper.get_formatted(), vec!["import os", "import sys"]);
+
+        let mut ingested = ImportHelper::new();
+        assert_eq!(ingested.ingest("import os; import sys; x = 1\n"), 2);
+        assert_eq!(ingested.get_formatted(), vec!["import os", "import sys"]);
+    }
+
+    #[test]
+    fn test_clone_includes_imports() {
+        let mut helper = ImportHelper::with_package_name("myapp".to_string());
+        helper.add_import_string("import os");
+        helper.add_import_string("from myapp.models import User");
+
+        let mut cloned = helper.clone();
+        assert_eq!(cloned.get_formatted(), helper.get_formatted());
+        assert!(helper.clone_config().is_empty());
+
+        cloned.add_import_string("import sys");
+        assert_eq!(helper.count(), 2);
+        assert_eq!(cloned.count(), 3);
+    }
+
+    #[test]
+    fn test_suggest_type_checking_is_advisory() {
+        let mut helper = ImportHelper::new();
+        helper.add_from_import("typing", &["Any", "cast"]);
+        helper.add_from_import("pydantic", &["BaseModel", "Field as PydanticField"]);
+
+        let type_only = HashSet::from(["Any", "PydanticField"]);
+        assert_eq!(
+            helper.suggest_type_checking(&type_only),
+            vec![
+                ("typing".to_string(), vec!["Any".to_string()]),
+                (
+                    "pydantic".to_string(),
+                    vec!["Field as PydanticField".to_string()]
+                ),
+            ]
+        );
+        assert!(helper.is_type_checking_empty());
+    }
+
+    #[test]
+    fn test_empty_group_placeholder() {
+        let config = FormattingConfig {
+            empty_group_placeholder: Some("# (no imports)".to_string()),
+            ..FormattingConfig::default()
+        };
+        let mut helper = ImportHelper::with_formatting_config(config);
+        helper.add_import_string("import os");
+        helper.add_import_string("import pydantic");
+
+        assert_eq!(
+            helper.get_formatted(),
+            vec!["import os", "", "import pydantic", "", "# (no imports)"]
+        );
+
+        // Default config emits nothing for empty groups
+        let mut plain = ImportHelper::new();
+        plain.add_import_string("import os");
+        assert_eq!(plain.get_formatted(), vec!["import os"]);
+    }
+
+    #[test]
+    fn test_retain_stdlib_only() {
+        let mut helper = ImportHelper::with_package_name("myapp".to_string());
+        helper.add_import_string("import os");
+        helper.add_import_string("from typing import Any");
+        helper.add_import_string("from pydantic import BaseModel");
+        helper.add_import_string("from myapp.models import User");
+        helper.add_import_string("from typing import Optional");
+
+        helper.retain(|import| import.category == ImportCategory::StandardLibrary);
+        assert_eq!(
+            helper.get_formatted(),
+            vec!["import os", "from typing import Any, Optional"]
+        );
+        assert_eq!(helper.count(), 3);
+    }
+
+    #[test]
+    fn test_ingest_parenthesized_import_with_item_comments() {
+        let mut helper = ImportHelper::new();
+        helper.ingest("from typing import (\n    Any,  # common\n    Optional,\n)\n");
+
+        assert_eq!(helper.package_items("typing"), vec!["Any", "Optional"]);
+        assert!(helper.raw_unparsed().is_empty());
+    }
+
+    #[test]
+    fn test_stdlib_submodule_imports() {
+        let mut helper = ImportHelper::new();
+        helper.add_import_string("import urllib.request");
+        helper.add_import_string("from xml.dom.minidom import parseString");
+        helper.add_import_string("import requests");
+
+        assert_eq!(
+            helper.get_formatted(),
+            vec![
+                "import urllib.request",
+                "from xml.dom.minidom import parseString",
+                "",
+                "import requests",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_try_add_import_string_reports_span() {
+        let mut helper = ImportHelper::new();
+        assert!(helper.try_add_import_string("import os").is_ok());
+
+        let error = helper
+            .try_add_import_string("from typing Any, Optional")
+            .unwrap_err();
+        assert_eq!(error.span(), Some(25..25));
+        assert_eq!(error.to_string(), "expected `import` keyword at 25..25");
+
+        let error = helper
+            .try_add_import_string("from pydantic import Base-Model")
+            .unwrap_err();
+        assert_eq!(error.span(), Some(21..31));
+        assert_eq!(helper.count(), 1);
+    }
+
+    #[test]
+    fn test_render_conditional_import_block() {
+        let mut helper = ImportHelper::new();
+        helper.add_import_string("import json");
+        helper
+            .add_conditional_import("settings.USE_X", &ImportSpec::direct("x"))
+            .add_conditional_import("settings.USE_X", &ImportSpec::from("x.ext", vec!["Plugin"]));
+
+        assert_eq!(
+            helper.render_file(),
+            "import json\n\nif settings.USE_X:\n    import x\n    from x.ext import Plugin\n"
+        );
+        assert_eq!(helper.get_formatted(), vec!["import json"]);
+    }
+
+    #[test]
+    fn test_manifest_round_trip() {
+        let mut helper = ImportHelper::with_package_name("myapp".to_string());
+        helper.add_import_string("import os");
+        helper.add_import_string("from typing import Any");
+        helper.add_import_string("from myapp.models import User");
+        helper.add_type_checking_import("from pydantic import BaseModel");
+
+        let manifest = helper.to_manifest();
+        let restored = ImportHelper::from_manifest(&manifest);
+
+        assert_eq!(restored.get_formatted(), helper.get_formatted());
+        assert_eq!(
+            restored.get_type_checking_formatted(),
+            helper.get_type_checking_formatted()
+        );
+        assert_eq!(restored.to_manifest(), manifest);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_manifest_json_round_trip() {
+        let mut helper = ImportHelper::new();
+        helper.add_import_string("import numpy as np");
+        helper.add_import_string("from typing import Any, Optional");
+
+        let manifest = helper.to_manifest();
+        let json = serde_json::to_string(&manifest).unwrap();
+        let parsed: ImportManifest = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed, manifest);
+        assert_eq!(
+            ImportHelper::from_manifest(&parsed).get_formatted(),
+            helper.get_formatted()
+        );
+    }
+
+    #[test]
+    fn test_alpha_subheaders_within_group() {
+        let config = FormattingConfig {
+            alpha_subheaders: true,
+            ..FormattingConfig::default()
+        };
+        let mut helper = ImportHelper::with_formatting_config(config);
+        helper.add_import_string("import os");
+        helper.add_import_string("from pydantic import BaseModel");
+        helper.add_import_string("from fastapi import FastAPI");
+        helper.add_import_string("from flask import Flask");
+        helper.add_import_string("from httpx import Client");
+
+        assert_eq!(
+            helper.get_formatted(),
+            vec![
+                "# O",
+                "import os",
+                "",
+                "# F",
+                "from fastapi import FastAPI",
+                "from flask import Flask",
+                "# H",
+                "from httpx import Client",
+                "# P",
+                "from pydantic import BaseModel",
+            ]
+        );
+        assert!(!ImportHelper::new().formatting_config().alpha_subheaders);
+    }
+
+    #[test]
+    fn test_alpha_subheaders_once_per_letter() {
+        let config = FormattingConfig {
+            alpha_subheaders: true,
+            ..FormattingConfig::default()
+        };
+        let mut helper = ImportHelper::with_formatting_config(config);
+        helper.add_import_string("import os");
+        helper.add_import_string("import sys");
+        helper.add_import_string("from os import path");
+        helper.add_import_string("from shutil import copy");
+
+        assert_eq!(
+            helper.get_formatted(),
+            vec![
+                "# O",
+                "import os",
+                "from os import path",
+                "# S",
+                "import sys",
+                "from shutil import copy",
+            ]
+        );
+        assert_eq!(helper.import_lines_estimate(), 6);
+    }
+
+    #[test]
+    fn test_import_lines_estimate_matches_formatted() {
+        let assert_estimate = |helper: &ImportHelper| {
+            assert_eq!(helper.import_lines_estimate(), helper.get_formatted().len());
+        };
+
+        let mut helper = ImportHelper::with_package_name("myapp".to_string());
+        assert_estimate(&helper);
+
+        helper.add_import_string("from __future__ import annotations");
+        helper.add_import_string("import os");
+        helper.add_import_string("import os");
+        helper.add_import_string("from typing import Any, Optional, List, Dict");
+        helper.add_import_string("from pydantic import BaseModel");
+        helper.add_import_string("from pydantic import Field");
+        helper.add_import_string("from myapp.models import User");
+        assert_estimate(&helper);
+
+        let mut config = FormattingConfig {
+            alpha_subheaders: true,
+            empty_group_placeholder: Some("# none".to_string()),
+            ..FormattingConfig::default()
+        };
+        let mut sparse = ImportHelper::with_formatting_config(config.clone());
+        sparse.add_import_string("import requests");
+        sparse.add_import_string("from fastapi import FastAPI");
+        assert_estimate(&sparse);
+
+        config.force_multiline = true;
+        helper.set_formatting_config(config);
+        assert_estimate(&helper);
+    }
+
+    #[test]
+    fn test_retain_honors_noqa() {
+        let mut helper = ImportHelper::new();
+        helper.add_import_string("import os  # noqa: F401");
+        helper.add_import_string("import sys");
+
+        let mut pruned = helper.clone();
+        pruned.retain(|_| false);
+        assert!(pruned.is_empty());
+
+        helper.set_formatting_config(FormattingConfig {
+            honor_noqa: true,
+            ..FormattingConfig::default()
+        });
+        helper.retain(|_| false);
+        assert_eq!(helper.get_formatted(), vec!["import os  # noqa: F401"]);
+    }
+
+    #[test]
+    fn test_commented_import_keeps_own_line() {
+        let mut helper = ImportHelper::new();
+        helper.add_import_string("from typing import Any");
+        helper.add_import_string("from typing import cast  # noqa: F401");
+        helper.add_import_string("from typing import Optional");
+
+        assert_eq!(
+            helper.get_formatted(),
+            vec![
+                "from typing import Any, Optional",
+                "from typing import cast  # noqa: F401",
+            ]
+        );
+        assert_eq!(helper.import_lines_estimate(), 2);
+        helper.assert_idempotent();
+    }
+
+    #[test]
+    fn test_prune_unused_imports() {
+        let mut helper = ImportHelper::new();
+        helper.add_import_string("from __future__ import annotations");
+        helper.add_import_string("import numpy as np");
+        helper.add_import_string("import os");
+        helper.add_import_string("from typing import Any, Optional, cast");
+
+        let used = HashSet::from(["np", "Optional"]);
+        let removed = helper.prune_unused(&used);
+
+        assert_eq!(removed, vec!["import os", "from typing import Any, cast"]);
+        assert_eq!(
+            helper.get_formatted(),
+            vec![
+                "from __future__ import annotations",
+                "",
+                "from typing import Optional",
+                "",
+                "import numpy as np",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_prune_unused_respects_noqa_and_type_checking_guard() {
+        let mut helper = ImportHelper::with_formatting_config(FormattingConfig {
+            honor_noqa: true,
+            ..FormattingConfig::default()
+        });
+        helper.add_import_string("import readline  # noqa: F401");
+        helper.add_type_checking_import("from pydantic import BaseModel");
+
+        let removed = helper.prune_unused(&HashSet::from(["BaseModel"]));
+        assert!(removed.is_empty());
+        assert_eq!(
+            helper.get_formatted(),
+            vec![
+                "from typing import TYPE_CHECKING",
+                "",
+                "import readline  # noqa: F401",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_prefer_direct_submodule_imports() {
+        let mut helper = ImportHelper::with_package_name("myapp".to_string());
+        helper.registry_mut().add_submodule("myapp.models");
+        helper.add_import_string("from myapp import models, Settings");
+        helper.add_import_string("from collections import abc as cabc");
+        helper.add_import_string("from pydantic import BaseModel");
+
+        helper.prefer_direct_submodule_imports();
+        assert_eq!(
+            helper.get_formatted(),
+            vec![
+                "import collections.abc as cabc",
+                "",
+                "from pydantic import BaseModel",
+                "",
+                "import myapp.models",
+                "from myapp import Settings",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reconcile_type_checking_adds_and_removes_guard() {
+        let mut helper = ImportHelper::new();
+        helper.set_auto_type_checking_import(false);
+        helper.add_import_string("from typing import Any");
+        helper.add_type_checking_import("from pydantic import BaseModel");
+        assert_eq!(helper.get_formatted(), vec!["from typing import Any"]);
+
+        // Adding: exactly one guard, even when reconciling repeatedly
+        helper.reconcile_type_checking().reconcile_type_checking();
+        assert_eq!(
+            helper.get_formatted(),
+            vec!["from typing import TYPE_CHECKING, Any"]
+        );
+
+        // Removing: the block became empty, so the guard goes away
+        helper.retain(|import| import.package != "pydantic");
+        helper.reconcile_type_checking();
+        assert_eq!(helper.get_formatted(), vec!["from typing import Any"]);
+    }
+
+    #[test]
+    fn test_reconcile_type_checking_drops_guard_only_import() {
+        let mut helper = ImportHelper::new();
+        helper.add_import_string("import os");
+        helper.add_type_checking_import("from pydantic import BaseModel");
+        assert_eq!(
+            helper.get_formatted(),
+            vec!["import os", "from typing import TYPE_CHECKING"]
+        );
+
+        helper.prune_unused(&HashSet::from(["os"]));
+        helper.reconcile_type_checking();
+        assert_eq!(helper.get_formatted(), vec!["import os"]);
+        assert!(helper.is_type_checking_empty());
+    }
+
+    #[test]
+    fn test_future_imports_ordering_and_routing() {
+        let mut helper = ImportHelper::new();
+        helper.set_resolver(|package| (package == "attrs").then_some(ImportCategory::Future));
+        helper.add_import_string("from __future__ import annotations");
+        helper.add_import_string("from __future__ import absolute_import, division");
+        helper.add_import_string("import attrs");
+
+        let expected = vec![
+            "from __future__ import annotations, absolute_import, division",
+            "",
+            "import attrs",
+        ];
+        assert_eq!(helper.get_formatted(), expected);
+
+        // Declaration order does not affect the result
+        let mut reversed = ImportHelper::new();
+        reversed.add_import_string("import attrs");
+        reversed.add_import_string("from __future__ import division, absolute_import");
+        reversed.add_import_string("from __future__ import annotations");
+        assert_eq!(reversed.get_formatted(), expected);
+
+        // Manifests cannot route other imports into the future section either
+        let mut manifest = helper.to_manifest();
+        for entry in &mut manifest.imports {
+            entry.category = ImportCategory::Future;
+        }
+        assert_eq!(
+            ImportHelper::from_manifest(&manifest).get_formatted(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_contains_package() {
+        let mut helper = ImportHelper::new();
+        helper.add_import_string("import os");
+        helper.add_type_checking_import("from pydantic.fields import FieldInfo");
+
+        assert!(helper.contains_package("os"));
+        assert!(helper.contains_package("pydantic.fields"));
+        assert!(!helper.contains_package("pydantic"));
+        assert!(!helper.contains_package("sys"));
+
+        assert!(helper.contains_package_or_submodule("pydantic"));
+        assert!(!helper.contains_package_or_submodule("pydantic_settings"));
+    }
+
+    #[test]
+    fn test_relative_imports_last() {
+        let mut helper = ImportHelper::with_package_name("myapp".to_string());
+        helper.add_import_string("from . import utils");
+        helper.add_import_string("from .schemas import Schema");
+        helper.add_import_string("from myapp.models import User");
+
+        assert_eq!(
+            helper.get_formatted(),
+            vec![
+                "from . import utils",
+                "from .schemas import Schema",
+                "from myapp.models import User",
+            ]
+        );
+
+        helper.set_formatting_config(FormattingConfig {
+            relative_imports_last: true,
+            ..FormattingConfig::default()
+        });
+        assert_eq!(
+            helper.get_formatted(),
+            vec![
+                "from myapp.models import User",
+                "from . import utils",
+                "from .schemas import Schema",
+            ]
+        );
+        assert_eq!(helper.import_lines_estimate(), 3);
+
+        helper.add_import_string("from . import auth");
+        helper.set_formatting_config(FormattingConfig {
+            relative_imports_last: true,
+            alpha_subheaders: true,
+            ..FormattingConfig::default()
+        });
+        assert_eq!(helper.import_lines_estimate(), helper.get_formatted().len());
+    }
+
+    #[test]
+    fn test_to_builder_code() {
+        let mut helper = ImportHelper::new();
+        helper.add_import_string("import os");
+        helper.add_import_string("import numpy as np");
+        helper.add_import_string("from typing import Optional");
+        helper.add_import_string("from typing import Any");
+        helper.add_import_string("import readline  # noqa: F401");
+        helper.add_type_checking_import("from pydantic import BaseModel");
+
+        let code = helper.to_builder_code();
+        let lines: Vec<&str> = code.lines().collect();
+        assert_eq!(lines[0], "let mut helper = ImportHelper::new();");
+        for expected in [
+            r#"helper.add_direct_import("os");"#,
+            r#"helper.add_direct_import("numpy as np");"#,
+            r#"helper.add_from_import("typing", &["TYPE_CHECKING", "Any", "Optional"]);"#,
+            r#"helper.add_import_string("import readline  # noqa: F401");"#,
+            r#"helper.add_type_checking_from_import("pydantic", &["BaseModel"]);"#,
+        ] {
+            assert!(lines.contains(&expected), "missing {expected} in:\n{code}");
+        }
+
+        // Replaying the emitted calls reproduces the imports
+        let mut replayed = ImportHelper::new();
+        replayed.add_direct_import("os");
+        replayed.add_direct_import("numpy as np");
+        replayed.add_from_import("typing", &["TYPE_CHECKING", "Any", "Optional"]);
+        replayed.add_import_string("import readline  # noqa: F401");
+        replayed.add_type_checking_from_import("pydantic", &["BaseModel"]);
+        assert_eq!(replayed.render_file(), helper.render_file());
+        assert_eq!(replayed.to_builder_code(), code);
+    }
+
+    #[test]
+    fn test_redundant_alias_rendering() {
+        let mut helper = ImportHelper::new();
+        helper.add_import_string("from x import y as y");
+        assert_eq!(helper.get_formatted(), vec!["from x import y as y"]);
+
+        helper.set_formatting_config(FormattingConfig {
+            remove_redundant_aliases: true,
+            ..FormattingConfig::default()
+        });
+        helper.add_import_string("from x import y");
+        assert_eq!(helper.get_formatted(), vec!["from x import y"]);
+    }
+
+    #[test]
+    fn test_categories_present() {
+        let mut helper = ImportHelper::with_package_name("myapp".to_string());
+        assert!(helper.categories_present().is_empty());
+
+        helper.add_import_string("import os");
+        helper.add_import_string("from pathlib import Path");
+        helper.add_import_string("from myapp.models import User");
+        helper.add_type_checking_import("from pydantic import BaseModel");
+
+        assert_eq!(
+            helper.categories_present(),
+            HashSet::from([ImportCategory::StandardLibrary, ImportCategory::Local])
+        );
+        assert_eq!(
+            helper.type_checking_categories_present(),
+            HashSet::from([ImportCategory::ThirdParty])
+        );
+    }
+
+    #[test]
+    fn test_vendor_package() {
+        let mut helper = ImportHelper::with_package_name("myapp".to_string());
+        helper.add_import_string("import requests");
+        helper.add_import_string("import requests.adapters as adapters_mod");
+        helper.add_import_string("from requests import get as fetch");
+        helper.add_import_string("from requests.exceptions import HTTPError");
+        helper.add_import_string("import requests_toolbelt");
+        helper.add_type_checking_import("from requests import Response");
+
+        helper.vendor_package("requests", "myapp._vendor");
+
+        assert_eq!(
+            helper.get_formatted(),
+            vec![
+                "from typing import TYPE_CHECKING",
+                "",
+                "import requests_toolbelt",
+                "",
+                "from myapp._vendor import requests",
+                "from myapp._vendor.requests import adapters as adapters_mod, get as fetch",
+                "from myapp._vendor.requests.exceptions import HTTPError",
+            ]
+        );
+        assert_eq!(
+            helper.get_type_checking_formatted(),
+            vec!["from myapp._vendor.requests import Response"]
+        );
+        assert_eq!(
+            helper.type_checking_categories_present(),
+            HashSet::from([ImportCategory::Local])
+        );
+    }
+
+    #[test]
+    fn test_content_hash_ignores_insertion_order() {
+        let mut first = ImportHelper::with_package_name("myapp".to_string());
+        first.add_import_string("from typing import Any");
+        first.add_import_string("import numpy as np");
+        first.add_import_string("from typing import Optional");
+        first.add_type_checking_import("from myapp.models import User");
+
+        let mut second = ImportHelper::with_package_name("myapp".to_string());
+        second.add_type_checking_import("from myapp.models import User");
+        second.add_import_string("from typing import Optional, Any");
+        second.add_import_string("import numpy as np");
+
+        assert_eq!(first.content_hash(), second.content_hash());
+        assert_eq!(ImportHelper::new().content_hash(), 0xcbf2_9ce4_8422_2325);
+
+        second.add_import_string("import os");
+        assert_ne!(first.content_hash(), second.content_hash());
+    }
+
+    #[test]
+    fn test_relative_depth_order() {
+        let mut helper = ImportHelper::with_package_name("myapp".to_string());
+        helper.add_import_string("from ..shared import Base");
+        helper.add_import_string("from .schemas import Schema");
+        helper.add_import_string("from ... import settings");
+        helper.add_import_string("from . import utils");
+        helper.add_import_string("from myapp.models import User");
+
+        let formatted = |helper: &mut ImportHelper, order: RelativeOrder| {
+            helper.set_formatting_config(FormattingConfig {
+                relative_depth_order: order,
+                ..FormattingConfig::default()
+            });
+            helper.get_formatted()
+        };
+
+        assert_eq!(
+            formatted(&mut helper, RelativeOrder::None),
+            vec![
+                "from . import utils",
+                "from ... import settings",
+                "from ..shared import Base",
+                "from .schemas import Schema",
+                "from myapp.models import User",
+            ]
+        );
+        assert_eq!(
+            formatted(&mut helper, RelativeOrder::ShallowFirst),
+            vec![
+                "from . import utils",
+                "from .schemas import Schema",
+                "from ..shared import Base",
+                "from ... import settings",
+                "from myapp.models import User",
+            ]
+        );
+        assert_eq!(
+            formatted(&mut helper, RelativeOrder::DeepFirst),
+            vec![
+                "from ... import settings",
+                "from ..shared import Base",
+                "from . import utils",
+                "from .schemas import Schema",
+                "from myapp.models import User",
+            ]
+        );
+        assert_eq!(helper.import_lines_estimate(), 5);
+    }
+
+    #[test]
+    fn test_take_formatted() {
+        let mut helper = ImportHelper::with_package_name("myapp".to_string());
+        helper.add_import_string("from typing import Any");
+        helper.add_import_string("import requests");
+        helper.add_type_checking_import("from myapp.models import User");
+        let expected = helper.get_formatted();
+
+        assert_eq!(helper.take_formatted(), expected);
+        assert!(helper.is_empty());
+        assert!(helper.is_type_checking_empty());
+        assert!(helper.take_formatted().is_empty());
+    }
+
+    #[test]
+    fn test_from_import_without_items_is_dropped() {
+        let mut helper = ImportHelper::new();
+        for statement in ["from x import", "from x import ", "from x import ()"] {
+            helper.add_import_string(statement);
+            assert!(matches!(
+                helper.try_add_import_string(statement),
+                Err(ImportParseError::InvalidStatement { .. })
+            ));
+        }
+        helper.add_import_string("import os");
+
+        assert_eq!(helper.get_formatted(), vec!["import os"]);
+    }
+
+    #[test]
+    fn test_merge_stdlib_third_party() {
+        let mut helper = ImportHelper::with_package_name("myapp".to_string());
+        helper.add_import_string("import requests");
+        helper.add_import_string("import os");
+        helper.add_import_string("from pydantic import BaseModel");
+        helper.add_import_string("from typing import Any");
+        helper.add_import_string("from myapp.models import User");
+
+        helper.set_formatting_config(FormattingConfig {
+            merge_stdlib_third_party: true,
+            ..FormattingConfig::default()
+        });
+        assert_eq!(
+            helper.get_formatted(),
+            vec![
+                "import os",
+                "import requests",
+                "from pydantic import BaseModel",
+                "from typing import Any",
+                "",
+                "from myapp.models import User",
+            ]
+        );
+        assert_eq!(helper.import_lines_estimate(), 6);
+    }
+
+    #[test]
+    fn test_from_all_categorized_round_trip() {
+        let mut helper = ImportHelper::with_package_name("myapp".to_string());
+        helper.add_import_string("from __future__ import annotations");
+        helper.add_import_string("import os");
+        helper.add_import_string("from typing import Any, Optional");
+        helper.add_import_string("import numpy as np");
+        helper.add_from_import_multiline(
+            "pydantic",
+            &["BaseModel", "Field", "validator", "root_validator"],
+        );
+        helper.add_import_string("from myapp.models import User  # noqa: F401");
+        helper.add_import_string("from . import utils");
+        helper.add_type_checking_import("from collections.abc import Mapping");
+        helper.add_type_checking_import("from myapp.services import Service");
+
+        let categorized = helper.get_all_categorized();
+        let rebuilt = ImportHelper::from_all_categorized(&categorized);
+        assert_eq!(rebuilt.get_all_categorized(), categorized);
+        assert_eq!(rebuilt.render_file(), helper.render_file());
+    }
+
+    #[test]
+    fn test_runtime_typing_name_not_repeated_in_type_checking() {
+        // Runtime first: the type-only import is redundant
+        let mut helper = ImportHelper::new();
+        helper.add_import_string("from typing import Any");
+        helper.add_type_checking_import("from typing import Any");
+        assert_eq!(helper.get_formatted(), vec!["from typing import Any"]);
+        assert!(helper.is_type_checking_empty());
+
+        // Type-only first: the runtime import takes the name over
+        let mut helper = ImportHelper::new();
+        helper.add_type_checking_import("from typing import Any, Protocol");
+        helper.add_import_string("from typing import Any");
+        assert_eq!(
+            helper.get_formatted(),
+            vec!["from typing import TYPE_CHECKING, Any"]
+        );
+        assert_eq!(
+            helper.get_type_checking_formatted(),
+            vec!["from typing import Protocol"]
+        );
+
+        // Once the block is emptied, the guard import goes away too
+        let mut helper = ImportHelper::new();
+        helper.add_type_checking_import("from typing import Any");
+        helper.add_import_string("from typing import Any");
+        assert_eq!(helper.get_formatted(), vec!["from typing import Any"]);
+        assert!(helper.is_type_checking_empty());
+
+        // Other packages are left alone
+        let mut helper = ImportHelper::new();
+        helper.add_import_string("from httpx import Client");
+        helper.add_type_checking_import("from httpx import Client, Response");
+        assert_eq!(
+            helper.get_type_checking_formatted(),
+            vec!["from httpx import Client, Response"]
+        );
+    }
+
+    #[test]
+    fn test_root_category_cache_keeps_submodules_correct() {
+        let mut helper = ImportHelper::with_package_name("myapp".to_string());
+        helper.registry_mut().add_stdlib_package("vendorlib.compat");
+        for statement in [
+            "import os",
+            "import os.path",
+            "from xml.etree import ElementTree",
+            "import xml.dom.minidom",
+            "from myapp.models import User",
+            "import myapp.services.auth",
+            "import vendorlib",
+            "import vendorlib.compat",
+        ] {
+            helper.add_import_string(statement);
+        }
+
+        let (_, stdlib, third_party, local) = helper.get_categorized();
+        assert_eq!(
+            stdlib,
+            vec![
+                "import os",
+                "import os.path",
+                "import vendorlib.compat",
+                "import xml.dom.minidom",
+                "from xml.etree import ElementTree",
+            ]
+        );
+        assert_eq!(third_party, vec!["import vendorlib"]);
+        assert_eq!(
+            local,
+            vec![
+                "import myapp.services.auth",
+                "from myapp.models import User"
+            ]
+        );
+
+        // Dotted local prefixes take precedence over a standard library root
+        let mut helper = ImportHelper::new();
+        helper.add_import_string("import json");
+        helper.add_local_package_prefix("json.custom");
+        helper.add_import_string("import json.custom.codec");
+        helper.add_import_string("import json.decoder");
+        let (_, stdlib, _, local) = helper.get_categorized();
+        assert_eq!(stdlib, vec!["import json", "import json.decoder"]);
+        assert_eq!(local, vec!["import json.custom.codec"]);
+    }
+
+    #[test]
+    fn test_ingest_preserves_fmt_off_block() {
+        let source = "import sys\nimport abc\n# fmt: off\nimport re, os\nfrom typing import Optional, Any\n# fmt: on\nimport json\nfrom typing import Dict\n";
+        let mut helper = ImportHelper::new();
+        helper.ingest(source);
+
+        assert_eq!(
+            helper.get_formatted(),
+            vec![
+                "import abc",
+                "import json",
+                "import sys",
+                "from typing import Dict",
+                "# fmt: off",
+                "import re, os",
+                "from typing import Optional, Any",
+                "# fmt: on",
+            ]
+        );
+        assert_eq!(helper.import_lines_estimate(), 8);
+
+        let mut reingested = ImportHelper::new();
+        reingested.ingest(&helper.render_file());
+        assert_eq!(reingested.get_formatted(), helper.get_formatted());
+    }
+
+    #[test]
+    fn test_type_checking_block_with_tabs() {
+        let mut helper = ImportHelper::with_formatting_config(FormattingConfig {
+            use_tabs: true,
+            ..FormattingConfig::default()
+        });
+        helper.add_type_checking_from_import(
+            "typing",
+            &["Callable", "Iterator", "Mapping", "Sequence"],
+        );
+
+        assert_eq!(
+            helper.render_file(),
+            "from typing import TYPE_CHECKING\n\nif TYPE_CHECKING:\n\tfrom typing import (\n\t\tCallable,\n\t\tIterator,\n\t\tMapping,\n\t\tSequence,\n\t)\n"
+        );
+    }
+
+    #[test]
+    fn test_difference_from_default_registry() {
+        let mut helper = ImportHelper::new();
+        assert!(helper.difference_from_default_registry().is_empty());
+
+        helper
+            .registry_mut()
+            .add_stdlib_package("foo")
+            .remove_third_party_package("requests");
+
+        let diff = helper.difference_from_default_registry();
+        assert_eq!(diff.added_stdlib, vec!["foo"]);
+        assert_eq!(diff.removed_third_party, vec!["requests"]);
+        assert!(diff.removed_stdlib.is_empty());
+        assert!(diff.added_third_party.is_empty());
+    }
+
+    #[test]
+    fn test_auto_disambiguate() {
+        let mut helper = ImportHelper::with_package_name("myapp".to_string());
+        helper.add_import_string("from pkg1 import Thing");
+        helper.add_import_string("from pkg2 import Other, Thing");
+        helper.add_import_string("from pkg1 import Thing");
+        helper.add_import_string("from myapp.models import Thing");
+        helper.add_import_string("from . import Thing");
+
+        let renames = helper.auto_disambiguate();
+        assert_eq!(
+            renames,
+            vec![
+                ("pkg2.Thing".to_string(), "pkg2_Thing".to_string()),
+                (
+                    "myapp.models.Thing".to_string(),
+                    "myapp_models_Thing".to_string()
+                ),
+                (".Thing".to_string(), "local_Thing".to_string()),
+            ]
+        );
+        assert_eq!(
+            helper.get_formatted(),
+            vec![
+                "from pkg1 import Thing",
+                "from pkg2 import Other, Thing as pkg2_Thing",
+                "",
+                "from . import Thing as local_Thing",
+                "from myapp.models import Thing as myapp_models_Thing",
+            ]
+        );
+        assert!(helper.auto_disambiguate().is_empty());
+    }
+
+    #[test]
+    fn test_auto_disambiguate_follows_insertion_order() {
+        let mut helper = ImportHelper::new();
+        helper.add_import_string("from pkg2 import Thing");
+        helper.add_import_string("from pkg1 import Thing");
+
+        // pkg1 sorts first but was added last, so it is the one renamed
+        let renames = helper.auto_disambiguate();
+        assert_eq!(
+            renames,
+            vec![("pkg1.Thing".to_string(), "pkg1_Thing".to_string())]
+        );
+        assert_eq!(
+            helper.get_formatted(),
+            vec![
+                "from pkg1 import Thing as pkg1_Thing",
+                "from pkg2 import Thing"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_contains_future() {
+        let mut helper = ImportHelper::new();
+        assert!(!helper.contains_future("annotations"));
+
+        helper.add_import_string("from __future__ import annotations");
+        helper.add_import_string("from __future__ import division, generator_stop");
+        helper.add_import_string("from typing import annotations");
+        assert!(helper.contains_future("annotations"));
+        assert!(helper.contains_future("generator_stop"));
+        assert!(!helper.contains_future("barry_as_FLUFL"));
+
+        helper.clear();
+        helper.add_import_string("from typing import annotations");
+        assert!(!helper.contains_future("annotations"));
+    }
+
+    #[test]
+    fn test_apply_to_source_replaces_import_block() {
+        let source = "#!/usr/bin/env python\n\"\"\"Example module.\n\nDetails.\n\"\"\"\nimport sys\nfrom myapp.models import User\nimport os\n# a note about typing\nfrom typing import TYPE_CHECKING\nif TYPE_CHECKING:\n    from httpx import Client\n\n\nclass Service:\n    import json\n";
+        let mut helper = ImportHelper::with_package_name("myapp".to_string());
+        helper.ingest(source);
+
+        assert_eq!(
+            helper.apply_to_source(source),
+            "#!/usr/bin/env python\n\"\"\"Example module.\n\nDetails.\n\"\"\"\n\nimport os\nimport sys\nfrom typing import TYPE_CHECKING\n\nfrom myapp.models import User\n\nif TYPE_CHECKING:\n    from httpx import Client\n\n\nclass Service:\n    import json\n"
+        );
+
+        // Applying the same imports again changes nothing
+        let applied = helper.apply_to_source(source);
+        assert_eq!(helper.apply_to_source(&applied), applied);
+    }
+
+    #[test]
+    fn test_apply_to_source_inserts_missing_block() {
+        let mut helper = ImportHelper::new();
+        helper.add_import_string("import os");
+
+        assert_eq!(
+            helper.apply_to_source("\"\"\"Docs.\"\"\"\nprint(os.name)\n"),
+            "\"\"\"Docs.\"\"\"\n\nimport os\n\nprint(os.name)\n"
+        );
+        assert_eq!(
+            helper.apply_to_source("print(os.name)\n"),
+            "import os\n\nprint(os.name)\n"
+        );
+        assert_eq!(helper.apply_to_source(""), "import os\n");
+
+        // Without imports to write, a file without a block is left alone
+        let empty = ImportHelper::new();
+        assert_eq!(empty.apply_to_source("x = 1\n\n\n"), "x = 1\n\n\n");
+        assert_eq!(empty.apply_to_source("import os\n\nx = 1\n"), "x = 1\n");
+    }
+
+    #[test]
+    fn test_apply_to_source_keeps_imports_after_code() {
+        let source = "import sys\nimport os\n\nx = 1\nimport json\n";
+        let mut helper = ImportHelper::new();
+        helper.ingest(source);
+        assert_eq!(
+            helper.apply_to_source(source),
+            "import os\nimport sys\n\nx = 1\nimport json\n"
+        );
+
+        let source = "import os\n\ntry:\n    import ujson as json\nexcept ImportError:\n    import json\nimport requests\n";
+        let mut helper = ImportHelper::new();
+        helper.ingest(source);
+        assert_eq!(helper.get_formatted(), vec!["import os"]);
+        assert_eq!(helper.apply_to_source(source), source);
+    }
+
+    #[test]
+    fn test_apply_to_source_ignores_docstring_text() {
+        let source = "\"\"\"Helpers.\n\nfrom here on the API is stable;\nimport this carefully.\n\"\"\"\n\nimport sys\nimport os\n\nx = 1\n";
+        let mut helper = ImportHelper::new();
+        helper.ingest(source);
+
+        assert!(helper.raw_unparsed().is_empty());
+        assert_eq!(
+            helper.apply_to_source(source),
+            "\"\"\"Helpers.\n\nfrom here on the API is stable;\nimport this carefully.\n\"\"\"\n\nimport os\nimport sys\n\nx = 1\n"
+        );
+    }
+
+    #[test]
+    fn test_apply_to_source_keeps_leading_comment_spacing() {
+        for source in [
+            "#!/usr/bin/env python\nimport os\n",
+            "#!/usr/bin/env python\n# -*- coding: utf-8 -*-\n\n\nimport os\n\nx = 1\n",
+        ] {
+            let mut helper = ImportHelper::new();
+            helper.ingest(source);
+            assert_eq!(helper.apply_to_source(source), source);
+        }
+    }
+
+    #[test]
+    fn test_add_local_module_exact_match() {
+        let mut helper = ImportHelper::new();
+        helper.add_local_module("helpers");
+        helper.add_import_string("import helpers");
+        helper.add_import_string("from helpers import slugify");
+        helper.add_import_string("import helpersx");
+        helper.add_import_string("import helpers.sub");
+
+        let (_, _, third_party, local) = helper.get_categorized();
+        assert_eq!(local, vec!["import helpers", "from helpers import slugify"]);
+        assert_eq!(third_party, vec!["import helpers.sub", "import helpersx"]);
+    }
+
+    #[test]
+    fn test_format_category_block() {
+        let mut helper = ImportHelper::new();
+        helper.add_import_string("import sys");
+        helper.add_import_string("from os import path");
+        helper.add_import_string("import requests");
+
+        assert_eq!(
+            helper.format_category_block(ImportCategory::StandardLibrary, false),
+            vec!["import sys", "from os import path"]
+        );
+        assert_eq!(
+            helper.format_category_block(ImportCategory::StandardLibrary, true),
+            vec!["import sys", "from os import path", ""]
+        );
+        assert!(helper
+            .format_category_block(ImportCategory::Future, true)
+            .is_empty());
+
+        // Blocks with trailing blanks concatenate into the grouped output
+        let mut composed = helper.format_category_block(ImportCategory::StandardLibrary, true);
+        composed.extend(helper.format_category_block(ImportCategory::ThirdParty, false));
+        assert_eq!(composed, helper.get_formatted());
+    }
+
+    #[test]
+    fn test_redundant_type_checking() {
+        let mut helper = ImportHelper::new();
+        helper.add_import_string("import httpx");
+        helper.add_import_string("from pydantic import BaseModel");
+        helper.add_type_checking_import("import httpx");
+        helper.add_type_checking_import("from pydantic import BaseModel, Field");
+
+        assert_eq!(
+            helper.redundant_type_checking(),
+            vec!["import httpx", "from pydantic import BaseModel"]
+        );
+
+        let removed = helper.remove_redundant_type_checking();
+        assert_eq!(
+            removed,
+            vec!["import httpx", "from pydantic import BaseModel"]
+        );
+        assert!(helper.redundant_type_checking().is_empty());
+        assert_eq!(
+            helper.get_type_checking_categorized().2,
+            vec!["from pydantic import Field"]
+        );
+
+        // Nothing left to remove is a no-op
+        assert!(helper.remove_redundant_type_checking().is_empty());
+    }
+
+    #[test]
+    fn test_remove_redundant_type_checking_drops_guard() {
+        let mut helper = ImportHelper::new();
+        helper.add_import_string("import httpx");
+        helper.add_type_checking_import("import httpx");
+        assert!(helper
+            .get_formatted()
+            .contains(&"from typing import TYPE_CHECKING".to_string()));
+
+        helper.remove_redundant_type_checking();
+        assert_eq!(helper.get_formatted(), vec!["import httpx"]);
+    }
+
+    #[test]
+    fn test_vertical_multiline_style_sorting() {
+        let mut helper = ImportHelper::with_formatting_config(FormattingConfig {
+            multiline_style: crate::MultilineStyle::Vertical,
+            ..FormattingConfig::default()
+        });
+        helper.add_import_string("from typing import Any, Dict, List, Optional");
+        helper.add_import_string("import sys");
+
+        assert_eq!(
+            helper.get_categorized().1,
+            vec![
+                "import sys",
+                "from typing import (Any,",
+                "                    Dict,",
+                "                    List,",
+                "                    Optional,)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rendered_statement_for() {
+        let mut helper = ImportHelper::new();
+        helper.add_import_string("from typing import Optional");
+        helper.add_import_string("from typing import Any, Dict");
+        helper.add_import_string("from typing import List");
+        helper.add_import_string("import os");
+        helper.add_import_string("from os import path");
+        helper.add_type_checking_import("from httpx import Client");
+
+        assert_eq!(
+            helper.rendered_statement_for("typing").as_deref(),
+            Some(concat!(
+                "from typing import (\n",
+                "    TYPE_CHECKING,\n",
+                "    Any,\n",
+                "    Dict,\n",
+                "    List,\n",
+                "    Optional,\n",
+                ")"
+            ))
+        );
+        assert_eq!(
+            helper.rendered_statement_for("os").as_deref(),
+            Some("from os import path")
+        );
+        assert_eq!(
+            helper.rendered_statement_for("httpx").as_deref(),
+            Some("from httpx import Client")
+        );
+        assert_eq!(helper.rendered_statement_for("requests"), None);
+
+        helper.add_import_string("import json");
+        assert_eq!(
+            helper.rendered_statement_for("json").as_deref(),
+            Some("import json")
+        );
+    }
+
+    #[test]
+    fn test_ingest_normalizes_blank_lines() {
+        let source = "import os\n\n\n\nimport sys\n\nimport requests\n\n\nfrom pydantic import BaseModel\n\n\n\nfrom .models import User\nif sys.version_info >= (3, 11):\n    import tomllib\n\n\n    import zoneinfo\n\n\nimport json\n";
 
-        // Add typing imports if any were found (only Any, Generic, TypeVar, Protocol)
-        if !typing_imports.is_empty() {
-            let mut sorted_typing: Vec<String> = typing_imports.into_iter().collect();
-            sorted_typing.sort();
-            let import_statement = format!("from typing import {}", sorted_typing.join(", "));
-            self.add_regular_import(&import_statement);
-        }
+        let mut helper = ImportHelper::new();
+        helper.ingest(source);
+        assert_eq!(
+            helper.render_file(),
+            "import json\nimport os\nimport sys\nif sys.version_info >= (3, 11):\n    import tomllib\n    import zoneinfo\n\nimport requests\nfrom pydantic import BaseModel\n\nfrom .models import User\n"
+        );
 
-        // Add collections.abc imports if any were found (e.g., Callable)
-        if !collections_abc_imports.is_empty() {
-            let mut sorted_collections: Vec<String> = collections_abc_imports.into_iter().collect();
-            sorted_collections.sort();
-            let import_statement = format!(
-                "from collections.abc import {}",
-                sorted_collections.join(", ")
-            );
-            self.add_regular_import(&import_statement);
-        }
+        let mut helper = ImportHelper::with_formatting_config(FormattingConfig {
+            lines_between_sections: 2,
+            ..FormattingConfig::default()
+        });
+        helper.ingest(source);
+        assert_eq!(
+            helper.render_file(),
+            "import json\nimport os\nimport sys\nif sys.version_info >= (3, 11):\n    import tomllib\n    import zoneinfo\n\n\nimport requests\nfrom pydantic import BaseModel\n\n\nfrom .models import User\n"
+        );
+        assert_eq!(helper.import_lines_estimate(), helper.get_formatted().len());
     }
 
-    /// Extract typing imports from a complex type string
-    /// This handles types like list[Any], dict[str, Any], etc.
-    /// Only imports what's actually needed for Python 3.13+ (Any, Generic, `TypeVar`, Protocol)
-    fn extract_typing_imports_from_type(type_str: &str) -> std::collections::HashSet<String> {
-        let mut typing_imports = std::collections::HashSet::new();
-
-        // Check for Any type (used in generics and standalone)
-        if type_str.contains("Any") {
-            typing_imports.insert("Any".to_string());
-        }
+    #[test]
+    fn test_add_from_import_owned() {
+        let package = String::from("pydantic");
+        let items = vec![String::from("Field"), String::from("BaseModel")];
 
-        // Check for Generic type (used for generic classes)
-        if type_str.contains("Generic") {
-            typing_imports.insert("Generic".to_string());
-        }
+        let mut owned = ImportHelper::new();
+        owned.add_from_import_owned(package.clone(), items.clone());
+        owned.add_type_checking_from_import_owned("httpx".to_string(), vec!["Client".to_string()]);
 
-        // Check for TypeVar usage
-        if type_str.contains("TypeVar") {
-            typing_imports.insert("TypeVar".to_string());
-        }
+        let mut borrowed = ImportHelper::new();
+        borrowed.add_from_import("pydantic", &["Field", "BaseModel"]);
+        borrowed.add_type_checking_from_import("httpx", &["Client"]);
 
-        // Check for Protocol type (structural subtyping)
-        if type_str.contains("Protocol") {
-            typing_imports.insert("Protocol".to_string());
-        }
+        assert_eq!(owned.render_file(), borrowed.render_file());
+        assert!(owned.contains_package("httpx"));
 
-        typing_imports
+        // No items means no import, as with the borrowed variant
+        owned.add_from_import_owned("os".to_string(), Vec::new());
+        assert!(!owned.contains_package("os"));
     }
-}
 
-impl Default for ImportHelper {
-    fn default() -> Self {
-        Self::new()
+    #[test]
+    fn test_dunder_modules_are_not_future_imports() {
+        let mut helper = ImportHelper::new();
+        helper.add_import_string("import __main__");
+        helper.add_import_string("from __main__ import app");
+        helper.add_import_string("from __futurex__ import thing");
+        helper.add_import_string("from __future__ import annotations");
+
+        assert_eq!(
+            helper.categorize_import("import __main__"),
+            ImportCategory::StandardLibrary
+        );
+        assert_eq!(
+            helper.categorize_import("from __futurex__ import thing"),
+            ImportCategory::ThirdParty
+        );
+        assert_eq!(
+            helper.get_categorized().0,
+            vec!["from __future__ import annotations"]
+        );
+        assert_eq!(
+            helper.get_categorized().1,
+            vec!["import __main__", "from __main__ import app"]
+        );
+        assert_eq!(
+            helper.get_categorized().2,
+            vec!["from __futurex__ import thing"]
+        );
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_large_imports() {
+        let mut helper = ImportHelper::new();
+        helper.add_from_import("typing", &["Any", "Dict", "List"]);
+        helper.add_from_import("typing", &["Optional", "Union", "Callable", "Any"]);
+        helper.add_from_import("collections", &["OrderedDict", "defaultdict"]);
+        helper.add_import_string("import os");
+
+        assert_eq!(helper.large_imports(5), vec![("typing".to_string(), 6)]);
+        assert!(helper.large_imports(7).is_empty());
+        assert_eq!(
+            helper.large_imports(1),
+            vec![("collections".to_string(), 2), ("typing".to_string(), 6)]
+        );
+    }
 
     #[test]
-    fn test_import_categorization() {
+    fn test_direct_import_depth_order() {
         let mut helper = ImportHelper::new();
+        helper.add_direct_import("sys");
+        helper.add_direct_import("os.path");
+        helper.add_direct_import("os");
+        helper.add_direct_import("xml.etree.ElementTree");
 
-        // Test future imports
-        helper.add_import_string("from __future__ import annotations");
-        assert_eq!(helper.sections.future.len(), 1);
+        assert_eq!(
+            helper.get_formatted(),
+            vec![
+                "import os",
+                "import os.path",
+                "import sys",
+                "import xml.etree.ElementTree"
+            ]
+        );
 
-        // Test standard library from import
-        helper.add_import_string("from typing import Optional");
-        assert_eq!(helper.sections.standard_library_from.len(), 1);
+        helper.formatting_config.direct_import_depth_order = Some(DepthOrder::ShallowFirst);
+        assert_eq!(
+            helper.get_formatted(),
+            vec![
+                "import os",
+                "import sys",
+                "import os.path",
+                "import xml.etree.ElementTree"
+            ]
+        );
 
-        // Test standard library direct import
-        helper.add_import_string("import uuid");
-        assert_eq!(helper.sections.standard_library_direct.len(), 1);
+        helper.formatting_config.direct_import_depth_order = Some(DepthOrder::DeepFirst);
+        assert_eq!(
+            helper.get_formatted(),
+            vec![
+                "import xml.etree.ElementTree",
+                "import os.path",
+                "import os",
+                "import sys"
+            ]
+        );
+    }
 
-        // Test third party
-        helper.add_import_string("from pydantic import BaseModel");
-        assert_eq!(helper.sections.third_party_from.len(), 1);
+    #[test]
+    fn test_emit_isort_off() {
+        let config = FormattingConfig {
+            preserve_order: true,
+            emit_isort_off: true,
+            ..FormattingConfig::default()
+        };
+        let mut helper = ImportHelper::with_formatting_config(config);
+        helper.add_from_import("typing", &["Optional", "Any"]);
+        helper.add_import_string("import requests");
 
-        // Test local imports
-        helper.add_import_string("from .models import User");
-        assert_eq!(helper.sections.local_from.len(), 1);
+        let rendered = helper.render_file();
+        assert_eq!(
+            rendered,
+            "# isort: off\nfrom typing import Optional, Any\n\nimport requests\n# isort: on\n"
+        );
+        assert_eq!(helper.package_items("typing"), vec!["Optional", "Any"]);
+
+        // Re-applying keeps a single pair of markers
+        let source = format!("{rendered}\nprint(1)\n");
+        assert_eq!(helper.apply_to_source(&source), source);
+        assert_eq!(helper.apply_to_source(&rendered), rendered);
+
+        // Either flag alone emits nothing
+        helper.formatting_config.preserve_order = false;
+        assert!(!helper.render_file().contains("isort"));
+        helper.formatting_config.preserve_order = true;
+        helper.formatting_config.emit_isort_off = false;
+        assert_eq!(
+            helper.render_file(),
+            "from typing import Optional, Any\n\nimport requests\n"
+        );
     }
 
     #[test]
-    fn test_import_merging() {
+    fn test_package_count() {
         let mut helper = ImportHelper::new();
+        assert_eq!(helper.package_count(), 0);
 
-        helper.add_import_string("from typing import Optional");
         helper.add_import_string("from typing import Any");
-        helper.add_import_string("from typing import List");
+        helper.add_import_string("from typing import Optional");
+        helper.add_import_string("from typing import List, Dict");
+        assert_eq!(helper.package_count(), 1);
 
-        let imports = helper.get_formatted();
+        helper.add_import_string("import os");
+        helper.add_import_string("from os import path");
+        helper.add_import_string("import requests");
+        helper.add_import_string("from pydantic import BaseModel");
+        helper.add_import_string("from .models import User");
+        helper.add_import_string("from .utils import helper");
+        helper.add_type_checking_import("from httpx import Client");
 
-        // Should merge into a single import
-        assert!(imports.iter().any(|i| i.contains("from typing import")));
-        assert!(imports
-            .iter()
-            .any(|i| i.contains("Any") && i.contains("Optional")));
+        // typing, os, requests, pydantic, .models, .utils
+        assert_eq!(helper.package_count(), 6);
     }
 
     #[test]
-    fn test_alphabetical_sorting_of_import_items() {
-        let mut helper = ImportHelper::new();
+    fn test_suggest_type_checking_for_locals() {
+        let mut helper = ImportHelper::with_package_name("myapp".to_string());
+        helper.add_local_package_prefix("shared");
+        helper.add_import_string("from myapp.models import User");
+        helper.add_import_string("from .utils import slugify");
+        helper.add_import_string("from shared.config import Settings");
+        helper.add_import_string("import myapp.signals");
+        helper.add_import_string("from pydantic import BaseModel");
+        helper.add_type_checking_import("from myapp.repositories import UserRepository");
 
-        // Test with unsorted items in a from import
-        helper.add_import_string("from typing import Optional, Any, List, Dict");
-        helper.add_import_string("from collections import defaultdict, OrderedDict, Counter");
-        helper.add_import_string("import uuid");
-        helper.add_import_string("import os");
+        assert_eq!(
+            helper.suggest_type_checking_for_locals("myapp.services.users"),
+            vec![
+                "from myapp.models import User",
+                "from .utils import slugify"
+            ]
+        );
+        assert_eq!(
+            helper.suggest_type_checking_for_locals("shared"),
+            vec![
+                "from .utils import slugify",
+                "from shared.config import Settings"
+            ]
+        );
+    }
 
-        let imports = helper.get_formatted();
+    #[test]
+    fn test_backslash_multiline_style_round_trip() {
+        let mut helper = ImportHelper::with_formatting_config(FormattingConfig {
+            multiline_style: crate::MultilineStyle::Backslash,
+            ..FormattingConfig::default()
+        });
+        helper.add_import_string("from typing import Any, Dict, List, Optional");
+        helper.add_import_string("import sys");
 
-        // Print for debugging
-        for import in &imports {
-            println!("{}", import);
-        }
+        assert_eq!(
+            helper.get_categorized().1,
+            vec![
+                "import sys",
+                "from typing import \\",
+                "    Any, \\",
+                "    Dict, \\",
+                "    List, \\",
+                "    Optional",
+            ]
+        );
 
-        // Check that direct imports come before from imports
-        let import_str = imports.join("\n");
-        let os_pos = import_str.find("import os").unwrap();
-        let uuid_pos = import_str.find("import uuid").unwrap();
-        let typing_pos = import_str.find("from typing import").unwrap();
+        let rendered = helper.render_file();
+        let mut reparsed = helper.clone_config();
+        reparsed.ingest(&rendered);
+        assert_eq!(reparsed.render_file(), rendered);
+    }
 
-        // Direct imports should come before from imports
+    #[test]
+    fn test_is_sorted() {
+        let helper = ImportHelper::new();
+
+        let sorted = "\"\"\"Module docs.\"\"\"\n\nfrom __future__ import annotations\n\nimport os\nfrom typing import TYPE_CHECKING\n\nimport requests\n\nif TYPE_CHECKING:\n    from httpx import Client\n\n\ndef main():\n    pass\n";
+        assert!(helper.is_sorted(sorted));
+        assert!(helper.is_sorted("import os\nimport sys   \n\n"));
+        assert!(helper.is_sorted("import os  \nimport sys\n\n\nprint(1)\t\n"));
+        assert!(helper.is_sorted("import os\r\nimport sys\r\n\r\n\r\nprint(1)\r\n"));
+        assert!(helper.is_sorted(""));
+
+        // Leading comments keep their spacing and later imports are not checked
+        assert!(helper.is_sorted("#!/usr/bin/env python\nimport os\n"));
+        assert!(helper.is_sorted("# -*- coding: utf-8 -*-\nimport os\nimport sys\n\nx = 1\n"));
+        assert!(helper.is_sorted("import os\n\nx = 1\nimport json\n"));
+        assert!(helper.is_sorted(
+            "import os\n\ntry:\n    import ujson as json\nexcept ImportError:\n    import json\nimport requests\n"
+        ));
+        assert!(!helper
+            .is_sorted("#!/usr/bin/env python\nimport sys\nimport os\n\nx = 1\nimport json\n"));
+
+        // Wrong order, missing group separator, unmerged imports
+        assert!(!helper.is_sorted("import sys\nimport os\n"));
+        assert!(!helper.is_sorted("import os\nimport requests\n"));
+        assert!(!helper.is_sorted("from typing import Any\nfrom typing import Optional\n"));
+
+        // The check depends on the formatting configuration
+        let config = FormattingConfig {
+            merge_stdlib_third_party: true,
+            ..FormattingConfig::default()
+        };
         assert!(
-            os_pos < typing_pos,
-            "Direct imports should come before from imports"
+            ImportHelper::with_formatting_config(config).is_sorted("import os\nimport requests\n")
         );
-        assert!(
-            uuid_pos < typing_pos,
-            "Direct imports should come before from imports"
+    }
+
+    #[test]
+    fn test_categorization_rules() {
+        use crate::PackageMatcher;
+
+        let mut helper = ImportHelper::new();
+        helper.add_import_string("from pydantic import BaseModel");
+        assert_eq!(
+            helper.categorize_import("from pydantic import BaseModel"),
+            ImportCategory::ThirdParty
         );
 
-        // Check that items within from imports are sorted alphabetically
-        let typing_section = &import_str[import_str.find("from typing").unwrap()..];
+        helper.set_categorization_rules(vec![
+            CategoryRule::new(
+                PackageMatcher::Exact("requests.compat".into()),
+                ImportCategory::ThirdParty,
+            ),
+            CategoryRule::new(
+                PackageMatcher::Prefix("requests".into()),
+                ImportCategory::Local,
+            ),
+            CategoryRule::new(
+                PackageMatcher::Glob("*_internal".into()),
+                ImportCategory::Local,
+            ),
+            CategoryRule::new(PackageMatcher::Prefix("os".into()), ImportCategory::Future),
+        ]);
+
+        // Prefix rules override the registry, including for submodules
+        assert_eq!(
+            helper.categorize_import("from requests.adapters import HTTPAdapter"),
+            ImportCategory::Local
+        );
+        // First match wins
+        assert_eq!(
+            helper.categorize_import("from requests.compat import urljoin"),
+            ImportCategory::ThirdParty
+        );
+        assert_eq!(
+            helper.categorize_import("import billing_internal.api"),
+            ImportCategory::Local
+        );
+        // No rule matches: registry fallback
+        assert_eq!(
+            helper.categorize_import("from pydantic import BaseModel"),
+            ImportCategory::ThirdParty
+        );
+        // The future section stays reserved for `__future__`
+        assert_eq!(
+            helper.categorize_import("import os.path"),
+            ImportCategory::StandardLibrary
+        );
 
-        // Verify alphabetical order in typing import (case-insensitive, but considering both single and multi-line)
-        assert!(
-            typing_section.contains("Any")
-                && typing_section.contains("Dict")
-                && typing_section.contains("List")
-                && typing_section.contains("Optional"),
-            "Import should contain all items: Any, Dict, List, Optional in alphabetical order"
+        // The resolver still takes precedence
+        helper.set_resolver(|package| {
+            (package == "requests").then_some(ImportCategory::StandardLibrary)
+        });
+        assert_eq!(
+            helper.categorize_import("import requests"),
+            ImportCategory::StandardLibrary
         );
+
+        assert_eq!(helper.clone_config().category_rules, helper.category_rules);
+        helper.reset();
+        assert!(helper.category_rules.is_empty());
     }
 
     #[test]
-    fn test_direct_imports_sorted_alphabetically() {
+    fn test_merge_items_into() {
         let mut helper = ImportHelper::new();
+        helper.add_import_string("from typing import Optional");
+        helper.add_type_checking_import("from httpx import Client, Response");
 
-        helper.add_direct_import("uuid");
-        helper.add_direct_import("os");
-        helper.add_direct_import("sys");
-        helper.add_direct_import("json");
-
-        let imports = helper.get_formatted();
-
-        // Should be sorted: json, os, sys, uuid
-        let import_lines: Vec<String> = imports
-            .iter()
-            .filter(|s| !s.is_empty() && s.contains("import"))
-            .cloned()
-            .collect();
+        helper.merge_items_into("typing", &["Dict", "Any", "Optional"]);
+        assert_eq!(helper.sections.standard_library_from.len(), 1);
+        assert_eq!(
+            helper.sections.standard_library_from[0].statement,
+            "from typing import TYPE_CHECKING, Any, Dict, Optional"
+        );
 
-        // Verify all 4 imports are present
+        // Absent package: created like add_from_import
+        helper.merge_items_into("pydantic", &["BaseModel"]);
         assert_eq!(
-            import_lines.len(),
-            4,
-            "Should have 4 direct imports, got: {:?}",
-            import_lines
+            helper.get_categorized().2,
+            vec!["from pydantic import BaseModel"]
         );
 
-        // Verify they are sorted alphabetically
-        assert!(
-            import_lines[0].contains("json"),
-            "First should be json, got: {}",
-            import_lines[0]
+        // Only `typing` names are reconciled with the TYPE_CHECKING block
+        helper.merge_items_into("httpx", &["Client"]);
+        assert_eq!(
+            helper.get_type_checking_categorized().2,
+            vec!["from httpx import Client, Response"]
         );
-        assert!(
-            import_lines[1].contains("os"),
-            "Second should be os, got: {}",
-            import_lines[1]
+        assert_eq!(
+            helper.get_categorized().2,
+            vec!["from httpx import Client", "from pydantic import BaseModel"]
         );
-        assert!(
-            import_lines[2].contains("sys"),
-            "Third should be sys, got: {}",
-            import_lines[2]
+
+        // Nothing to merge
+        helper.merge_items_into("typing", &[]);
+        assert_eq!(helper.sections.standard_library_from.len(), 1);
+    }
+
+    #[test]
+    fn test_reset_registry() {
+        let mut helper = ImportHelper::new();
+        helper
+            .registry_mut()
+            .add_stdlib_package("company_std")
+            .remove_stdlib_package("json")
+            .add_third_party_package("company_lib")
+            .remove_third_party_package("requests");
+        assert_eq!(
+            helper.categorize_import("import company_std"),
+            ImportCategory::StandardLibrary
         );
-        assert!(
-            import_lines[3].contains("uuid"),
-            "Fourth should be uuid, got: {}",
-            import_lines[3]
+        assert!(!helper.category_cache.is_empty());
+
+        helper.reset_registry();
+        assert!(helper.category_cache.is_empty());
+        assert!(helper.difference_from_default_registry().is_empty());
+        assert!(helper.registry().is_stdlib("json"));
+        assert!(helper.registry().is_third_party("requests"));
+        assert_eq!(
+            helper.categorize_import("import company_std"),
+            ImportCategory::ThirdParty
         );
     }
 
     #[test]
-    fn test_uppercase_priority_in_import_sorting() {
+    fn test_auto_added_imports() {
         let mut helper = ImportHelper::new();
+        helper.add_import_string("import os");
+        assert!(helper.auto_added_imports().is_empty());
 
-        // Test with mixed case items - uppercase should come before lowercase for same letter
-        helper.add_import_string("from example import Ab, AA, Aa, AB");
-
-        let imports = helper.get_formatted();
+        helper.add_type_checking_import("from httpx import Client");
+        assert_eq!(
+            helper.auto_added_imports(),
+            vec!["from typing import TYPE_CHECKING"]
+        );
+        assert!(helper.sections.standard_library_from[0].auto_added);
+        assert!(!helper.sections.standard_library_direct[0].auto_added);
 
-        // Print for debugging
-        for import in &imports {
-            println!("{}", import);
-        }
+        // Merging keeps caller items out of the injected statement
+        helper.merge_items_into("typing", &["Any"]);
+        assert_eq!(
+            helper.auto_added_imports(),
+            vec!["from typing import TYPE_CHECKING"]
+        );
+        assert_eq!(
+            helper.get_categorized().1,
+            vec!["import os", "from typing import TYPE_CHECKING, Any"]
+        );
 
-        let import_str = imports.join("\n");
-        let example_section = &import_str[import_str.find("from example").unwrap()..];
+        // Removing the TYPE_CHECKING imports drops the injected guard again
+        helper.retain(|import| import.package != "httpx");
+        helper.reconcile_type_checking();
+        assert!(helper.auto_added_imports().is_empty());
 
-        // Verify all items are present (order doesn't matter as much as presence for multiline)
-        assert!(example_section.contains("AA"), "Should contain AA");
-        assert!(example_section.contains("AB"), "Should contain AB");
-        assert!(example_section.contains("Aa"), "Should contain Aa");
-        assert!(example_section.contains("Ab"), "Should contain Ab");
+        // A guard written by the caller is not flagged
+        let mut ingested = ImportHelper::new();
+        ingested.ingest(
+            "from typing import TYPE_CHECKING\n\nif TYPE_CHECKING:\n    from httpx import Client\n",
+        );
+        assert!(ingested.auto_added_imports().is_empty());
     }
 
     #[test]
-    fn test_comprehensive_case_sorting() {
+    fn test_module_sort_independent_of_item_sort() {
         let mut helper = ImportHelper::new();
+        helper.add_import_string("import module10");
+        helper.add_import_string("import module2");
+        helper.add_import_string("from pkg10 import alpha, BETA, Gamma");
+        helper.add_import_string("from pkg2 import zeta, ALPHA");
+        helper.add_import_string("from pkg1 import x");
 
-        // Test with multiple letters and mixed cases
-        helper.add_import_string("from test import zz, ZZ, bb, BB, aa, AA, cc, CC");
-
-        let imports = helper.get_formatted();
-
-        let import_str = imports.join("\n");
-        let test_section = &import_str[import_str.find("from test").unwrap()..];
+        assert_eq!(
+            helper.get_formatted(),
+            vec![
+                "import module10",
+                "import module2",
+                "from pkg1 import x",
+                "from pkg10 import BETA, alpha, Gamma",
+                "from pkg2 import ALPHA, zeta",
+            ]
+        );
 
-        // Verify all items are present in the correct import section
-        assert!(test_section.contains("AA"), "Should contain AA");
-        assert!(test_section.contains("BB"), "Should contain BB");
-        assert!(test_section.contains("CC"), "Should contain CC");
-        assert!(test_section.contains("ZZ"), "Should contain ZZ");
-        assert!(test_section.contains("aa"), "Should contain aa");
-        assert!(test_section.contains("bb"), "Should contain bb");
-        assert!(test_section.contains("cc"), "Should contain cc");
-        assert!(test_section.contains("zz"), "Should contain zz");
+        helper.formatting_config.module_sort = SortMode::Natural;
+        let expected = vec![
+            "import module2",
+            "import module10",
+            "from pkg1 import x",
+            "from pkg2 import ALPHA, zeta",
+            "from pkg10 import BETA, alpha, Gamma",
+        ];
+        assert_eq!(helper.get_formatted(), expected);
+        assert_eq!(helper.get_categorized().2, expected);
     }
 
     #[test]
-    fn test_type_checking_imports() {
-        let mut helper = ImportHelper::with_package_name("mypackage".to_string());
-
-        // Add regular imports
-        helper.add_import_string("from __future__ import annotations");
-        helper.add_import_string("from typing import Any");
-        helper.add_import_string("from pydantic import BaseModel");
+    fn test_render_file_annotates_conflicts() {
+        let mut helper = ImportHelper::with_formatting_config(FormattingConfig {
+            annotate_conflicts: true,
+            ..FormattingConfig::default()
+        });
+        helper.add_import_string("from a import Other, Thing");
+        helper.add_import_string("from b import Thing");
+        helper.add_import_string("from c import Unrelated");
 
-        // Add TYPE_CHECKING imports
-        helper.add_type_checking_import("import httpx");
-        helper.add_type_checking_import("from typing import TYPE_CHECKING");
-        helper.add_type_checking_import("from collections.abc import Callable");
-        helper.add_type_checking_import("from mypackage.models import User");
+        assert_eq!(
+            helper.render_file(),
+            concat!(
+                "from a import Other\n",
+                "from a import Thing as a_Thing  # conflict\n",
+                "from b import Thing as b_Thing  # conflict\n",
+                "from c import Unrelated\n",
+            )
+        );
 
-        // Check that regular imports are counted correctly
-        assert_eq!(helper.count(), 3);
-        assert_eq!(helper.count_type_checking(), 4);
-        assert!(!helper.is_type_checking_empty());
+        // Off by default, and nothing changes without a conflict
+        helper.formatting_config.annotate_conflicts = false;
+        assert!(helper.render_file().contains("from b import Thing\n"));
+        let mut plain = ImportHelper::with_formatting_config(helper.formatting_config.clone());
+        plain.formatting_config.annotate_conflicts = true;
+        plain.add_import_string("from a import Thing");
+        assert_eq!(plain.render_file(), "from a import Thing\n");
+    }
 
-        // Generate TYPE_CHECKING imports
-        let (future, stdlib, third_party, local) = helper.get_type_checking_categorized();
+    #[test]
+    fn test_iter_packages_merges_items() {
+        let mut helper = ImportHelper::new();
+        helper.add_import_string("from typing import List, Any");
+        helper.add_import_string("from typing import Optional, Any");
+        helper.add_type_checking_import("from typing import Sequence");
+        helper.add_import_string("import json");
 
-        // Verify categorization
-        assert!(
-            third_party.iter().any(|s| s.contains("import httpx")),
-            "Should have httpx in third_party"
-        );
-        assert!(
-            stdlib
-                .iter()
-                .any(|s| s.contains("from typing import TYPE_CHECKING")),
-            "Should have TYPE_CHECKING"
-        );
-        assert!(
-            stdlib
-                .iter()
-                .any(|s| s.contains("from collections.abc import Callable")),
-            "Should have Callable"
+        let packages: Vec<(&str, Vec<&str>)> = helper.iter_packages().collect();
+        assert_eq!(
+            packages,
+            vec![
+                ("json", vec![]),
+                ("typing", vec!["Any", "List", "Optional", "TYPE_CHECKING"]),
+            ]
         );
-        assert!(
-            local
-                .iter()
-                .any(|s| s.contains("from mypackage.models import User")),
-            "Should have User in local"
+    }
+
+    #[test]
+    fn test_future_imports_first_with_custom_section_order() {
+        let mut helper = ImportHelper::with_package_and_config(
+            "myapp".to_string(),
+            FormattingConfig {
+                section_order: vec![
+                    ImportCategory::Local,
+                    ImportCategory::ThirdParty,
+                    ImportCategory::Future,
+                ],
+                ..FormattingConfig::default()
+            },
         );
-        assert!(
-            future.is_empty(),
-            "Should have no future imports in TYPE_CHECKING"
+        helper.add_import_string("import os");
+        helper.add_import_string("from myapp.models import User");
+        helper.add_import_string("import requests");
+        helper.add_import_string("from __future__ import annotations");
+
+        assert_eq!(
+            helper.render_file(),
+            concat!(
+                "from __future__ import annotations\n",
+                "\n",
+                "from myapp.models import User\n",
+                "\n",
+                "import requests\n",
+                "\n",
+                "import os\n",
+            )
         );
+        assert_eq!(helper.import_lines_estimate(), 7);
     }
 
     #[test]
-    fn test_programmatic_import_builders() {
+    fn test_set_line_length_changes_wrapping() {
         let mut helper = ImportHelper::new();
+        helper.add_import_string("from collections import OrderedDict, defaultdict");
+        assert_eq!(
+            helper.get_formatted(),
+            vec!["from collections import defaultdict, OrderedDict"]
+        );
 
-        // Test add_from_import
-        helper.add_from_import("typing", &["Any", "Optional"]);
-        helper.add_from_import("json", &["loads"]);
-
-        // Test add_direct_import
-        helper.add_direct_import("sys");
+        helper.set_line_length(40).set_indent_size(2);
+        assert_eq!(
+            helper.get_formatted(),
+            vec![
+                "from collections import (",
+                "  defaultdict,",
+                "  OrderedDict,",
+                ")"
+            ]
+        );
+    }
 
-        // Test TYPE_CHECKING builders
-        helper.add_type_checking_from_import("httpx", &["Client", "Response"]);
-        helper.add_type_checking_direct_import("logging");
+    #[test]
+    fn test_lint_reports_each_violation() {
+        let helper = ImportHelper::with_package_name("myapp".to_string());
+        let source = concat!(
+            "\"\"\"Module docs.\"\"\"\n",
+            "import requests\n",
+            "import os\n",
+            "from typing import Optional, Any\n",
+            "import json, sys\n",
+            "from os.path import *\n",
+            "from myapp import models\n",
+            "\n",
+            "print(models)\n",
+        );
 
-        // Verify regular imports
-        let (_, stdlib, _, _) = helper.get_categorized();
-        assert!(
-            stdlib
-                .iter()
-                .any(|s| s.contains("Any") && s.contains("Optional")),
-            "Should have Any and Optional in typing imports"
+        let found: Vec<(LintCode, usize)> = helper
+            .lint(source)
+            .into_iter()
+            .map(|violation| (violation.code, violation.line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (LintCode::WrongGroupOrder, 3),
+                (LintCode::UnsortedItems, 4),
+                (LintCode::MultipleImports, 5),
+                (LintCode::WildcardImport, 6),
+                (LintCode::MissingBlankLine, 7),
+            ]
         );
-        assert!(stdlib.iter().any(|s| s.contains("from json import loads")));
-        assert!(stdlib.iter().any(|s| s.contains("import sys")));
+        assert_eq!(
+            helper.lint("import sys, os\n")[0].to_string(),
+            "1: I004 2 modules imported in one statement"
+        );
+    }
 
-        // Verify TYPE_CHECKING imports
-        let (_, tc_stdlib, tc_third_party, _) = helper.get_type_checking_categorized();
-        assert!(tc_third_party
-            .iter()
-            .any(|s| s.contains("from httpx import Client, Response")));
-        assert!(tc_stdlib.iter().any(|s| s.contains("import logging")));
+    #[test]
+    fn test_lint_accepts_organized_block() {
+        let helper = ImportHelper::with_package_name("myapp".to_string());
+        let source = concat!(
+            "from __future__ import annotations\n",
+            "\n",
+            "import os\n",
+            "from typing import (  # wrapped\n",
+            "    TYPE_CHECKING,\n",
+            "    Any,\n",
+            ")\n",
+            "\n",
+            "import requests\n",
+            "\n",
+            "from myapp import models\n",
+            "\n",
+            "if TYPE_CHECKING:\n",
+            "    from httpx import Client\n",
+            "    import os\n",
+        );
+        assert_eq!(helper.lint(source), Vec::new());
     }
 
     #[test]
-    fn test_type_checking_four_categories() {
-        let mut helper = ImportHelper::with_package_name("myapp".to_string());
+    fn test_typing_backport_reroutes_listed_names() {
+        let mut helper = ImportHelper::new();
+        helper.set_typing_backport(&["Self", "override"], "typing_extensions");
+        helper.add_from_import("typing", &["Any", "Self"]);
+        helper.add_type_checking_from_import("typing", &["override"]);
+        helper.add_from_import("collections", &["OrderedDict"]);
 
-        // Add imports in all four categories for TYPE_CHECKING
-        helper.add_type_checking_import("from __future__ import annotations");
-        helper.add_type_checking_import("from typing import Protocol");
-        helper.add_type_checking_import("from httpx import Client");
-        helper.add_type_checking_import("from myapp.models import User");
+        assert_eq!(
+            helper.get_formatted(),
+            vec![
+                "from collections import OrderedDict",
+                "from typing import TYPE_CHECKING, Any",
+                "",
+                "from typing_extensions import Self",
+            ]
+        );
+        assert!(helper
+            .get_type_checking_formatted()
+            .contains(&"from typing_extensions import override".to_string()));
+
+        // Statement strings are taken as written
+        helper.add_import_string("from typing import Self");
+        assert!(helper
+            .get_formatted()
+            .contains(&"from typing import TYPE_CHECKING, Any, Self".to_string()));
+    }
 
-        let (future, stdlib, third_party, local) = helper.get_type_checking_categorized();
+    #[test]
+    fn test_render_file_with_header_comment() {
+        let mut helper = ImportHelper::with_formatting_config(FormattingConfig {
+            header_comment: Some(
+                "AUTOGENERATED - DO NOT EDIT\nGenerated by schema-gen".to_string(),
+            ),
+            ..FormattingConfig::default()
+        });
+        helper.add_import_string("import os");
+        helper.add_import_string("import requests");
 
-        // Verify all four categories
-        assert_eq!(future.len(), 1, "Should have 1 future import");
-        assert_eq!(stdlib.len(), 1, "Should have 1 stdlib import");
-        assert_eq!(third_party.len(), 1, "Should have 1 third-party import");
-        assert_eq!(local.len(), 1, "Should have 1 local import");
+        assert_eq!(
+            helper.render_file(),
+            concat!(
+                "# AUTOGENERATED - DO NOT EDIT\n",
+                "# Generated by schema-gen\n",
+                "import os\n",
+                "\n",
+                "import requests\n",
+            )
+        );
 
-        assert!(future[0].contains("from __future__ import annotations"));
-        assert!(stdlib[0].contains("from typing import Protocol"));
-        assert!(third_party[0].contains("from httpx import Client"));
-        assert!(local[0].contains("from myapp.models import User"));
+        // After the docstring, and not duplicated when applied again
+        let source = "\"\"\"Models.\"\"\"\nimport os\n\nprint(os.name)\n";
+        let applied = helper.apply_to_source(source);
+        assert_eq!(
+            applied,
+            concat!(
+                "\"\"\"Models.\"\"\"\n",
+                "\n",
+                "# AUTOGENERATED - DO NOT EDIT\n",
+                "# Generated by schema-gen\n",
+                "import os\n",
+                "\n",
+                "import requests\n",
+                "\n",
+                "print(os.name)\n",
+            )
+        );
+        assert_eq!(helper.apply_to_source(&applied), applied);
     }
 
     #[test]
-    fn test_clear_preserves_configuration() {
-        let mut helper = ImportHelper::with_package_name("mypackage".to_string());
-        let mut config = FormattingConfig::black_compatible();
-        config.force_multiline = true;
-        helper.set_formatting_config(config.clone());
-        helper.add_local_package_prefix("other_package");
-
-        // Add imports
-        helper.add_import_string("from typing import Any");
-        helper.add_type_checking_import("from httpx import Client");
-        assert_eq!(helper.count(), 1);
-        assert_eq!(helper.count_type_checking(), 1);
+    fn test_replace_item_renames_and_dedups() {
+        let mut helper = ImportHelper::new();
+        helper.add_import_string("from pydantic import BaseModel, validator");
+        helper.add_type_checking_import("from pydantic import validator as check");
 
-        // Clear imports
-        helper.clear();
+        assert!(helper.replace_item("pydantic", "validator", "field_validator"));
+        assert_eq!(
+            helper.get_formatted(),
+            vec![
+                "from typing import TYPE_CHECKING",
+                "",
+                "from pydantic import BaseModel, field_validator",
+            ]
+        );
+        assert_eq!(
+            helper.get_type_checking_formatted(),
+            vec!["from pydantic import field_validator as check"]
+        );
 
-        // Verify imports are cleared
-        assert!(helper.is_empty());
-        assert!(helper.is_type_checking_empty());
-        assert_eq!(helper.count(), 0);
-        assert_eq!(helper.count_type_checking(), 0);
+        // Renaming onto an item that is already imported keeps one of them
+        helper.add_import_string("from pydantic import Field");
+        assert!(helper.replace_item("pydantic", "Field", "BaseModel"));
+        assert_eq!(
+            helper.get_formatted(),
+            vec![
+                "from typing import TYPE_CHECKING",
+                "",
+                "from pydantic import BaseModel, field_validator",
+            ]
+        );
 
-        // Verify configuration is preserved
-        assert_eq!(helper.package_name.as_deref(), Some("mypackage"));
-        assert!(helper.local_package_prefixes.contains("mypackage"));
-        assert!(helper.local_package_prefixes.contains("other_package"));
-        assert_eq!(helper.formatting_config().line_length, 88);
-        assert!(helper.formatting_config().force_multiline);
+        assert!(!helper.replace_item("pydantic", "root_validator", "model_validator"));
+        assert!(!helper.replace_item("attrs", "field_validator", "validator"));
     }
 
     #[test]
-    fn test_reset_clears_everything() {
-        let mut helper = ImportHelper::with_package_name("mypackage".to_string());
-        let mut config = FormattingConfig::black_compatible();
-        config.force_multiline = true;
-        helper.set_formatting_config(config);
-        helper.add_local_package_prefix("other_package");
-
-        // Add imports
-        helper.add_import_string("from typing import Any");
-        helper.add_type_checking_import("from httpx import Client");
-        assert_eq!(helper.count(), 1);
-        assert_eq!(helper.count_type_checking(), 1);
-
-        // Reset to default
-        helper.reset();
+    fn test_apply_overrides_moves_packages() {
+        let mut helper = ImportHelper::with_package_name("myapp".to_string());
+        helper.add_local_module("helpers");
 
-        // Verify everything is reset
-        assert!(helper.is_empty());
-        assert!(helper.is_type_checking_empty());
-        assert_eq!(helper.count(), 0);
-        assert_eq!(helper.count_type_checking(), 0);
+        // Base layer, then an override layer moving `requests` again
+        helper.apply_overrides(&CategoryOverrides {
+            stdlib: vec!["helpers".to_string()],
+            third_party: vec!["json".to_string()],
+            local: vec!["requests".to_string()],
+        });
+        helper.apply_overrides(&CategoryOverrides {
+            third_party: vec!["requests".to_string()],
+            local: vec!["tomli".to_string()],
+            ..CategoryOverrides::default()
+        });
 
-        // Verify configuration is reset to default
-        assert_eq!(helper.package_name, None);
-        assert!(helper.local_package_prefixes.is_empty());
-        assert_eq!(helper.formatting_config().line_length, 79); // Default PEP8
-        assert!(!helper.formatting_config().force_multiline);
+        for statement in [
+            "import json",
+            "import helpers",
+            "import requests",
+            "import tomli",
+        ] {
+            helper.add_import_string(statement);
+        }
+        let (_, stdlib, third_party, local) = helper.get_categorized();
+        assert_eq!(stdlib, vec!["import helpers"]);
+        assert_eq!(third_party, vec!["import json", "import requests"]);
+        assert_eq!(local, vec!["import tomli"]);
     }
 
     #[test]
-    fn test_clear_and_reuse() {
-        let mut helper = ImportHelper::with_package_name("mypackage".to_string());
-
-        // First use
-        helper.add_import_string("from typing import Any");
-        helper.add_import_string("from pydantic import BaseModel");
-        assert_eq!(helper.count(), 2);
-
-        // Clear for reuse with same configuration
-        helper.clear();
-        assert!(helper.is_empty());
+    fn test_apply_overrides_submodule() {
+        let mut helper = ImportHelper::new();
+        helper.apply_overrides(&CategoryOverrides {
+            third_party: vec!["xml.etree".to_string()],
+            ..CategoryOverrides::default()
+        });
+        helper.add_import_string("from xml.etree import ElementTree");
+        helper.add_import_string("import xml.etree.cElementTree");
+        helper.add_import_string("import xml.dom");
 
-        // Second use with same configuration
-        helper.add_import_string("import json");
-        helper.add_import_string("import sys");
-        let (_, stdlib, _, _) = helper.get_categorized();
-        assert_eq!(stdlib.len(), 2);
-        assert!(stdlib.iter().any(|s| s.contains("import json")));
-        assert!(stdlib.iter().any(|s| s.contains("import sys")));
+        let (_, stdlib, third_party, _) = helper.get_categorized();
+        assert_eq!(stdlib, vec!["import xml.dom"]);
+        assert_eq!(
+            third_party,
+            vec![
+                "import xml.etree.cElementTree",
+                "from xml.etree import ElementTree"
+            ]
+        );
+        assert_eq!(
+            helper.clone_config().category_overrides,
+            helper.category_overrides
+        );
     }
 }
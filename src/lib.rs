@@ -38,12 +38,22 @@ pub mod utils;
 
 // Re-export the main ImportHelper and key types
 pub use core::ImportHelper;
-pub use registry::PackageRegistry;
+pub use registry::{PackageRegistry, PythonVersion};
+pub use utils::formatting::normalize_statement;
+pub use utils::parsing::parse_imports;
 
 // Re-export types that might be needed for advanced usage
 #[allow(unused_imports)]
-pub use types::{FormattingConfig, ImportCategory, ImportSections, ImportStatement, ImportType};
+pub use types::{
+    CategoryOverrides, CategoryRule, DepthOrder, FormattingConfig, ImportCategory, ImportManifest,
+    ImportParseError, ImportSections, ImportStatement, ImportStats, ImportType, LintCode,
+    LintViolation, ManifestEntry, MultilineStyle, PackageMatcher, ParseCategoryError,
+    ParseConfigError, RegistryDiff, RelativeOrder, SortMode, SourceImport, Style,
+};
 
 // Re-export constants for external use
 #[allow(unused_imports)]
-pub use registry::constants::{COMMON_THIRD_PARTY_PACKAGES, PYTHON_STDLIB_MODULES};
+pub use registry::constants::{
+    COMMON_THIRD_PARTY_PACKAGES, DEFAULT_PYTHON_VERSION, DISTRIBUTION_NAMES, PYTHON_STDLIB_MODULES,
+    PYTHON_STDLIB_VERSIONED_MODULES,
+};
@@ -3,6 +3,34 @@
 //! This module contains the default lists of Python standard library modules
 //! and common third-party packages used to initialize the package registry.
 
+/// A Python version as `(major, minor)`
+pub type VersionTuple = (u8, u8);
+
+/// Python version `(major, minor)` targeted by the default standard library list
+pub const DEFAULT_PYTHON_VERSION: VersionTuple = (3, 13);
+
+/// Standard library modules whose availability depends on the Python version
+///
+/// Each entry is `(module, added_in, removed_in)`; a module is available when
+/// `added_in <= version < removed_in`.
+pub const PYTHON_STDLIB_VERSIONED_MODULES: &[(&str, VersionTuple, Option<VersionTuple>)] = &[
+    ("zoneinfo", (3, 9), None),
+    ("graphlib", (3, 9), None),
+    ("tomllib", (3, 11), None),
+    ("distutils", (3, 0), Some((3, 12))),
+    ("imp", (3, 0), Some((3, 12))),
+    ("asynchat", (3, 0), Some((3, 12))),
+    ("asyncore", (3, 0), Some((3, 12))),
+    ("smtpd", (3, 0), Some((3, 12))),
+    ("cgi", (3, 0), Some((3, 13))),
+    ("cgitb", (3, 0), Some((3, 13))),
+    ("crypt", (3, 0), Some((3, 13))),
+    ("imghdr", (3, 0), Some((3, 13))),
+    ("pipes", (3, 0), Some((3, 13))),
+    ("telnetlib", (3, 0), Some((3, 13))),
+    ("uu", (3, 0), Some((3, 13))),
+];
+
 /// Python standard library modules for categorization
 ///
 /// This list includes commonly used standard library modules that are part of
@@ -70,6 +98,8 @@ pub const PYTHON_STDLIB_MODULES: &[&str] = &[
     "sysconfig",
     "types",
     "warnings",
+    "tomllib",
+    "__main__",
 ];
 
 /// Common third-party packages that might be recognized
@@ -89,3 +119,18 @@ pub const COMMON_THIRD_PARTY_PACKAGES: &[&str] = &[
     "pytest",
     "sqlalchemy",
 ];
+
+/// Import names whose distribution (PyPI project) name differs
+///
+/// Each entry maps a top-level import name to the distribution that provides
+/// it, so dependency reports can list names suitable for requirements files.
+pub const DISTRIBUTION_NAMES: &[(&str, &str)] = &[
+    ("PIL", "Pillow"),
+    ("bs4", "beautifulsoup4"),
+    ("cv2", "opencv-python"),
+    ("dateutil", "python-dateutil"),
+    ("dotenv", "python-dotenv"),
+    ("jwt", "PyJWT"),
+    ("sklearn", "scikit-learn"),
+    ("yaml", "PyYAML"),
+];
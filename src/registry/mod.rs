@@ -6,8 +6,52 @@
 
 pub mod constants;
 
-use constants::{COMMON_THIRD_PARTY_PACKAGES, PYTHON_STDLIB_MODULES};
-use std::collections::HashSet;
+use crate::types::{ImportCategory, RegistryDiff};
+use constants::{
+    VersionTuple, COMMON_THIRD_PARTY_PACKAGES, DEFAULT_PYTHON_VERSION, DISTRIBUTION_NAMES,
+    PYTHON_STDLIB_MODULES, PYTHON_STDLIB_VERSIONED_MODULES,
+};
+use std::collections::{HashMap, HashSet};
+
+/// Supported Python versions for [`PackageRegistry::for_python`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PythonVersion {
+    /// Python 3.8
+    Py38,
+    /// Python 3.9
+    Py39,
+    /// Python 3.10
+    Py310,
+    /// Python 3.11
+    Py311,
+    /// Python 3.12
+    Py312,
+    /// Python 3.13
+    Py313,
+}
+
+impl PythonVersion {
+    /// Get the version as a `(major, minor)` tuple
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::PythonVersion;
+    ///
+    /// assert_eq!(PythonVersion::Py311.as_tuple(), (3, 11));
+    /// ```
+    #[must_use]
+    pub const fn as_tuple(self) -> VersionTuple {
+        match self {
+            Self::Py38 => (3, 8),
+            Self::Py39 => (3, 9),
+            Self::Py310 => (3, 10),
+            Self::Py311 => (3, 11),
+            Self::Py312 => (3, 12),
+            Self::Py313 => (3, 13),
+        }
+    }
+}
 
 /// Registry for package categorization
 ///
@@ -20,28 +64,111 @@ pub struct PackageRegistry {
     stdlib_packages: HashSet<String>,
     /// Known third-party packages
     third_party_packages: HashSet<String>,
+    /// Import names mapped to the distribution that provides them
+    distribution_names: HashMap<String, String>,
+    /// Dotted module paths known to be submodules (e.g. `myapp.models`)
+    submodules: HashSet<String>,
 }
 
 impl PackageRegistry {
     /// Create a new registry with default Python 3.13 stdlib and common third-party packages
+    ///
+    /// The stdlib list targets [`DEFAULT_PYTHON_VERSION`].
     #[must_use]
     pub fn new() -> Self {
+        let (major, minor) = DEFAULT_PYTHON_VERSION;
+        Self::for_python_version(major, minor)
+    }
+
+    /// Create a registry whose stdlib list matches a specific Python version
+    ///
+    /// Modules added or removed across versions (see
+    /// [`PYTHON_STDLIB_VERSIONED_MODULES`]) are included only when available.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::PackageRegistry;
+    ///
+    /// assert!(!PackageRegistry::for_python_version(3, 10).is_stdlib("tomllib"));
+    /// assert!(PackageRegistry::for_python_version(3, 11).is_stdlib("tomllib"));
+    /// assert!(PackageRegistry::for_python_version(3, 11).is_stdlib("distutils"));
+    /// ```
+    #[must_use]
+    pub fn for_python_version(major: u8, minor: u8) -> Self {
         Self {
-            stdlib_packages: Self::default_stdlib_packages(),
+            stdlib_packages: Self::stdlib_packages_for((major, minor)),
             third_party_packages: Self::default_third_party_packages(),
+            distribution_names: Self::default_distribution_names(),
+            submodules: HashSet::new(),
         }
     }
 
+    /// Create a registry whose stdlib list matches a supported Python version
+    ///
+    /// Equivalent to [`for_python_version`](Self::for_python_version), without
+    /// the possibility of passing a version that does not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::{PackageRegistry, PythonVersion};
+    ///
+    /// assert!(!PackageRegistry::for_python(PythonVersion::Py310).is_stdlib("tomllib"));
+    /// assert!(PackageRegistry::for_python(PythonVersion::Py311).is_stdlib("tomllib"));
+    /// ```
+    #[must_use]
+    pub fn for_python(version: PythonVersion) -> Self {
+        let (major, minor) = version.as_tuple();
+        Self::for_python_version(major, minor)
+    }
+
     /// Check if a package is in the standard library
+    ///
+    /// A dotted module matches when it or any of its parents is registered, so
+    /// `urllib.request` is stdlib because `urllib` is. Full dotted paths can also
+    /// be registered on their own when the root should not match.
     #[must_use]
     pub fn is_stdlib(&self, package: &str) -> bool {
-        self.stdlib_packages.contains(package)
+        contains_module(&self.stdlib_packages, package)
     }
 
     /// Check if a package is a known third-party package
+    ///
+    /// Like [`is_stdlib`](Self::is_stdlib), a dotted module matches when it or
+    /// any of its parents is registered, so `requests.adapters` is third-party.
     #[must_use]
     pub fn is_third_party(&self, package: &str) -> bool {
-        self.third_party_packages.contains(package)
+        contains_module(&self.third_party_packages, package)
+    }
+
+    /// Look up the category a package is registered under
+    ///
+    /// Returns `Some(StandardLibrary)` if [`is_stdlib`](Self::is_stdlib) (which
+    /// includes submodules of registered packages), otherwise
+    /// `Some(ThirdParty)` if [`is_third_party`](Self::is_third_party) (also
+    /// including submodules), and
+    /// `None` for unknown packages. A package in both lists is standard library.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::{ImportCategory, PackageRegistry};
+    ///
+    /// let registry = PackageRegistry::new();
+    /// assert_eq!(registry.contains("os.path"), Some(ImportCategory::StandardLibrary));
+    /// assert_eq!(registry.contains("requests"), Some(ImportCategory::ThirdParty));
+    /// assert_eq!(registry.contains("my_unknown_pkg"), None);
+    /// ```
+    #[must_use]
+    pub fn contains(&self, package: &str) -> Option<ImportCategory> {
+        if self.is_stdlib(package) {
+            Some(ImportCategory::StandardLibrary)
+        } else if self.is_third_party(package) {
+            Some(ImportCategory::ThirdParty)
+        } else {
+            None
+        }
     }
 
     /// Add a package to the standard library registry
@@ -114,12 +241,106 @@ impl PackageRegistry {
         self
     }
 
+    /// Register a dotted module path as a submodule
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper.registry_mut().add_submodule("myapp.models");
+    /// assert!(helper.registry().is_submodule("myapp.models"));
+    /// ```
+    pub fn add_submodule(&mut self, path: impl Into<String>) -> &mut Self {
+        self.submodules.insert(path.into());
+        self
+    }
+
+    /// Check if a dotted path is a known submodule
+    ///
+    /// Registered submodules and dotted standard library entries (such as
+    /// `collections.abc`) are recognized.
+    #[must_use]
+    pub fn is_submodule(&self, path: &str) -> bool {
+        path.contains('.')
+            && (self.submodules.contains(path) || self.stdlib_packages.contains(path))
+    }
+
+    /// Map an import name to the distribution that provides it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportHelper;
+    ///
+    /// let mut helper = ImportHelper::new();
+    /// helper
+    ///     .registry_mut()
+    ///     .add_distribution_name("google.protobuf", "protobuf");
+    /// ```
+    pub fn add_distribution_name(
+        &mut self,
+        import_name: impl Into<String>,
+        distribution: impl Into<String>,
+    ) -> &mut Self {
+        self.distribution_names
+            .insert(import_name.into(), distribution.into());
+        self
+    }
+
+    /// Remove an import-name-to-distribution mapping
+    pub fn remove_distribution_name(&mut self, import_name: &str) -> &mut Self {
+        self.distribution_names.remove(import_name);
+        self
+    }
+
+    /// Get the distribution name for an import name
+    ///
+    /// Falls back to the import name itself when no mapping is registered.
+    #[must_use]
+    pub fn distribution_name<'a>(&'a self, import_name: &'a str) -> &'a str {
+        self.distribution_names
+            .get(import_name)
+            .map_or(import_name, String::as_str)
+    }
+
+    /// Reset to the default import-name-to-distribution mapping
+    pub fn reset_distribution_names_to_defaults(&mut self) -> &mut Self {
+        self.distribution_names = Self::default_distribution_names();
+        self
+    }
+
+    /// Get the default import-name-to-distribution mapping
+    fn default_distribution_names() -> HashMap<String, String> {
+        DISTRIBUTION_NAMES
+            .iter()
+            .map(|(import_name, distribution)| {
+                ((*import_name).to_string(), (*distribution).to_string())
+            })
+            .collect()
+    }
+
     /// Get the default Python 3.13 standard library packages
     fn default_stdlib_packages() -> HashSet<String> {
-        PYTHON_STDLIB_MODULES
+        Self::stdlib_packages_for(DEFAULT_PYTHON_VERSION)
+    }
+
+    /// Get the standard library packages available in a Python version
+    fn stdlib_packages_for(version: VersionTuple) -> HashSet<String> {
+        let mut packages: HashSet<String> = PYTHON_STDLIB_MODULES
             .iter()
             .map(|s| (*s).to_string())
-            .collect()
+            .collect();
+
+        for (module, added, removed) in PYTHON_STDLIB_VERSIONED_MODULES {
+            if *added <= version && removed.map_or(true, |removed| version < removed) {
+                packages.insert((*module).to_string());
+            } else {
+                packages.remove(*module);
+            }
+        }
+        packages
     }
 
     /// Get the default common third-party packages
@@ -153,6 +374,57 @@ impl PackageRegistry {
     pub fn count_third_party_packages(&self) -> usize {
         self.third_party_packages.len()
     }
+
+    /// Compare the stdlib and third-party packages against a baseline registry
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::PackageRegistry;
+    ///
+    /// let mut registry = PackageRegistry::new();
+    /// registry.add_stdlib_package("foo");
+    ///
+    /// let diff = registry.diff(&PackageRegistry::new());
+    /// assert_eq!(diff.added_stdlib, vec!["foo"]);
+    /// assert!(diff.removed_stdlib.is_empty());
+    /// ```
+    #[must_use]
+    pub fn diff(&self, baseline: &PackageRegistry) -> RegistryDiff {
+        RegistryDiff {
+            added_stdlib: sorted_difference(&self.stdlib_packages, &baseline.stdlib_packages),
+            removed_stdlib: sorted_difference(&baseline.stdlib_packages, &self.stdlib_packages),
+            added_third_party: sorted_difference(
+                &self.third_party_packages,
+                &baseline.third_party_packages,
+            ),
+            removed_third_party: sorted_difference(
+                &baseline.third_party_packages,
+                &self.third_party_packages,
+            ),
+        }
+    }
+}
+
+/// Check whether a module or one of its dotted parents is in `packages`
+fn contains_module(packages: &HashSet<String>, package: &str) -> bool {
+    let mut candidate = package;
+    loop {
+        if packages.contains(candidate) {
+            return true;
+        }
+        match candidate.rsplit_once('.') {
+            Some((parent, _)) => candidate = parent,
+            None => return false,
+        }
+    }
+}
+
+/// Get the packages in `packages` but not in `other`, sorted
+fn sorted_difference(packages: &HashSet<String>, other: &HashSet<String>) -> Vec<String> {
+    let mut difference: Vec<String> = packages.difference(other).cloned().collect();
+    difference.sort();
+    difference
 }
 
 impl Default for PackageRegistry {
@@ -227,4 +499,114 @@ mod tests {
         assert!(registry.is_stdlib("pkg2"));
         assert!(registry.is_third_party("lib1"));
     }
+
+    #[test]
+    fn test_stdlib_submodules() {
+        let mut registry = PackageRegistry::new();
+        assert!(registry.is_stdlib("urllib.request"));
+        assert!(registry.is_stdlib("xml.dom.minidom"));
+        assert!(!registry.is_stdlib("urllib3"));
+
+        // Register a single dotted path without its root
+        registry
+            .remove_stdlib_package("xml")
+            .add_stdlib_package("xml.dom.minidom");
+        assert!(registry.is_stdlib("xml.dom.minidom"));
+        assert!(!registry.is_stdlib("xml.etree.ElementTree"));
+    }
+
+    #[test]
+    fn test_default_python_version_matches_new() {
+        let (major, minor) = DEFAULT_PYTHON_VERSION;
+        let versioned = PackageRegistry::for_python_version(major, minor);
+        let default = PackageRegistry::new();
+
+        assert_eq!(versioned.stdlib_packages, default.stdlib_packages);
+        assert_eq!(versioned.third_party_packages, default.third_party_packages);
+        assert!(!default.is_stdlib("distutils"));
+        assert!(default.is_stdlib("zoneinfo"));
+    }
+
+    #[test]
+    fn test_distribution_names() {
+        let mut registry = PackageRegistry::new();
+        assert_eq!(registry.distribution_name("cv2"), "opencv-python");
+        assert_eq!(registry.distribution_name("requests"), "requests");
+
+        registry.add_distribution_name("google", "protobuf");
+        assert_eq!(registry.distribution_name("google"), "protobuf");
+
+        registry.remove_distribution_name("cv2");
+        assert_eq!(registry.distribution_name("cv2"), "cv2");
+        registry.reset_distribution_names_to_defaults();
+        assert_eq!(registry.distribution_name("cv2"), "opencv-python");
+    }
+
+    #[test]
+    fn test_diff_against_baseline() {
+        let baseline = PackageRegistry::new();
+        assert!(baseline.diff(&PackageRegistry::new()).is_empty());
+
+        let mut registry = PackageRegistry::new();
+        registry
+            .add_stdlib_packages(&["zeta", "alpha"])
+            .remove_stdlib_package("typing")
+            .add_third_party_package("my_company_lib")
+            .remove_third_party_package("pydantic");
+
+        let diff = registry.diff(&baseline);
+        assert_eq!(diff.added_stdlib, vec!["alpha", "zeta"]);
+        assert_eq!(diff.removed_stdlib, vec!["typing"]);
+        assert_eq!(diff.added_third_party, vec!["my_company_lib"]);
+        assert_eq!(diff.removed_third_party, vec!["pydantic"]);
+    }
+
+    #[test]
+    fn test_for_python_enum() {
+        let py38 = PackageRegistry::for_python(PythonVersion::Py38);
+        assert!(!py38.is_stdlib("zoneinfo"));
+        assert!(py38.is_stdlib("distutils"));
+
+        let py39 = PackageRegistry::for_python(PythonVersion::Py39);
+        assert!(py39.is_stdlib("zoneinfo"));
+        assert!(!py39.is_stdlib("tomllib"));
+
+        let py312 = PackageRegistry::for_python(PythonVersion::Py312);
+        assert!(py312.is_stdlib("tomllib"));
+        assert!(!py312.is_stdlib("distutils"));
+
+        assert!(PackageRegistry::for_python(PythonVersion::Py313)
+            .diff(&PackageRegistry::new())
+            .is_empty());
+    }
+
+    #[test]
+    fn test_contains() {
+        let mut registry = PackageRegistry::new();
+        assert_eq!(
+            registry.contains("json"),
+            Some(ImportCategory::StandardLibrary)
+        );
+        assert_eq!(
+            registry.contains("urllib.request"),
+            Some(ImportCategory::StandardLibrary)
+        );
+        assert_eq!(
+            registry.contains("pydantic"),
+            Some(ImportCategory::ThirdParty)
+        );
+        assert_eq!(
+            registry.contains("requests.adapters"),
+            Some(ImportCategory::ThirdParty)
+        );
+        assert_eq!(registry.contains("not_a_registered_pkg"), None);
+        assert_eq!(registry.contains("not_a_registered_pkg.requests"), None);
+
+        // Standard library wins when a package is registered in both lists
+        registry.add_third_party_package("json");
+        assert_eq!(
+            registry.contains("json"),
+            Some(ImportCategory::StandardLibrary)
+        );
+    }
 }
@@ -4,8 +4,14 @@
 //! py-import-helper library, including import categories, statements, and
 //! type aliases for better API ergonomics.
 
+use crate::utils::categorization::{matches_package_pattern, matches_package_prefix};
+use crate::utils::parsing::natural_cmp;
+use std::collections::HashSet;
+use std::ops::Range;
+
 /// Represents the different categories of Python imports for proper ordering
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ImportCategory {
     /// Future imports (from __future__ import ...)
     Future,
@@ -17,8 +23,101 @@ pub enum ImportCategory {
     Local,
 }
 
+impl ImportCategory {
+    /// All categories in PEP 8 order
+    pub const ALL: [Self; 4] = [
+        Self::Future,
+        Self::StandardLibrary,
+        Self::ThirdParty,
+        Self::Local,
+    ];
+
+    /// Human-readable label for this category, suitable for section headers
+    #[must_use]
+    pub const fn section_label(&self) -> &'static str {
+        match self {
+            Self::Future => "Future",
+            Self::StandardLibrary => "Standard Library",
+            Self::ThirdParty => "Third Party",
+            Self::Local => "Local",
+        }
+    }
+
+    /// Position of this category in PEP 8 ordering (0 = first)
+    #[must_use]
+    pub const fn rank(&self) -> u8 {
+        match self {
+            Self::Future => 0,
+            Self::StandardLibrary => 1,
+            Self::ThirdParty => 2,
+            Self::Local => 3,
+        }
+    }
+}
+
+impl std::str::FromStr for ImportCategory {
+    type Err = ParseCategoryError;
+
+    /// Parse a category name as used in config files and CLI flags
+    ///
+    /// Accepts `future`, `stdlib`/`standard_library`, `third_party`/`thirdparty`
+    /// and `local`, ignoring case and surrounding whitespace.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::ImportCategory;
+    ///
+    /// assert_eq!("STDLIB".parse(), Ok(ImportCategory::StandardLibrary));
+    /// assert!("vendored".parse::<ImportCategory>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "future" => Ok(Self::Future),
+            "stdlib" | "standard_library" => Ok(Self::StandardLibrary),
+            "third_party" | "thirdparty" => Ok(Self::ThirdParty),
+            "local" => Ok(Self::Local),
+            _ => Err(ParseCategoryError(s.to_string())),
+        }
+    }
+}
+
+/// Error returned when a string does not name an [`ImportCategory`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseCategoryError(pub String);
+
+impl std::fmt::Display for ParseCategoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "unknown import category '{}': expected future, stdlib, third_party or local",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseCategoryError {}
+
+/// Error returned when a configuration file cannot be read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseConfigError {
+    /// 1-based line number of the offending line
+    pub line: usize,
+    /// Description of the problem
+    pub message: String,
+}
+
+impl std::fmt::Display for ParseConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseConfigError {}
+
 /// Represents the type of import statement
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ImportType {
     /// Direct import (import module)
     Direct,
@@ -28,6 +127,7 @@ pub enum ImportType {
 
 /// Configuration for import formatting (isort/ruff compatible)
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(default))]
 pub struct FormattingConfig {
     /// Maximum line length before breaking into multi-line format (default: 79 for PEP 8)
     pub line_length: usize,
@@ -41,6 +141,103 @@ pub struct FormattingConfig {
     pub force_multiline: bool,
     /// Minimum number of items to trigger multi-line format when auto-detecting (default: 4)
     pub multiline_threshold: usize,
+    /// Whether to separate `__future__` imports from the next group with a blank line (default: true)
+    pub blank_line_after_future: bool,
+    /// Packages whose merged items keep declaration order instead of being sorted (default: empty)
+    pub preserve_item_order_packages: HashSet<String>,
+    /// Line emitted in place of an empty standard library, third-party or local group (default: None)
+    pub empty_group_placeholder: Option<String>,
+    /// Insert a `# A`, `# B`, ... comment before each first-letter bucket within a group;
+    /// direct and from imports of a letter are moved together under it (default: false)
+    pub alpha_subheaders: bool,
+    /// Keep imports whose comment contains `noqa` when pruning, e.g. with `retain` (default: false)
+    pub honor_noqa: bool,
+    /// Place relative imports (`from . import x`) after absolute ones within a group (default: false)
+    pub relative_imports_last: bool,
+    /// Render `from x import y as y` as `from x import y` (default: false)
+    pub remove_redundant_aliases: bool,
+    /// Merge aliased items into one statement with the other items from the same package;
+    /// when false each `as` item gets its own statement, like isort's default (default: true)
+    pub combine_as_imports: bool,
+    /// Order relative imports by their number of leading dots (default: `RelativeOrder::None`)
+    pub relative_depth_order: RelativeOrder,
+    /// Render standard library and third-party imports as a single group (default: false)
+    pub merge_stdlib_third_party: bool,
+    /// Indent continuation lines and the `TYPE_CHECKING` block with a tab instead of spaces (default: false)
+    pub use_tabs: bool,
+    /// Layout of multi-line from imports (default: `MultilineStyle::VerticalHangingIndent`)
+    pub multiline_style: MultilineStyle,
+    /// Number of blank lines between import groups (default: 1)
+    pub lines_between_sections: usize,
+    /// Order direct imports by dotted depth (`os` vs `os.path`) before alphabetically (default: None)
+    pub direct_import_depth_order: Option<DepthOrder>,
+    /// Keep declaration order of merged items for every package (default: false)
+    pub preserve_order: bool,
+    /// With `preserve_order`, wrap the rendered block in `# isort: off` and
+    /// `# isort: on` so isort leaves the intentional order alone (default: false)
+    pub emit_isort_off: bool,
+    /// Separator between items of single-line from imports: a comma with optional
+    /// spaces or tabs around it, such as `","` or `" , "`.
+    /// `from_toml` (with the `toml` feature) rejects any other value; set directly,
+    /// another value is silently replaced by the default (default: `", "`)
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "deserialize_item_separator")
+    )]
+    pub item_separator: String,
+    /// Ordering of modules (packages) within a group; items keep their own ordering
+    /// (default: `SortMode::Lexicographic`)
+    pub module_sort: SortMode,
+    /// When rendering a whole file, alias every from import item whose name is
+    /// also imported from another module and mark it with `# conflict`
+    /// (default: false)
+    pub annotate_conflicts: bool,
+    /// Order of the import groups; categories left out follow in default order.
+    /// `__future__` imports always come first, wherever `Future` is listed, as
+    /// PEP 8 requires (default: future, stdlib, third-party, local)
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "deserialize_section_order")
+    )]
+    pub section_order: Vec<ImportCategory>,
+    /// Comment emitted above the rendered import block, e.g. a generated-code
+    /// marker; each line gets a `# ` prefix unless it already starts with `#`
+    /// (default: None)
+    pub header_comment: Option<String>,
+}
+
+/// Check whether an item separator is a comma with optional spaces or tabs around it
+fn is_valid_item_separator(separator: &str) -> bool {
+    separator.trim_matches([' ', '\t']) == ","
+}
+
+/// Deserialize `section_order` from category names as accepted by [`ImportCategory`]'s `FromStr`
+#[cfg(feature = "serde")]
+fn deserialize_section_order<'de, D>(deserializer: D) -> Result<Vec<ImportCategory>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let names: Vec<String> = serde::Deserialize::deserialize(deserializer)?;
+    names
+        .iter()
+        .map(|name| name.parse().map_err(serde::de::Error::custom))
+        .collect()
+}
+
+/// Deserialize `item_separator`, rejecting anything but a comma with optional spaces
+#[cfg(feature = "serde")]
+fn deserialize_item_separator<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let separator: String = serde::Deserialize::deserialize(deserializer)?;
+    if is_valid_item_separator(&separator) {
+        Ok(separator)
+    } else {
+        Err(serde::de::Error::custom(format!(
+            "'{separator}' is not a comma with optional spaces"
+        )))
+    }
 }
 
 impl Default for FormattingConfig {
@@ -52,6 +249,32 @@ impl Default for FormattingConfig {
             force_single_line: false,
             force_multiline: false,
             multiline_threshold: 4,
+            blank_line_after_future: true,
+            preserve_item_order_packages: HashSet::new(),
+            empty_group_placeholder: None,
+            alpha_subheaders: false,
+            honor_noqa: false,
+            relative_imports_last: false,
+            remove_redundant_aliases: false,
+            combine_as_imports: true,
+            relative_depth_order: RelativeOrder::None,
+            merge_stdlib_third_party: false,
+            use_tabs: false,
+            multiline_style: MultilineStyle::VerticalHangingIndent,
+            lines_between_sections: 1,
+            direct_import_depth_order: None,
+            preserve_order: false,
+            emit_isort_off: false,
+            item_separator: ", ".to_string(),
+            module_sort: SortMode::Lexicographic,
+            annotate_conflicts: false,
+            section_order: vec![
+                ImportCategory::Future,
+                ImportCategory::StandardLibrary,
+                ImportCategory::ThirdParty,
+                ImportCategory::Local,
+            ],
+            header_comment: None,
         }
     }
 }
@@ -82,6 +305,226 @@ impl FormattingConfig {
     pub fn pep8_compatible() -> Self {
         Self::default()
     }
+
+    /// Load a configuration from the `[formatting]` table of a TOML document
+    ///
+    /// The table may also be nested as `[tool.py-import-helper.formatting]`,
+    /// so the configuration can live in `pyproject.toml`; a top-level
+    /// `[formatting]` table takes precedence. Keys are named after the fields
+    /// of this struct; enum fields take their variant names in snake case
+    /// (e.g. `multiline_style = "vertical"`), and `direct_import_depth_order`
+    /// is left out for no depth ordering. Missing keys keep their defaults,
+    /// and unknown keys and other tables are ignored. Requires the `toml`
+    /// feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseConfigError`] if the document is not valid TOML, a
+    /// known key has a value of the wrong type, or `item_separator` is not a
+    /// comma with optional spaces or tabs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::{FormattingConfig, MultilineStyle};
+    ///
+    /// let config = FormattingConfig::from_toml(
+    ///     "[formatting]\nline_length = 100\nmultiline_style = \"vertical\"\n",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(config.line_length, 100);
+    /// assert_eq!(config.multiline_style, MultilineStyle::Vertical);
+    /// assert!(config.use_trailing_comma);
+    ///
+    /// let config = FormattingConfig::from_toml(
+    ///     "[tool.py-import-helper.formatting]\nline_length = 88\n",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(config.line_length, 88);
+    /// ```
+    #[cfg(feature = "toml")]
+    pub fn from_toml(text: &str) -> Result<Self, ParseConfigError> {
+        #[derive(serde::Deserialize)]
+        struct Document {
+            formatting: Option<FormattingConfig>,
+            tool: Option<Tools>,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct Tools {
+            #[serde(rename = "py-import-helper")]
+            helper: Option<ToolTable>,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct ToolTable {
+            formatting: Option<FormattingConfig>,
+        }
+
+        let document: Document = toml::from_str(text).map_err(|error| ParseConfigError {
+            line: error
+                .span()
+                .map_or(1, |span| text[..span.start].matches('\n').count() + 1),
+            message: error.message().to_string(),
+        })?;
+        Ok(document
+            .formatting
+            .or_else(|| document.tool?.helper?.formatting)
+            .unwrap_or_default())
+    }
+
+    /// Check whether merged items of a package keep their declaration order
+    ///
+    /// True with `preserve_order`, or when the package is listed in
+    /// `preserve_item_order_packages`.
+    #[must_use]
+    pub fn preserves_item_order(&self, package: &str) -> bool {
+        self.preserve_order || self.preserve_item_order_packages.contains(package)
+    }
+
+    /// Get the separator joining the items of a single-line from import
+    ///
+    /// This is `item_separator`, or `", "` when it is not a single comma with
+    /// optional spaces or tabs (anything else would produce invalid Python).
+    #[must_use]
+    pub fn separator(&self) -> &str {
+        if is_valid_item_separator(&self.item_separator) {
+            &self.item_separator
+        } else {
+            ", "
+        }
+    }
+
+    /// Get one level of indentation: a tab with `use_tabs`, otherwise `indent_size` spaces
+    #[must_use]
+    pub fn indent(&self) -> String {
+        if self.use_tabs {
+            "\t".to_string()
+        } else {
+            " ".repeat(self.indent_size)
+        }
+    }
+}
+
+/// Ordering of relative imports (`from . import x`, `from .. import y`) by depth
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum RelativeOrder {
+    /// Plain alphabetical order of the package path
+    #[default]
+    None,
+    /// Fewest leading dots first (`.` before `..`), then alphabetically
+    ShallowFirst,
+    /// Most leading dots first (`...` before `.`), then alphabetically
+    DeepFirst,
+}
+
+/// How module names are ordered within a group
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum SortMode {
+    /// Plain character order: `module1`, `module10`, `module2`
+    #[default]
+    Lexicographic,
+    /// Digit runs compare numerically: `module1`, `module2`, `module10`
+    Natural,
+}
+
+impl SortMode {
+    /// Compare two module names (or statements) in this mode
+    #[must_use]
+    pub fn compare(self, a: &str, b: &str) -> std::cmp::Ordering {
+        match self {
+            Self::Lexicographic => a.cmp(b),
+            Self::Natural => natural_cmp(a, b),
+        }
+    }
+}
+
+/// Ordering of direct imports (`import os`, `import os.path`) by dotted depth
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum DepthOrder {
+    /// Fewest dotted segments first (`os` before `os.path`), then alphabetically
+    ShallowFirst,
+    /// Most dotted segments first (`os.path` before `os`), then alphabetically
+    DeepFirst,
+}
+
+/// Layout of multi-line from imports, named after isort's `multi_line_output` modes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum MultilineStyle {
+    /// One item per indented line, the opening paren on the `from` line and
+    /// the closing paren on its own line (isort mode 3, Black-compatible)
+    ///
+    /// ```text
+    /// from package import (
+    ///     alpha,
+    ///     beta,
+    /// )
+    /// ```
+    #[default]
+    VerticalHangingIndent,
+    /// One item per line, aligned after the opening paren, with the closing
+    /// paren after the last item (isort mode 1)
+    ///
+    /// ```text
+    /// from package import (alpha,
+    ///                      beta)
+    /// ```
+    Vertical,
+    /// One item per indented line, continued with backslashes instead of
+    /// parentheses; `use_trailing_comma` does not apply
+    ///
+    /// ```text
+    /// from package import \
+    ///     alpha, \
+    ///     beta
+    /// ```
+    Backslash,
+}
+
+/// Formatting tool whose exact import style should be reproduced
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Style {
+    /// Black (88 character lines)
+    Black,
+    /// isort defaults (79 character lines)
+    Isort,
+    /// Plain PEP 8 (79 character lines)
+    Pep8,
+    /// ruff (88 character lines)
+    Ruff,
+}
+
+impl Style {
+    /// Get the `FormattingConfig` preset matching this style
+    #[must_use]
+    pub fn config(self) -> FormattingConfig {
+        match self {
+            Self::Black => FormattingConfig::black_compatible(),
+            Self::Isort => FormattingConfig::isort_compatible(),
+            Self::Pep8 => FormattingConfig::pep8_compatible(),
+            Self::Ruff => FormattingConfig::ruff_compatible(),
+        }
+    }
 }
 
 /// Specification for adding imports in a structured way
@@ -142,6 +585,79 @@ impl ImportSpec {
     }
 }
 
+/// Error returned when an import specification or statement cannot be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportParseError {
+    /// The package/module name is empty
+    EmptyPackage,
+    /// The package/module name is not a valid dotted Python identifier
+    InvalidPackage(String),
+    /// An imported item is not a valid Python identifier (or `*`)
+    InvalidItem(String),
+    /// An import statement string is malformed
+    InvalidStatement {
+        /// What was expected at the failing position
+        message: String,
+        /// Byte range in the original string where parsing failed
+        span: Option<Range<usize>>,
+    },
+}
+
+impl ImportParseError {
+    /// Get the byte range in the original statement where parsing failed, if known
+    #[must_use]
+    pub fn span(&self) -> Option<Range<usize>> {
+        match self {
+            Self::InvalidStatement { span, .. } => span.clone(),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ImportParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EmptyPackage => write!(f, "package name must not be empty"),
+            Self::InvalidPackage(package) => {
+                write!(
+                    f,
+                    "invalid package name '{package}': expected a dotted identifier"
+                )
+            }
+            Self::InvalidItem(item) => {
+                write!(
+                    f,
+                    "invalid import item '{item}': expected an identifier or '*'"
+                )
+            }
+            Self::InvalidStatement { message, span } => match span {
+                Some(span) => write!(f, "{message} at {}..{}", span.start, span.end),
+                None => write!(f, "{message}"),
+            },
+        }
+    }
+}
+
+impl std::error::Error for ImportParseError {}
+
+/// An import-related fragment found while scanning Python source code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceImport {
+    /// A single-line import statement, flagged when inside `if TYPE_CHECKING:`
+    Statement {
+        /// The normalized import statement
+        statement: String,
+        /// Whether the import appeared inside an `if TYPE_CHECKING:` block
+        type_checking: bool,
+    },
+    /// A version-gated block (`if sys.version_info ...:`) kept verbatim, line by line
+    ConditionalBlock(Vec<String>),
+    /// Lines between `# fmt: off` and `# fmt: on` (fences included) kept verbatim
+    Fenced(Vec<String>),
+    /// A top-level import that could not be fully structured, kept as its original text
+    Unparsed(String),
+}
+
 /// Represents a single import statement with its category and formatting information
 #[derive(Debug, Clone)]
 pub struct ImportStatement {
@@ -158,6 +674,169 @@ pub struct ImportStatement {
     /// Whether this is a multi-line import
     #[allow(dead_code)]
     pub is_multiline: bool,
+    /// Trailing comment of a single-line import, without the leading `#` (e.g. `noqa: F401`)
+    pub comment: Option<String>,
+    /// Whether the library injected this import (e.g. the `TYPE_CHECKING` guard) rather than the caller
+    pub auto_added: bool,
+}
+
+/// Breakdown of the imports currently held by an `ImportHelper`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportStats {
+    /// Regular direct imports (`import module`), excluding future imports
+    pub direct: usize,
+    /// Regular from imports (`from module import item`), excluding future imports
+    pub from_: usize,
+    /// Regular `from __future__` imports
+    pub future: usize,
+    /// Imports in the `TYPE_CHECKING` block
+    pub type_checking: usize,
+    /// Packages whose from imports were collected more than once and get merged on output
+    pub merged_packages: usize,
+}
+
+/// How a [`CategoryRule`] matches a package name
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PackageMatcher {
+    /// The package name itself, not its submodules
+    Exact(String),
+    /// The package and its submodules (segment-aware: `app` matches `app.models`, not `apps`)
+    Prefix(String),
+    /// A glob pattern with `*` wildcards, matched against the package and its dotted parents
+    Glob(String),
+}
+
+impl PackageMatcher {
+    /// Check whether a package name matches
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::PackageMatcher;
+    ///
+    /// assert!(PackageMatcher::Prefix("app".into()).matches("app.models"));
+    /// assert!(!PackageMatcher::Exact("app".into()).matches("app.models"));
+    /// assert!(PackageMatcher::Glob("*_sdk".into()).matches("billing_sdk.client"));
+    /// ```
+    #[must_use]
+    pub fn matches(&self, package: &str) -> bool {
+        match self {
+            Self::Exact(name) => package == name,
+            Self::Prefix(prefix) => matches_package_prefix(package, prefix),
+            Self::Glob(pattern) => matches_package_pattern(package, pattern),
+        }
+    }
+}
+
+/// A categorization rule: packages matching `matcher` belong to `category`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CategoryRule {
+    /// Which packages the rule applies to
+    pub matcher: PackageMatcher,
+    /// The category assigned to matching packages
+    pub category: ImportCategory,
+}
+
+impl CategoryRule {
+    /// Create a rule assigning `category` to packages matching `matcher`
+    #[must_use]
+    pub fn new(matcher: PackageMatcher, category: ImportCategory) -> Self {
+        Self { matcher, category }
+    }
+}
+
+/// Packages forced into a category, applied by
+/// [`ImportHelper::apply_overrides`](crate::ImportHelper::apply_overrides)
+///
+/// Apply several in turn to layer configurations: a package listed by a
+/// later layer ends up in that layer's category.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CategoryOverrides {
+    /// Packages (and their submodules) treated as standard library
+    pub stdlib: Vec<String>,
+    /// Packages (and their submodules) treated as third-party
+    pub third_party: Vec<String>,
+    /// Packages (and their submodules) treated as local
+    pub local: Vec<String>,
+}
+
+/// The kind of problem reported by [`ImportHelper::lint`](crate::ImportHelper::lint)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LintCode {
+    /// `I001`: an import belongs to an earlier group than the import before it
+    WrongGroupOrder,
+    /// `I002`: imports of different groups are not separated by a blank line
+    MissingBlankLine,
+    /// `I003`: the items of a from import are not sorted
+    UnsortedItems,
+    /// `I004`: several modules are imported in one statement (`import a, b`)
+    MultipleImports,
+    /// `I005`: a wildcard import (`from module import *`)
+    WildcardImport,
+}
+
+impl LintCode {
+    /// Get the short code of this violation kind (e.g. `I001`)
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::WrongGroupOrder => "I001",
+            Self::MissingBlankLine => "I002",
+            Self::UnsortedItems => "I003",
+            Self::MultipleImports => "I004",
+            Self::WildcardImport => "I005",
+        }
+    }
+}
+
+impl std::fmt::Display for LintCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A problem found in an import block by [`ImportHelper::lint`](crate::ImportHelper::lint)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintViolation {
+    /// The kind of problem
+    pub code: LintCode,
+    /// 1-based line number of the offending import statement
+    pub line: usize,
+    /// Human-readable description
+    pub message: String,
+}
+
+impl std::fmt::Display for LintViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} {}", self.line, self.code, self.message)
+    }
+}
+
+/// Packages a `PackageRegistry` has gained or lost relative to a baseline registry
+///
+/// Every list is sorted alphabetically.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegistryDiff {
+    /// Packages registered as standard library that the baseline does not have
+    pub added_stdlib: Vec<String>,
+    /// Baseline standard library packages that are no longer registered
+    pub removed_stdlib: Vec<String>,
+    /// Packages registered as third-party that the baseline does not have
+    pub added_third_party: Vec<String>,
+    /// Baseline third-party packages that are no longer registered
+    pub removed_third_party: Vec<String>,
+}
+
+impl RegistryDiff {
+    /// Check whether the registries hold the same packages
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added_stdlib.is_empty()
+            && self.removed_stdlib.is_empty()
+            && self.added_third_party.is_empty()
+            && self.removed_third_party.is_empty()
+    }
 }
 
 /// Type alias for the return type of categorized imports methods
@@ -182,8 +861,80 @@ pub type CategorizedImports = (
     Vec<String>, // local_imports
 );
 
+impl ImportStatement {
+    /// Check whether the import carries a `noqa` comment
+    #[must_use]
+    pub fn has_noqa(&self) -> bool {
+        self.comment
+            .as_deref()
+            .is_some_and(|comment| comment.contains("noqa"))
+    }
+
+    /// Get the names this statement binds in the importing namespace
+    ///
+    /// Aliases take precedence (`import numpy as np` binds `np`). A plain direct
+    /// import of a dotted module binds its root (`import os.path` binds `os`).
+    /// Star imports bind no known names.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use py_import_helper::{ImportCategory, utils::parsing::parse_import};
+    ///
+    /// let import = parse_import("from a import b as c, d", ImportCategory::Local).unwrap();
+    /// assert_eq!(import.binding_names(), vec!["c", "d"]);
+    /// ```
+    #[must_use]
+    pub fn binding_names(&self) -> Vec<String> {
+        self.items
+            .iter()
+            .flat_map(|item| item.split(','))
+            .map(str::trim)
+            .filter(|name| !name.is_empty() && *name != "*")
+            .map(|name| match name.rsplit_once(" as ") {
+                Some((_, alias)) => alias.trim().to_string(),
+                None if self.import_type == ImportType::Direct => {
+                    name.split('.').next().unwrap_or(name).to_string()
+                }
+                None => name.to_string(),
+            })
+            .collect()
+    }
+}
+
+/// A structured, tool-agnostic description of collected imports
+///
+/// Produced by [`ImportHelper::to_manifest`](crate::ImportHelper::to_manifest)
+/// and serializable with the `serde` feature (e.g. to JSON or TOML).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ImportManifest {
+    /// Every collected import, regular sections first
+    pub imports: Vec<ManifestEntry>,
+}
+
+/// A single import in an [`ImportManifest`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ManifestEntry {
+    /// The import statement as collected
+    pub statement: String,
+    /// The category the import was sorted into
+    pub category: ImportCategory,
+    /// Whether this is a direct or from import
+    pub import_type: ImportType,
+    /// The package/module being imported
+    pub package: String,
+    /// Imported items (the module itself for direct imports)
+    pub items: Vec<String>,
+    /// Trailing comment of the import, without the leading `#`
+    pub comment: Option<String>,
+    /// Whether the import belongs in the `TYPE_CHECKING` block
+    pub type_checking: bool,
+}
+
 /// A collection of imports organized by category and type for proper formatting
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct ImportSections {
     /// Future imports
     pub future: Vec<ImportStatement>,
@@ -216,3 +967,266 @@ pub struct ImportSections {
     /// `TYPE_CHECKING` local from imports
     pub type_checking_local_from: Vec<ImportStatement>,
 }
+
+impl ImportSections {
+    /// Create sections with room for `capacity` imports in each regular section
+    ///
+    /// `TYPE_CHECKING` sections are typically small and are not preallocated.
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            future: Vec::with_capacity(capacity),
+            standard_library_direct: Vec::with_capacity(capacity),
+            standard_library_from: Vec::with_capacity(capacity),
+            third_party_direct: Vec::with_capacity(capacity),
+            third_party_from: Vec::with_capacity(capacity),
+            local_direct: Vec::with_capacity(capacity),
+            local_from: Vec::with_capacity(capacity),
+            ..Self::default()
+        }
+    }
+
+    /// Get the regular (non-`TYPE_CHECKING`) sections in declaration order
+    #[must_use]
+    pub fn regular_sections(&self) -> [&Vec<ImportStatement>; 7] {
+        [
+            &self.future,
+            &self.standard_library_direct,
+            &self.standard_library_from,
+            &self.third_party_direct,
+            &self.third_party_from,
+            &self.local_direct,
+            &self.local_from,
+        ]
+    }
+
+    /// Get the `TYPE_CHECKING` sections in declaration order
+    #[must_use]
+    pub fn type_checking_sections(&self) -> [&Vec<ImportStatement>; 7] {
+        [
+            &self.type_checking_future,
+            &self.type_checking_standard_library_direct,
+            &self.type_checking_standard_library_from,
+            &self.type_checking_third_party_direct,
+            &self.type_checking_third_party_from,
+            &self.type_checking_local_direct,
+            &self.type_checking_local_from,
+        ]
+    }
+
+    /// Get mutable references to every section, regular sections first
+    pub fn sections_mut(&mut self) -> [&mut Vec<ImportStatement>; 14] {
+        [
+            &mut self.future,
+            &mut self.standard_library_direct,
+            &mut self.standard_library_from,
+            &mut self.third_party_direct,
+            &mut self.third_party_from,
+            &mut self.local_direct,
+            &mut self.local_from,
+            &mut self.type_checking_future,
+            &mut self.type_checking_standard_library_direct,
+            &mut self.type_checking_standard_library_from,
+            &mut self.type_checking_third_party_direct,
+            &mut self.type_checking_third_party_from,
+            &mut self.type_checking_local_direct,
+            &mut self.type_checking_local_from,
+        ]
+    }
+
+    /// Get every section paired with its field name, in declaration order
+    #[must_use]
+    pub fn named_sections(&self) -> [(&'static str, &Vec<ImportStatement>); 14] {
+        [
+            ("future", &self.future),
+            ("standard_library_direct", &self.standard_library_direct),
+            ("standard_library_from", &self.standard_library_from),
+            ("third_party_direct", &self.third_party_direct),
+            ("third_party_from", &self.third_party_from),
+            ("local_direct", &self.local_direct),
+            ("local_from", &self.local_from),
+            ("type_checking_future", &self.type_checking_future),
+            (
+                "type_checking_standard_library_direct",
+                &self.type_checking_standard_library_direct,
+            ),
+            (
+                "type_checking_standard_library_from",
+                &self.type_checking_standard_library_from,
+            ),
+            (
+                "type_checking_third_party_direct",
+                &self.type_checking_third_party_direct,
+            ),
+            (
+                "type_checking_third_party_from",
+                &self.type_checking_third_party_from,
+            ),
+            (
+                "type_checking_local_direct",
+                &self.type_checking_local_direct,
+            ),
+            ("type_checking_local_from", &self.type_checking_local_from),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_category_labels_and_ranks() {
+        let expected = [
+            (ImportCategory::Future, "Future", 0),
+            (ImportCategory::StandardLibrary, "Standard Library", 1),
+            (ImportCategory::ThirdParty, "Third Party", 2),
+            (ImportCategory::Local, "Local", 3),
+        ];
+
+        for (category, label, rank) in expected {
+            assert_eq!(category.section_label(), label);
+            assert_eq!(category.rank(), rank);
+        }
+
+        let ranks: Vec<u8> = ImportCategory::ALL
+            .iter()
+            .map(ImportCategory::rank)
+            .collect();
+        assert_eq!(ranks, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_binding_names() {
+        let binding = |statement: &str| {
+            crate::utils::parsing::parse_import(statement, ImportCategory::ThirdParty)
+                .unwrap()
+                .binding_names()
+        };
+
+        assert_eq!(binding("import numpy"), vec!["numpy"]);
+        assert_eq!(binding("import numpy as np"), vec!["np"]);
+        assert_eq!(binding("import os.path"), vec!["os"]);
+        assert_eq!(binding("import os, sys"), vec!["os", "sys"]);
+        assert_eq!(binding("from a import b"), vec!["b"]);
+        assert_eq!(binding("from a import b as c"), vec!["c"]);
+        assert_eq!(binding("from . import sibling"), vec!["sibling"]);
+        assert!(binding("from a import *").is_empty());
+    }
+
+    #[test]
+    fn test_import_category_from_str() {
+        for (input, expected) in [
+            ("future", ImportCategory::Future),
+            ("FUTURE", ImportCategory::Future),
+            ("stdlib", ImportCategory::StandardLibrary),
+            ("standard_library", ImportCategory::StandardLibrary),
+            ("Standard_Library", ImportCategory::StandardLibrary),
+            ("third_party", ImportCategory::ThirdParty),
+            ("thirdparty", ImportCategory::ThirdParty),
+            ("ThirdParty", ImportCategory::ThirdParty),
+            ("local", ImportCategory::Local),
+            (" Local ", ImportCategory::Local),
+        ] {
+            assert_eq!(input.parse::<ImportCategory>(), Ok(expected), "{input}");
+        }
+
+        let error = "vendored".parse::<ImportCategory>().unwrap_err();
+        assert_eq!(error, ParseCategoryError("vendored".to_string()));
+        assert_eq!(
+            error.to_string(),
+            "unknown import category 'vendored': expected future, stdlib, third_party or local"
+        );
+        assert!("".parse::<ImportCategory>().is_err());
+        assert!("third-party".parse::<ImportCategory>().is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn test_formatting_config_from_toml() {
+        let text = r#"
+[project]
+name = "demo"
+
+[formatting]
+line_length = 100   # wider than pep8
+force_single_line = true
+preserve_item_order_packages = ["typing", "enum"]
+item_separator = ",  "
+module_sort = "natural"
+direct_import_depth_order = "deep_first"
+unknown_key = { nested = 1 }
+
+[tool.other]
+line_length = 5
+"#;
+        let config = FormattingConfig::from_toml(text).unwrap();
+        assert_eq!(config.line_length, 100);
+        assert!(config.force_single_line);
+        assert!(config.preserves_item_order("enum"));
+        assert_eq!(config.item_separator, ",  ");
+        assert_eq!(config.module_sort, SortMode::Natural);
+        assert_eq!(
+            config.direct_import_depth_order,
+            Some(DepthOrder::DeepFirst)
+        );
+        assert_eq!(config.indent_size, FormattingConfig::default().indent_size);
+
+        assert_eq!(
+            FormattingConfig::from_toml("").unwrap().line_length,
+            FormattingConfig::default().line_length
+        );
+
+        let config = FormattingConfig::from_toml(concat!(
+            "[formatting]\n",
+            "section_order = [\n",
+            "    \"future\",\n",
+            "    \"local\",  # before third-party\n",
+            "    \"third_party\",\n",
+            "]\n",
+        ))
+        .unwrap();
+        assert_eq!(
+            config.section_order,
+            vec![
+                ImportCategory::Future,
+                ImportCategory::Local,
+                ImportCategory::ThirdParty
+            ]
+        );
+
+        let config = FormattingConfig::from_toml(concat!(
+            "[project]\n",
+            "name = \"demo\"\n",
+            "\n",
+            "[tool.py-import-helper.formatting]\n",
+            "line_length = 88\n",
+            "multiline_style = \"backslash\"\n",
+            "\n",
+            "[tool.ruff]\n",
+            "line-length = 120\n",
+        ))
+        .unwrap();
+        assert_eq!(config.line_length, 88);
+        assert_eq!(config.multiline_style, MultilineStyle::Backslash);
+
+        let error = FormattingConfig::from_toml("[formatting]\n\nuse_tabs = 1\n").unwrap_err();
+        assert_eq!(error.line, 3);
+        assert!(error.message.contains("expected a boolean"), "{error}");
+
+        let error =
+            FormattingConfig::from_toml("[formatting]\nitem_separator = \" \"\n").unwrap_err();
+        assert_eq!(error.line, 2);
+        assert!(
+            error
+                .message
+                .contains("' ' is not a comma with optional spaces"),
+            "{error}"
+        );
+        assert!(
+            FormattingConfig::from_toml("[formatting]\nsection_order = [\"vendored\"]\n").is_err()
+        );
+        assert!(FormattingConfig::from_toml("[formatting]\nitem_separator = \",,\"\n").is_err());
+        assert!(FormattingConfig::from_toml("[formatting]\nitem_separator = \";,\"\n").is_err());
+    }
+}
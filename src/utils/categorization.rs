@@ -3,7 +3,7 @@
 //! This module provides functions for categorizing Python imports into
 //! future, standard library, third-party, and local categories according to PEP 8.
 
-use super::parsing::extract_package;
+use super::parsing::{extract_package, is_future_import};
 use crate::registry::constants::{COMMON_THIRD_PARTY_PACKAGES, PYTHON_STDLIB_MODULES};
 use crate::types::ImportCategory;
 use std::collections::HashSet;
@@ -15,7 +15,7 @@ pub fn categorize_import<S: ::std::hash::BuildHasher>(
     local_package_prefixes: &HashSet<String, S>,
 ) -> ImportCategory {
     // Future imports always come first
-    if import_statement.starts_with("from __future__") {
+    if is_future_import(import_statement) {
         return ImportCategory::Future;
     }
 
@@ -56,7 +56,7 @@ pub fn is_local_import<S: ::std::hash::BuildHasher>(
 
     // Check custom local package prefixes
     for prefix in local_package_prefixes {
-        if package.starts_with(prefix.as_str()) {
+        if matches_package_prefix(&package, prefix) {
             return true;
         }
     }
@@ -64,6 +64,80 @@ pub fn is_local_import<S: ::std::hash::BuildHasher>(
     false
 }
 
+/// Check if a package matches a (possibly dotted) prefix on a module-segment boundary
+///
+/// The prefix must be followed by `.` or the end of the package name, so
+/// `services.auth` matches `services.auth.handlers` but not `services.authz`.
+///
+/// # Examples
+///
+/// ```
+/// use py_import_helper::utils::categorization::matches_package_prefix;
+///
+/// assert!(matches_package_prefix("services.auth.handlers", "services.auth"));
+/// assert!(matches_package_prefix("services.auth", "services.auth"));
+/// assert!(!matches_package_prefix("services.authz", "services.auth"));
+/// ```
+#[must_use]
+pub fn matches_package_prefix(package: &str, prefix: &str) -> bool {
+    package
+        .strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
+
+/// Check if a package (or one of its dotted parents) matches a glob pattern
+///
+/// The only supported wildcard is `*`, matching any run of characters
+/// (including none).
+///
+/// # Examples
+///
+/// ```
+/// use py_import_helper::utils::categorization::matches_package_pattern;
+///
+/// assert!(matches_package_pattern("company_auth", "company_*"));
+/// assert!(matches_package_pattern("billing.internal.api", "*.internal"));
+/// assert!(!matches_package_pattern("companyx", "company_*"));
+/// ```
+#[must_use]
+pub fn matches_package_pattern(package: &str, pattern: &str) -> bool {
+    if glob_match(pattern, package) {
+        return true;
+    }
+
+    package
+        .match_indices('.')
+        .any(|(index, _)| glob_match(pattern, &package[..index]))
+}
+
+/// Match text against a pattern where `*` matches any run of characters
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == text[t] {
+            p += 1;
+            t += 1;
+        } else if let Some((star, matched)) = backtrack {
+            // Let the last `*` swallow one more character and retry
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
 /// Check if a package is part of Python's standard library
 #[must_use]
 pub fn is_standard_library_package(package: &str) -> bool {
@@ -87,6 +161,19 @@ mod tests {
         assert_eq!(category, ImportCategory::Future);
     }
 
+    #[test]
+    fn test_categorize_dunder_modules() {
+        let prefixes = HashSet::new();
+        assert_eq!(
+            categorize_import("from __main__ import app", &prefixes),
+            ImportCategory::StandardLibrary
+        );
+        assert_eq!(
+            categorize_import("from __futurex__ import thing", &prefixes),
+            ImportCategory::ThirdParty
+        );
+    }
+
     #[test]
     fn test_categorize_stdlib_import() {
         let prefixes = HashSet::new();
@@ -124,6 +211,27 @@ mod tests {
         assert!(!is_local_import("from typing import Any", &prefixes));
     }
 
+    #[test]
+    fn test_matches_package_prefix_dotted() {
+        assert!(matches_package_prefix("services.auth", "services.auth"));
+        assert!(matches_package_prefix(
+            "services.auth.handlers",
+            "services.auth"
+        ));
+        assert!(!matches_package_prefix("services.authz", "services.auth"));
+        assert!(!matches_package_prefix("services", "services.auth"));
+    }
+
+    #[test]
+    fn test_matches_package_pattern() {
+        assert!(matches_package_pattern("company_auth", "company_*"));
+        assert!(matches_package_pattern("company_auth.models", "company_*"));
+        assert!(!matches_package_pattern("companyx", "company_*"));
+        assert!(matches_package_pattern("billing.internal", "*.internal"));
+        assert!(!matches_package_pattern("billing.internals", "*.internal"));
+        assert!(matches_package_pattern("a.b.c", "a.*.c"));
+    }
+
     #[test]
     fn test_is_standard_library_package() {
         assert!(is_standard_library_package("typing"));
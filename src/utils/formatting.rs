@@ -3,115 +3,447 @@
 //! This module provides functions for formatting Python import statements
 //! according to PEP 8 and common formatting standards (isort, Black).
 
-use super::parsing::custom_import_sort;
-use crate::types::{FormattingConfig, ImportStatement};
-use std::collections::{HashMap, HashSet};
+use super::parsing::{custom_import_sort, parse_import, validate_import_statement};
+use crate::types::{
+    FormattingConfig, ImportCategory, ImportStatement, ImportType, MultilineStyle, SortMode,
+};
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 /// Format a list of imports, merging same-package imports where appropriate
 #[must_use]
 pub fn format_imports(imports: &[ImportStatement], config: &FormattingConfig) -> Vec<String> {
-    let mut package_imports: HashMap<String, Vec<&ImportStatement>> = HashMap::new();
+    merged_statements(imports, config)
+        .into_iter()
+        .flat_map(|(statement, comment)| {
+            let mut lines = statement.render(config);
+            if let (Some(first), Some(comment)) = (lines.first_mut(), comment) {
+                first.push_str("  # ");
+                first.push_str(comment);
+            }
+            lines
+        })
+        .collect()
+}
 
-    // Group imports by package
-    for import in imports {
+/// A statement produced by merging imports, not yet rendered into lines
+enum Merged<'a> {
+    /// An import kept as written, such as a direct import
+    Verbatim(&'a str),
+    /// A from import of already ordered items
+    From {
+        package: &'a str,
+        items: Vec<String>,
+    },
+}
+
+impl Merged<'_> {
+    /// Render the statement into its physical lines
+    fn render(&self, config: &FormattingConfig) -> Vec<String> {
+        match self {
+            Self::Verbatim(statement) => vec![(*statement).to_string()],
+            Self::From { package, items } => render_from_import(package, items, config),
+        }
+    }
+
+    /// Count the lines [`render`](Self::render) produces, without building them
+    fn line_count(&self, config: &FormattingConfig) -> usize {
+        match self {
+            Self::Verbatim(statement) => statement.lines().count(),
+            Self::From { package, items } => from_import_line_count(package, items, config),
+        }
+    }
+}
+
+/// Merge a list of imports into ordered statements, each with its trailing comment
+///
+/// Imports are grouped by package in module order; imports carrying a comment
+/// are not merged and follow the merged statements of their package.
+fn merged_statements<'a>(
+    imports: &'a [ImportStatement],
+    config: &FormattingConfig,
+) -> Vec<(Merged<'a>, Option<&'a str>)> {
+    let mut package_imports: HashMap<&str, Vec<&ImportStatement>> = HashMap::new();
+    let mut commented = commented_imports(imports);
+
+    // Group imports by package; commented imports keep their own line
+    for import in imports.iter().filter(|import| import.comment.is_none()) {
         package_imports
-            .entry(import.package.clone())
+            .entry(import.package.as_str())
             .or_default()
             .push(import);
     }
 
-    let mut result = Vec::new();
-    let mut packages: Vec<_> = package_imports.keys().collect();
-    packages.sort();
+    // Packages in sorted order
+    let mut packages: Vec<&str> = package_imports
+        .keys()
+        .copied()
+        .chain(commented.keys().copied())
+        .collect::<BTreeSet<&str>>()
+        .into_iter()
+        .collect();
+    if config.module_sort != SortMode::Lexicographic {
+        packages.sort_by(|a, b| config.module_sort.compare(a, b));
+    }
 
+    let mut result = Vec::new();
     for package in packages {
-        let imports_for_package = package_imports
-            .get(package)
-            .expect("BUG: package key must exist in HashMap");
-
-        if let Some(first) = imports_for_package.first() {
-            if imports_for_package.len() == 1 && first.items.is_empty() {
-                // Single direct import (e.g., "import os"), use as-is
-                result.push(first.statement.clone());
-            } else {
-                // Either multiple imports from same package, or a single import with items
-                // In both cases, apply formatting logic (may need multi-line)
-                result.extend(merge_package_imports(imports_for_package, config));
-            }
+        if let Some(imports_for_package) = package_imports.get(package) {
+            result.extend(
+                merge_statements(imports_for_package, config)
+                    .into_iter()
+                    .map(|statement| (statement, None)),
+            );
+        }
+        for import in commented.remove(package).unwrap_or_default() {
+            result.extend(
+                merge_statements(&[import], config)
+                    .into_iter()
+                    .map(|statement| (statement, import.comment.as_deref())),
+            );
         }
     }
-
     result
 }
 
+/// Group the imports carrying a trailing comment by package, dropping exact duplicates
+fn commented_imports(imports: &[ImportStatement]) -> HashMap<&str, Vec<&ImportStatement>> {
+    let mut seen = HashSet::new();
+    let mut commented: HashMap<&str, Vec<&ImportStatement>> = HashMap::new();
+    for import in imports {
+        if let Some(comment) = &import.comment {
+            if seen.insert((&import.statement, comment)) {
+                commented
+                    .entry(import.package.as_str())
+                    .or_default()
+                    .push(import);
+            }
+        }
+    }
+    commented
+}
+
 /// Merge multiple imports from the same package with configurable formatting
 #[must_use]
 pub fn merge_package_imports(
     imports: &[&ImportStatement],
     config: &FormattingConfig,
 ) -> Vec<String> {
-    let mut all_items = HashSet::new();
-    let package = &imports[0].package;
+    merge_statements(imports, config)
+        .iter()
+        .flat_map(|statement| statement.render(config))
+        .collect()
+}
+
+/// Merge the imports of one package into the statements to render
+fn merge_statements<'a>(
+    imports: &[&'a ImportStatement],
+    config: &FormattingConfig,
+) -> Vec<Merged<'a>> {
+    // Direct imports ("import os", "import numpy as np") are never merged,
+    // only deduplicated
+    if imports.iter().all(|i| i.import_type == ImportType::Direct) {
+        let unique: BTreeSet<&str> = imports.iter().map(|i| i.statement.as_str()).collect();
+        return unique.into_iter().map(Merged::Verbatim).collect();
+    }
 
-    // Collect all items being imported from this package
+    let mut seen = HashSet::new();
+    let mut sorted_items = Vec::new();
+    let package = imports[0].package.as_str();
+
+    // Collect all items being imported from this package, in declaration order.
+    // Items are keyed on their full "name as alias" form, so `Any` and
+    // `Any as AnyT` are distinct bindings while repeated identical items collapse.
     for import in imports {
-        all_items.extend(import.items.iter().cloned());
+        for item in &import.items {
+            let key = item_key(item, config);
+            if seen.insert(key.clone()) {
+                sorted_items.push(key);
+            }
+        }
     }
 
-    if all_items.is_empty() {
+    if sorted_items.is_empty() {
         // Simple "import package" statements
-        return imports.iter().map(|i| i.statement.clone()).collect();
+        return imports
+            .iter()
+            .map(|i| Merged::Verbatim(i.statement.as_str()))
+            .collect();
+    }
+
+    if !config.preserves_item_order(package) {
+        sorted_items.sort_by(|a, b| custom_import_sort(a, b));
+    }
+    if package == "__future__" {
+        // `annotations` leads the future group; the rest keep their sorted order
+        if let Some(index) = sorted_items.iter().position(|item| item == "annotations") {
+            let annotations = sorted_items.remove(index);
+            sorted_items.insert(0, annotations);
+        }
+    }
+
+    if config.combine_as_imports {
+        return vec![Merged::From {
+            package,
+            items: sorted_items,
+        }];
     }
 
-    let mut sorted_items: Vec<_> = all_items.into_iter().collect();
-    sorted_items.sort_by(|a, b| custom_import_sort(a, b));
+    // Plain items share one statement; each aliased item gets its own
+    let (aliased, plain): (Vec<String>, Vec<String>) = sorted_items
+        .into_iter()
+        .partition(|item| item.contains(" as "));
+    let mut result = Vec::new();
+    if !plain.is_empty() {
+        result.push(Merged::From {
+            package,
+            items: plain,
+        });
+    }
+    result.extend(aliased.into_iter().map(|item| Merged::From {
+        package,
+        items: vec![item],
+    }));
+    result
+}
+
+/// Render a single `from package import ...` statement for already ordered items
+fn render_from_import(package: &str, items: &[String], config: &FormattingConfig) -> Vec<String> {
+    if !should_use_multiline(package, items, config) {
+        // Single line
+        return vec![format!(
+            "from {} import {}",
+            package,
+            items.join(config.separator())
+        )];
+    }
+
+    let comma = if config.use_trailing_comma { "," } else { "" };
+    match config.multiline_style {
+        MultilineStyle::VerticalHangingIndent => {
+            // Multi-line with parentheses
+            let indent = config.indent();
+            let mut result = vec![format!("from {} import (", package)];
+            for item in items {
+                result.push(format!("{}{}{}", indent, item, comma));
+            }
+            result.push(")".to_string());
+            result
+        }
+        MultilineStyle::Vertical => {
+            // Items aligned after the opening paren, which is always spaces
+            let opening = format!("from {} import (", package);
+            let align = " ".repeat(opening.len());
+            let last = items.len() - 1;
+            items
+                .iter()
+                .enumerate()
+                .map(|(index, item)| {
+                    let prefix = if index == 0 { &opening } else { &align };
+                    if index == last {
+                        format!("{prefix}{item}{comma})")
+                    } else {
+                        format!("{prefix}{item},")
+                    }
+                })
+                .collect()
+        }
+        MultilineStyle::Backslash => {
+            // A trailing comma is a syntax error without parentheses
+            let indent = config.indent();
+            let last = items.len() - 1;
+            let mut result = vec![format!("from {} import \\", package)];
+            for (index, item) in items.iter().enumerate() {
+                if index == last {
+                    result.push(format!("{indent}{item}"));
+                } else {
+                    result.push(format!("{indent}{item}, \\"));
+                }
+            }
+            result
+        }
+    }
+}
+
+/// Normalize an imported item into the form used for deduplication and rendering
+///
+/// Whitespace is collapsed and, with `remove_redundant_aliases`, `y as y`
+/// becomes `y`.
+fn item_key(item: &str, config: &FormattingConfig) -> String {
+    let key = item.split_whitespace().collect::<Vec<_>>().join(" ");
+    match key.split_once(" as ") {
+        Some((name, alias)) if config.remove_redundant_aliases && name == alias => name.to_string(),
+        _ => key,
+    }
+}
 
-    // Determine if we should use multi-line format
-    let should_use_multiline = if config.force_multiline {
+/// Decide whether a merged from import is rendered in parenthesized multi-line form
+fn should_use_multiline(package: &str, items: &[String], config: &FormattingConfig) -> bool {
+    if config.force_multiline {
         true
     } else if config.force_single_line {
         false
     } else {
         // Auto-detect based on configuration
-        let total_chars = sorted_items.iter().map(String::len).sum::<usize>();
+        let total_chars = items.iter().map(String::len).sum::<usize>();
         let import_line_length = "from ".len()
             + package.len()
             + " import ".len()
             + total_chars
-            + (sorted_items.len() * 2);
+            + (items.len() * config.separator().len());
+
+        items.len() >= config.multiline_threshold || import_line_length > config.line_length
+    }
+}
+
+/// Count the lines [`format_imports`] would produce, without building them
+#[must_use]
+pub fn count_import_lines(imports: &[ImportStatement], config: &FormattingConfig) -> usize {
+    merged_statements(imports, config)
+        .iter()
+        .map(|(statement, _)| statement.line_count(config))
+        .sum()
+}
+
+/// Count the lines [`render_from_import`] produces for the same arguments
+fn from_import_line_count(package: &str, items: &[String], config: &FormattingConfig) -> usize {
+    if !should_use_multiline(package, items, config) {
+        return 1;
+    }
+    match config.multiline_style {
+        MultilineStyle::VerticalHangingIndent => items.len() + 2,
+        MultilineStyle::Vertical => items.len(),
+        MultilineStyle::Backslash => items.len() + 1,
+    }
+}
 
-        sorted_items.len() >= config.multiline_threshold || import_line_length > config.line_length
-    };
+/// Get the first-letter bucket of a package for [`insert_alpha_subheaders`]
+pub(crate) fn alpha_bucket(package: &str) -> Option<char> {
+    package
+        .trim_start_matches('.')
+        .chars()
+        .next()
+        .filter(char::is_ascii_alphabetic)
+        .map(|letter| letter.to_ascii_uppercase())
+}
 
-    if should_use_multiline {
-        // Multi-line with parentheses
-        let indent = " ".repeat(config.indent_size);
-        let mut result = vec![format!("from {} import (", package)];
+/// Insert a `# X` comment line before each first-letter bucket of imports
+///
+/// Buckets are keyed on the uppercased first letter of the imported package
+/// (leading dots of relative imports are ignored). Imports are first moved
+/// next to the others of their bucket, keeping their order otherwise, so each
+/// header appears once: `import os`, `import sys`, `from os import path`
+/// becomes `# O` with both `os` imports, then `# S`. Imports without a letter
+/// bucket come first. Continuation lines of multi-line imports stay with
+/// their statement.
+///
+/// # Examples
+///
+/// ```
+/// use py_import_helper::utils::formatting::insert_alpha_subheaders;
+///
+/// let lines = vec!["import attrs".to_string(), "import boto3".to_string()];
+/// assert_eq!(
+///     insert_alpha_subheaders(lines),
+///     vec!["# A", "import attrs", "# B", "import boto3"]
+/// );
+/// ```
+#[must_use]
+pub fn insert_alpha_subheaders(lines: Vec<String>) -> Vec<String> {
+    // Split into statements, each with the continuation lines that follow it
+    let mut statements: Vec<(Option<char>, Vec<String>)> = Vec::new();
+    for line in lines {
+        let package = line
+            .strip_prefix("import ")
+            .or_else(|| line.strip_prefix("from "));
+        match (package, statements.last_mut()) {
+            (None, Some((_, statement))) => statement.push(line),
+            _ => statements.push((package.and_then(alpha_bucket), vec![line])),
+        }
+    }
+    statements.sort_by_key(|(letter, _)| *letter);
 
-        for item in &sorted_items {
-            if config.use_trailing_comma {
-                result.push(format!("{}{},", indent, item));
-            } else {
-                result.push(format!("{}{}", indent, item));
+    let mut result = Vec::new();
+    let mut current: Option<char> = None;
+    for (letter, lines) in statements {
+        if let Some(letter) = letter {
+            if current != Some(letter) {
+                result.push(format!("# {letter}"));
+                current = Some(letter);
             }
         }
+        result.extend(lines);
+    }
+    result
+}
 
-        result.push(")".to_string());
-        result
-    } else {
-        // Single line
-        vec![format!(
-            "from {} import {}",
-            package,
-            sorted_items.join(", ")
-        )]
+/// Canonicalize a single import statement without an [`ImportHelper`](crate::ImportHelper)
+///
+/// The statement is parsed, its items are sorted and it is re-rendered in
+/// single- or multi-line form according to `config`. Multi-line results are
+/// joined with `\n`. Returns `None` if the statement is not a valid import.
+///
+/// # Examples
+///
+/// ```
+/// use py_import_helper::{normalize_statement, FormattingConfig};
+///
+/// let config = FormattingConfig::default();
+/// assert_eq!(
+///     normalize_statement("from typing import Optional,Any", &config),
+///     Some("from typing import Any, Optional".to_string())
+/// );
+/// assert_eq!(normalize_statement("form typing import Any", &config), None);
+/// ```
+#[must_use]
+pub fn normalize_statement(statement: &str, config: &FormattingConfig) -> Option<String> {
+    validate_import_statement(statement).ok()?;
+    // The category does not affect how a single statement is rendered
+    let import = parse_import(statement, ImportCategory::ThirdParty)?;
+
+    if import.import_type == ImportType::Direct {
+        return Some(
+            import
+                .statement
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" "),
+        );
     }
+    Some(merge_package_imports(&[&import], config).join("\n"))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::{ImportCategory, ImportType};
+    use crate::types::ImportCategory;
+
+    /// Build a from import as the parser would; formatting ignores the category
+    fn from_import(package: &str, items: &[&str]) -> ImportStatement {
+        ImportStatement {
+            statement: format!("from {package} import {}", items.join(", ")),
+            category: ImportCategory::ThirdParty,
+            import_type: ImportType::From,
+            package: package.to_string(),
+            items: items.iter().map(|item| (*item).to_string()).collect(),
+            is_multiline: false,
+            comment: None,
+            auto_added: false,
+        }
+    }
+
+    /// Build a direct import as the parser would; formatting ignores the category
+    fn direct_import(package: &str) -> ImportStatement {
+        ImportStatement {
+            statement: format!("import {package}"),
+            category: ImportCategory::ThirdParty,
+            import_type: ImportType::Direct,
+            package: package.to_string(),
+            items: vec![package.to_string()],
+            is_multiline: false,
+            comment: None,
+            auto_added: false,
+        }
+    }
 
     #[test]
     fn test_merge_package_imports() {
@@ -122,6 +454,8 @@ mod tests {
             package: "typing".to_string(),
             items: vec!["Any".to_string()],
             is_multiline: false,
+            comment: None,
+            auto_added: false,
         };
 
         let import2 = ImportStatement {
@@ -131,6 +465,8 @@ mod tests {
             package: "typing".to_string(),
             items: vec!["Optional".to_string()],
             is_multiline: false,
+            comment: None,
+            auto_added: false,
         };
 
         let config = FormattingConfig::default();
@@ -139,4 +475,280 @@ mod tests {
         assert!(merged[0].contains("Any"));
         assert!(merged[0].contains("Optional"));
     }
+
+    #[test]
+    fn test_merge_preserves_item_order_for_configured_package() {
+        let import = from_import("enum_lib", &["Zeta", "Alpha", "Mid"]);
+
+        let mut config = FormattingConfig::default();
+        assert_eq!(
+            merge_package_imports(&[&import], &config),
+            vec!["from enum_lib import Alpha, Mid, Zeta"]
+        );
+
+        config
+            .preserve_item_order_packages
+            .insert("enum_lib".to_string());
+        assert_eq!(
+            merge_package_imports(&[&import], &config),
+            vec!["from enum_lib import Zeta, Alpha, Mid"]
+        );
+    }
+
+    #[test]
+    fn test_merge_direct_imports_deduplicates() {
+        let direct = direct_import("os");
+
+        let config = FormattingConfig::default();
+        let merged = merge_package_imports(&[&direct, &direct], &config);
+        assert_eq!(merged, vec!["import os"]);
+    }
+
+    #[test]
+    fn test_merge_aliased_items() {
+        let from_typing = |items: &[&str]| from_import("typing", items);
+        let config = FormattingConfig::default();
+
+        // Identical aliased items collapse, even with irregular spacing
+        let aliased = from_typing(&["Any as AnyT"]);
+        let spaced = from_typing(&["Any  as  AnyT"]);
+        assert_eq!(
+            merge_package_imports(&[&aliased, &spaced], &config),
+            vec!["from typing import Any as AnyT"]
+        );
+
+        // A plain and an aliased import of the same name are distinct bindings
+        let plain = from_typing(&["Any"]);
+        assert_eq!(
+            merge_package_imports(&[&plain, &aliased, &plain], &config),
+            vec!["from typing import Any, Any as AnyT"]
+        );
+    }
+
+    #[test]
+    fn test_normalize_statement() {
+        let config = FormattingConfig::default();
+
+        assert_eq!(
+            normalize_statement("from typing import Optional, Any, TYPE_CHECKING", &config),
+            Some("from typing import TYPE_CHECKING, Any, Optional".to_string())
+        );
+        assert_eq!(
+            normalize_statement("  import   numpy  as np ", &config),
+            Some("import numpy as np".to_string())
+        );
+        assert_eq!(
+            normalize_statement("from typing import (\n    Optional,\n    Any,\n)", &config),
+            Some("from typing import Any, Optional".to_string())
+        );
+
+        let multiline = FormattingConfig {
+            force_multiline: true,
+            ..FormattingConfig::default()
+        };
+        assert_eq!(
+            normalize_statement("from typing import Optional, Any", &multiline),
+            Some("from typing import (\n    Any,\n    Optional,\n)".to_string())
+        );
+
+        assert_eq!(normalize_statement("import", &config), None);
+        assert_eq!(normalize_statement("x = 1", &config), None);
+    }
+
+    #[test]
+    fn test_insert_alpha_subheaders() {
+        let lines: Vec<String> = [
+            "import attrs",
+            "import boto3",
+            "from botocore import (",
+            "    Config,",
+            ")",
+            "from .models import User",
+        ]
+        .iter()
+        .map(|line| (*line).to_string())
+        .collect();
+
+        assert_eq!(
+            insert_alpha_subheaders(lines),
+            vec![
+                "# A",
+                "import attrs",
+                "# B",
+                "import boto3",
+                "from botocore import (",
+                "    Config,",
+                ")",
+                "# M",
+                "from .models import User",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_future_imports_annotations_first() {
+        let future = |item: &str| from_import("__future__", &[item]);
+        let imports = [
+            future("division"),
+            future("annotations"),
+            future("absolute_import"),
+        ];
+        let refs: Vec<&ImportStatement> = imports.iter().collect();
+
+        assert_eq!(
+            merge_package_imports(&refs, &FormattingConfig::default()),
+            vec!["from __future__ import annotations, absolute_import, division"]
+        );
+    }
+
+    #[test]
+    fn test_remove_redundant_aliases() {
+        let import = from_import("x", &["y as y", "z as w"]);
+
+        let mut config = FormattingConfig::default();
+        assert_eq!(
+            merge_package_imports(&[&import], &config),
+            vec!["from x import y as y, z as w"]
+        );
+
+        config.remove_redundant_aliases = true;
+        assert_eq!(
+            merge_package_imports(&[&import], &config),
+            vec!["from x import y, z as w"]
+        );
+    }
+
+    #[test]
+    fn test_combine_as_imports() {
+        let from = |items: &[&str]| from_import("x", items);
+        let first = from(&["a as b", "e"]);
+        let second = from(&["c as d"]);
+        let statements = [first.clone(), second.clone()];
+        let imports = [&first, &second];
+
+        let mut config = FormattingConfig::default();
+        assert_eq!(
+            merge_package_imports(&imports, &config),
+            vec!["from x import a as b, c as d, e"]
+        );
+        assert_eq!(count_import_lines(&statements, &config), 1);
+
+        config.combine_as_imports = false;
+        assert_eq!(
+            merge_package_imports(&imports, &config),
+            vec![
+                "from x import e",
+                "from x import a as b",
+                "from x import c as d",
+            ]
+        );
+        assert_eq!(count_import_lines(&statements, &config), 3);
+    }
+
+    #[test]
+    fn test_multiline_with_tabs() {
+        let import = from_import("typing", &["Any", "Dict", "List", "Optional"]);
+        let config = FormattingConfig {
+            use_tabs: true,
+            ..FormattingConfig::default()
+        };
+
+        assert_eq!(
+            merge_package_imports(&[&import], &config),
+            vec![
+                "from typing import (",
+                "\tAny,",
+                "\tDict,",
+                "\tList,",
+                "\tOptional,",
+                ")",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_multiline_styles() {
+        let imports = vec![from_import("package", &["alpha", "beta", "gamma"])];
+        let mut config = FormattingConfig {
+            force_multiline: true,
+            ..FormattingConfig::default()
+        };
+
+        let expected = vec![
+            "from package import (",
+            "    alpha,",
+            "    beta,",
+            "    gamma,",
+            ")",
+        ];
+        assert_eq!(format_imports(&imports, &config), expected);
+        assert_eq!(count_import_lines(&imports, &config), expected.len());
+
+        config.multiline_style = MultilineStyle::Vertical;
+        let expected = vec![
+            "from package import (alpha,",
+            "                     beta,",
+            "                     gamma,)",
+        ];
+        assert_eq!(format_imports(&imports, &config), expected);
+        assert_eq!(count_import_lines(&imports, &config), expected.len());
+
+        config.use_trailing_comma = false;
+        assert_eq!(
+            format_imports(&imports, &config),
+            vec![
+                "from package import (alpha,",
+                "                     beta,",
+                "                     gamma)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_backslash_multiline_style() {
+        let imports = vec![from_import("package", &["alpha", "beta", "gamma"])];
+        let config = FormattingConfig {
+            force_multiline: true,
+            multiline_style: MultilineStyle::Backslash,
+            ..FormattingConfig::default()
+        };
+
+        let expected = vec![
+            "from package import \\",
+            "    alpha, \\",
+            "    beta, \\",
+            "    gamma",
+        ];
+        assert_eq!(format_imports(&imports, &config), expected);
+        assert_eq!(count_import_lines(&imports, &config), expected.len());
+    }
+
+    #[test]
+    fn test_item_separator() {
+        let imports = vec![from_import("typing", &["Any", "Optional"])];
+        let mut config = FormattingConfig {
+            item_separator: ",".to_string(),
+            ..FormattingConfig::default()
+        };
+        assert_eq!(
+            format_imports(&imports, &config),
+            vec!["from typing import Any,Optional"]
+        );
+
+        config.item_separator = " , ".to_string();
+        assert_eq!(
+            format_imports(&imports, &config),
+            vec!["from typing import Any , Optional"]
+        );
+
+        // Anything but a single comma with spaces falls back to the default
+        for invalid in [" ", ",,", ";,", ",\n"] {
+            config.item_separator = invalid.to_string();
+            assert_eq!(config.separator(), ", ");
+        }
+        assert_eq!(
+            format_imports(&imports, &config),
+            vec!["from typing import Any, Optional"]
+        );
+    }
 }
@@ -9,5 +9,5 @@ pub mod parsing;
 
 // Re-export commonly used functions
 pub use categorization::{categorize_import, is_local_import};
-pub use formatting::{format_imports, merge_package_imports};
+pub use formatting::{format_imports, merge_package_imports, normalize_statement};
 pub use parsing::{custom_import_sort, extract_items, extract_package};
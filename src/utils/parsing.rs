@@ -3,7 +3,13 @@
 //! This module provides functions for parsing Python import statements
 //! and extracting relevant information such as package names and imported items.
 
-use crate::types::{ImportCategory, ImportStatement, ImportType};
+use super::categorization::is_local_import;
+use crate::registry::PackageRegistry;
+use crate::types::{ImportCategory, ImportParseError, ImportStatement, ImportType, SourceImport};
+use std::borrow::Cow;
+use std::collections::{HashSet, VecDeque};
+use std::io::BufRead;
+use std::ops::Range;
 
 /// Extract the package name from an import statement
 ///
@@ -18,31 +24,47 @@ use crate::types::{ImportCategory, ImportStatement, ImportType};
 /// ```
 #[must_use]
 pub fn extract_package(import_statement: &str) -> String {
+    package_of(import_statement).to_string()
+}
+
+/// Check whether a statement is a `from __future__ import ...` import
+///
+/// The package must be exactly `__future__`; other dunder modules such as
+/// `__main__` are regular imports.
+pub(crate) fn is_future_import(import_statement: &str) -> bool {
+    import_statement
+        .strip_prefix("from ")
+        .and_then(|rest| rest.split_once(" import "))
+        .is_some_and(|(package, _)| package.trim() == "__future__")
+}
+
+/// Borrowing variant of [`extract_package`] for hot paths that only need a lookup key
+pub(crate) fn package_of(import_statement: &str) -> &str {
     if let Some(from_part) = import_statement.strip_prefix("from ") {
         // Use split_once for Unicode-safe splitting
         if let Some((package, _)) = from_part.split_once(" import ") {
             let pkg = package.trim();
             // Validate non-empty package
             if pkg.is_empty() {
-                return import_statement.to_string();
+                return import_statement;
             }
-            return pkg.to_string();
+            return pkg;
         }
     } else if let Some(import_part) = import_statement.strip_prefix("import ") {
-        // For direct imports, return the full module path
+        // For direct imports, return the full module path of the first module
         let pkg = import_part
-            .split_whitespace()
+            .split(|c: char| c.is_whitespace() || c == ',')
             .next()
             .unwrap_or(import_part)
             .trim();
         // Validate non-empty package
         if pkg.is_empty() {
-            return import_statement.to_string();
+            return import_statement;
         }
-        return pkg.to_string();
+        return pkg;
     }
 
-    import_statement.to_string()
+    import_statement
 }
 
 /// Extract imported items from an import statement
@@ -62,26 +84,36 @@ pub fn extract_package(import_statement: &str) -> String {
 /// ```
 #[must_use]
 pub fn extract_items(import_statement: &str) -> Vec<String> {
+    let mut items = extract_items_in_order(import_statement);
+    if import_statement.starts_with("from ") {
+        // Sort items with ALL_CAPS first, then mixed case alphabetically
+        items.sort_by(|a, b| custom_import_sort(a, b));
+    }
+    items
+}
+
+/// Extract imported items from an import statement, keeping declaration order
+///
+/// # Examples
+///
+/// ```
+/// use py_import_helper::utils::parsing::extract_items_in_order;
+///
+/// let items = extract_items_in_order("from typing import Optional, Any");
+/// assert_eq!(items, vec!["Optional", "Any"]);
+/// ```
+#[must_use]
+pub fn extract_items_in_order(import_statement: &str) -> Vec<String> {
     if let Some(from_part) = import_statement.strip_prefix("from ") {
         // Use split_once for Unicode-safe splitting
         if let Some((_, items_part)) = from_part.split_once(" import ") {
-            // Unicode-safe character replacement in single pass
-            let cleaned: String = items_part
-                .chars()
-                .map(|c| match c {
-                    '(' | ')' | ',' => ' ',
-                    _ => c,
-                })
-                .collect();
-            let mut items: Vec<String> = cleaned
-                .split_whitespace()
-                .map(|s| s.trim().to_string())
+            // Split on commas and parentheses, collapsing whitespace so that
+            // aliased items ("name as alias") stay together
+            return strip_item_comments(items_part)
+                .split(['(', ')', ','])
+                .map(|s| s.split_whitespace().collect::<Vec<_>>().join(" "))
                 .filter(|s| !s.is_empty())
                 .collect();
-
-            // Sort items with ALL_CAPS first, then mixed case alphabetically
-            items.sort_by(|a, b| custom_import_sort(a, b));
-            return items;
         }
     } else if let Some(import_part) = import_statement.strip_prefix("import ") {
         // For direct imports, the "item" is the module itself
@@ -90,6 +122,52 @@ pub fn extract_items(import_statement: &str) -> Vec<String> {
     Vec::new()
 }
 
+/// Natural ordering: runs of digits compare by numeric value, everything else by character
+///
+/// # Examples
+///
+/// ```
+/// use py_import_helper::utils::parsing::natural_cmp;
+/// use std::cmp::Ordering;
+///
+/// assert_eq!(natural_cmp("module2", "module10"), Ordering::Less);
+/// assert_eq!(natural_cmp("v1.10", "v1.9"), Ordering::Greater);
+/// ```
+#[must_use]
+pub fn natural_cmp(a: &str, b: &str) -> std::cmp::Ordering {
+    let digits_end = |s: &str| s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (mut a_rest, mut b_rest) = (a, b);
+    loop {
+        match (a_rest.chars().next(), b_rest.chars().next()) {
+            // Equal up to leading zeros; fall back to a plain comparison for a total order
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return std::cmp::Ordering::Less,
+            (Some(_), None) => return std::cmp::Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let (a_end, b_end) = (digits_end(a_rest), digits_end(b_rest));
+                let a_number = a_rest[..a_end].trim_start_matches('0');
+                let b_number = b_rest[..b_end].trim_start_matches('0');
+                let ordering = a_number
+                    .len()
+                    .cmp(&b_number.len())
+                    .then_with(|| a_number.cmp(b_number));
+                if ordering.is_ne() {
+                    return ordering;
+                }
+                a_rest = &a_rest[a_end..];
+                b_rest = &b_rest[b_end..];
+            }
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return x.cmp(&y);
+                }
+                a_rest = &a_rest[x.len_utf8()..];
+                b_rest = &b_rest[y.len_utf8()..];
+            }
+        }
+    }
+}
+
 /// Custom sorting for import items: `ALL_CAPS` first (alphabetically), then mixed case (alphabetically)
 ///
 /// This follows the convention used by isort and Black formatters.
@@ -133,14 +211,900 @@ pub fn custom_import_sort(a: &str, b: &str) -> std::cmp::Ordering {
     }
 }
 
+/// Collect import statements from Python source code
+///
+/// Returns each top-level import as a single-line statement, flagged when it
+/// appeared inside an `if TYPE_CHECKING:` block. Parenthesized multi-line
+/// and backslash-continued imports are joined into one line. Version-gated blocks such as
+/// `if sys.version_info >= (3, 11):` that only contain imports are returned
+/// verbatim as [`SourceImport::ConditionalBlock`], and imports between Black's
+/// `# fmt: off` / `# fmt: on` fences as [`SourceImport::Fenced`]. All other
+/// code is ignored, including the contents of triple-quoted strings such as
+/// the module docstring.
+///
+/// # Examples
+///
+/// ```
+/// use py_import_helper::types::SourceImport;
+/// use py_import_helper::utils::parsing::collect_import_statements;
+///
+/// let source = "import os\nfrom typing import (\n    Any,\n    Optional,\n)\n";
+/// let imports = collect_import_statements(source);
+/// assert_eq!(
+///     imports[1],
+///     SourceImport::Statement {
+///         statement: "from typing import Any, Optional".to_string(),
+///         type_checking: false,
+///     }
+/// );
+/// ```
+#[must_use]
+pub fn collect_import_statements(source: &str) -> Vec<SourceImport> {
+    let mut result = Vec::new();
+    let mut in_type_checking = false;
+    let mut pending: Option<String> = None;
+    let mut conditional: Option<Vec<String>> = None;
+    let mut fenced: Option<Vec<String>> = None;
+    let mut continued: Option<String> = None;
+    let mut string: Option<&str> = None;
+
+    for raw_line in source.lines() {
+        // Everything up to `# fmt: on` is kept exactly as written
+        if let Some(block) = fenced.as_mut() {
+            block.push(raw_line.trim_end().to_string());
+            if raw_line.trim() == "# fmt: on" {
+                result.extend(finish_fenced_block(fenced.take()));
+            }
+            continue;
+        }
+
+        // The lines of a triple-quoted string are text, not code
+        if let Some(delimiter) = string {
+            if raw_line.contains(delimiter) {
+                string = None;
+            }
+            continue;
+        }
+        if pending.is_none() && continued.is_none() {
+            string = opened_string(raw_line);
+        }
+
+        // Join backslash-continued lines into one logical line
+        let line: Cow<str> = match continued.take() {
+            Some(head) => Cow::Owned(format!("{head} {}", raw_line.trim())),
+            None => Cow::Borrowed(raw_line),
+        };
+        if pending.is_none() && !line.trim_start().starts_with('#') {
+            if let Some(head) = line.trim_end().strip_suffix('\\') {
+                continued = Some(head.trim_end().to_string());
+                continue;
+            }
+        }
+        let line = line.as_ref();
+        let trimmed = line.trim();
+
+        // Continue a parenthesized multi-line import
+        if let Some(buffer) = pending.as_mut() {
+            buffer.push('\n');
+            buffer.push_str(line.trim_end());
+            if trimmed.split('#').next().unwrap_or_default().contains(')') {
+                result.extend(classify_import(buffer, in_type_checking));
+                pending = None;
+            }
+            continue;
+        }
+
+        // Continue a version-gated block until the next unindented statement
+        if let Some(block) = conditional.as_mut() {
+            let continues = trimmed.is_empty()
+                || line.starts_with(char::is_whitespace)
+                || trimmed.starts_with("elif ")
+                || trimmed == "else:";
+            if continues {
+                block.push(line.trim_end().to_string());
+                continue;
+            }
+            result.extend(finish_conditional_block(conditional.take()));
+        }
+
+        if trimmed == "# fmt: off" && !line.starts_with(char::is_whitespace) {
+            in_type_checking = false;
+            fenced = Some(vec![trimmed.to_string()]);
+            continue;
+        }
+
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        // Any unindented line ends a TYPE_CHECKING block (or starts a new one)
+        if !line.starts_with(char::is_whitespace) {
+            if is_version_guard(trimmed) {
+                in_type_checking = false;
+                conditional = Some(vec![line.trim_end().to_string()]);
+                continue;
+            }
+            in_type_checking = is_type_checking_guard(trimmed);
+            if in_type_checking {
+                continue;
+            }
+        } else if !in_type_checking {
+            // Indented imports outside TYPE_CHECKING are function-local
+            continue;
+        }
+
+        // `import os; import sys; x = 1` holds several statements; only imports are kept
+        for segment in split_statements(trimmed) {
+            if !is_import_segment(segment) {
+                continue;
+            }
+
+            let code = segment.split('#').next().unwrap_or_default();
+            if code.contains('(') && !code.contains(')') {
+                pending = Some(segment.to_string());
+            } else {
+                result.extend(classify_import(segment, in_type_checking));
+            }
+        }
+    }
+
+    result.extend(finish_conditional_block(conditional));
+    result.extend(finish_fenced_block(fenced));
+    result
+}
+
+/// Parse the import statements of Python source read line by line
+///
+/// Only the lines of the statement being parsed are buffered, so huge files
+/// can be processed without loading them. Parenthesized multi-line and
+/// backslash-continued imports are joined, and lines holding several
+/// statements (`import os; import sys`) yield each import. Imports anywhere
+/// in the source are yielded, including indented ones, with their trailing
+/// comment. They are categorized without an [`ImportHelper`](crate::ImportHelper),
+/// using the default [`PackageRegistry`] (submodules such as `os.path` included)
+/// and treating only relative imports as local.
+///
+/// Malformed imports yield an error and parsing continues with the next
+/// line; a read error is yielded once and ends the iteration.
+///
+/// # Examples
+///
+/// ```
+/// use py_import_helper::{parse_imports, ImportCategory};
+/// use std::io::Cursor;
+///
+/// let source = "import os\nfrom typing import (\n    Any,\n)\nprint(os.name)\n";
+/// let imports: Vec<_> = parse_imports(Cursor::new(source))
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+/// assert_eq!(imports.len(), 2);
+/// assert_eq!(imports[1].statement, "from typing import Any");
+/// assert_eq!(imports[1].category, ImportCategory::StandardLibrary);
+/// ```
+pub fn parse_imports<R: BufRead>(
+    reader: R,
+) -> impl Iterator<Item = Result<ImportStatement, ImportParseError>> {
+    let registry = PackageRegistry::new();
+    let mut lines = reader.lines();
+    let mut parsed: VecDeque<Result<ImportStatement, ImportParseError>> = VecDeque::new();
+    let mut failed = false;
+
+    std::iter::from_fn(move || loop {
+        if let Some(result) = parsed.pop_front() {
+            return Some(result);
+        }
+        if failed {
+            return None;
+        }
+
+        let mut next_line = || match lines.next()? {
+            Ok(line) => Some(Ok(line)),
+            Err(error) => Some(Err(ImportParseError::InvalidStatement {
+                message: format!("failed to read source: {error}"),
+                span: None,
+            })),
+        };
+        let mut line = match next_line()? {
+            Ok(line) => line,
+            Err(error) => {
+                failed = true;
+                return Some(Err(error));
+            }
+        };
+
+        // Join backslash-continued lines into one logical line
+        while !line.trim_start().starts_with('#') {
+            let Some(head) = line.trim_end().strip_suffix('\\') else {
+                break;
+            };
+            let head = head.trim_end().to_string();
+            match next_line() {
+                Some(Ok(next)) => line = format!("{head} {}", next.trim()),
+                Some(Err(error)) => {
+                    failed = true;
+                    return Some(Err(error));
+                }
+                None => line = head,
+            }
+        }
+
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        for segment in split_statements(trimmed) {
+            if !is_import_segment(segment) {
+                continue;
+            }
+
+            let code = segment.split('#').next().unwrap_or_default();
+            if !code.contains('(') || code.contains(')') {
+                parsed.push_back(parse_source_statement(segment, &registry));
+                continue;
+            }
+
+            // Buffer a parenthesized import up to its closing parenthesis
+            let mut buffer = segment.to_string();
+            loop {
+                match next_line() {
+                    Some(Ok(next)) => {
+                        buffer.push('\n');
+                        buffer.push_str(next.trim_end());
+                        if next.split('#').next().unwrap_or_default().contains(')') {
+                            parsed.push_back(parse_source_statement(&buffer, &registry));
+                            break;
+                        }
+                    }
+                    Some(Err(error)) => {
+                        failed = true;
+                        parsed.push_back(Err(error));
+                        break;
+                    }
+                    None => {
+                        parsed.push_back(Err(ImportParseError::InvalidStatement {
+                            message: "unterminated parenthesized import".to_string(),
+                            span: None,
+                        }));
+                        break;
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Parse one (possibly parenthesized) import statement found in source code
+fn parse_source_statement(
+    original: &str,
+    registry: &PackageRegistry,
+) -> Result<ImportStatement, ImportParseError> {
+    let (code, comment) = split_trailing_comment(original);
+    let statement = join_import_lines(code);
+    validate_import_statement(&statement)?;
+
+    let category = if is_future_import(&statement) {
+        ImportCategory::Future
+    } else if is_local_import(&statement, &HashSet::<String>::new()) {
+        ImportCategory::Local
+    } else {
+        registry
+            .contains(&extract_package(&statement))
+            .unwrap_or(ImportCategory::ThirdParty)
+    };
+    let statement = match comment {
+        Some(comment) => format!("{statement}  # {comment}"),
+        None => statement,
+    };
+    parse_import(&statement, category).ok_or_else(|| ImportParseError::InvalidStatement {
+        message: format!("unsupported import statement '{statement}'"),
+        span: None,
+    })
+}
+
+/// Locate the top-of-file import block in Python source code
+///
+/// Returns the byte range from the first top-level import to the end of the
+/// last line that belongs to the block: imports (parenthesized or
+/// backslash-continued), `if TYPE_CHECKING:` and `if sys.version_info ...:`
+/// blocks, the comments and blank lines between them, and the `# fmt: on`
+/// closing a `# fmt: off` fence opened in the block. Leading comments
+/// (shebang, encoding, license header) and the module docstring are skipped.
+/// Without an import block, the empty range marks where one belongs: after the
+/// docstring, or after the leading comments when there is none.
+///
+/// # Examples
+///
+/// ```
+/// use py_import_helper::utils::parsing::locate_import_block;
+///
+/// let source = "\"\"\"Docs.\"\"\"\n\nimport os\nimport sys\n\nprint(os.name)\n";
+/// let block = locate_import_block(source);
+/// assert_eq!(&source[block], "import os\nimport sys\n");
+///
+/// assert_eq!(locate_import_block("\"\"\"Docs.\"\"\"\nx = 1\n"), 12..12);
+/// ```
+#[must_use]
+pub fn locate_import_block(source: &str) -> Range<usize> {
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for line in source.split_inclusive('\n') {
+        lines.push((offset, line));
+        offset += line.len();
+    }
+    let end_of = |index: usize| lines[index].0 + lines[index].1.len();
+    let is_trivia = |line: &str| {
+        let trimmed = line.trim();
+        trimmed.is_empty() || trimmed.starts_with('#')
+    };
+
+    // Leading comments such as a shebang or license header
+    let mut index = 0;
+    let mut insert_at = 0;
+    while index < lines.len() && is_trivia(lines[index].1) {
+        if !lines[index].1.trim().is_empty() {
+            insert_at = end_of(index);
+        }
+        index += 1;
+    }
+
+    if let Some(last) = docstring_end(&lines, index) {
+        insert_at = end_of(last);
+        index = last + 1;
+    }
+    while index < lines.len() && is_trivia(lines[index].1) {
+        index += 1;
+    }
+
+    let starts_block = |line: &str| {
+        let trimmed = line.trim();
+        !line.starts_with(char::is_whitespace)
+            && (is_import_segment(trimmed)
+                || is_type_checking_guard(trimmed)
+                || is_version_guard(trimmed))
+    };
+    if index >= lines.len() || !starts_block(lines[index].1) {
+        return insert_at..insert_at;
+    }
+
+    let start = lines[index].0;
+    let mut end = start;
+    let mut in_guard = false;
+    let mut fenced = false;
+    while index < lines.len() {
+        let (_, line) = lines[index];
+        let trimmed = line.trim();
+        if is_trivia(line) {
+            // The `# fmt: on` closing a fence within the block belongs to it
+            match trimmed {
+                "# fmt: off" => fenced = true,
+                "# fmt: on" if fenced => {
+                    fenced = false;
+                    end = end_of(index);
+                }
+                _ => {}
+            }
+            index += 1;
+            continue;
+        }
+
+        if line.starts_with(char::is_whitespace) {
+            if !in_guard {
+                break;
+            }
+        } else if is_import_segment(trimmed) {
+            in_guard = false;
+        } else if is_type_checking_guard(trimmed) || is_version_guard(trimmed) {
+            in_guard = true;
+        } else if !(in_guard && (trimmed.starts_with("elif ") || trimmed == "else:")) {
+            break;
+        }
+
+        let last = continuation_end(&lines, index);
+        end = end_of(last);
+        index = last + 1;
+    }
+    start..end
+}
+
+/// Get the index of the last line of a module docstring starting at `index`
+fn docstring_end(lines: &[(usize, &str)], index: usize) -> Option<usize> {
+    let (_, line) = lines.get(index)?;
+    let text = line.trim().trim_start_matches(['r', 'R', 'u', 'U']);
+
+    let Some(delimiter) = ["\"\"\"", "'''"]
+        .into_iter()
+        .find(|delimiter| text.starts_with(delimiter))
+    else {
+        // A single-quoted docstring fits on one line
+        return (text.starts_with('"') || text.starts_with('\'')).then_some(index);
+    };
+
+    if text[3..].contains(delimiter) {
+        return Some(index);
+    }
+    let closing = lines[index + 1..]
+        .iter()
+        .position(|(_, line)| line.contains(delimiter));
+    Some(closing.map_or(lines.len() - 1, |position| index + 1 + position))
+}
+
+/// Get the index of the last physical line of the statement starting at `index`
+fn continuation_end(lines: &[(usize, &str)], index: usize) -> usize {
+    let code = |index: usize| {
+        lines[index]
+            .1
+            .split('#')
+            .next()
+            .unwrap_or_default()
+            .trim_end()
+    };
+
+    let mut last = index;
+    if code(index).contains('(') && !code(index).contains(')') {
+        while last + 1 < lines.len() {
+            last += 1;
+            if code(last).contains(')') {
+                break;
+            }
+        }
+    } else {
+        while code(last).ends_with('\\') && last + 1 < lines.len() {
+            last += 1;
+        }
+    }
+    last
+}
+
+/// Split a line into the statements separated by top-level semicolons
+///
+/// Semicolons inside string literals, brackets or a trailing comment do not
+/// split; the comment stays attached to the last segment. Segments are trimmed
+/// and empty segments are dropped.
+///
+/// # Examples
+///
+/// ```
+/// use py_import_helper::utils::parsing::split_statements;
+///
+/// assert_eq!(
+///     split_statements("import os; import sys; x = 1"),
+///     vec!["import os", "import sys", "x = 1"]
+/// );
+/// ```
+#[must_use]
+pub fn split_statements(line: &str) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    let mut start = 0;
+
+    for (index, ch) in line.char_indices() {
+        match quote {
+            Some(open) if ch == open => quote = None,
+            Some(_) => {}
+            None => match ch {
+                '\'' | '"' => quote = Some(ch),
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth = depth.saturating_sub(1),
+                ';' if depth == 0 => {
+                    segments.push(line[start..index].trim());
+                    start = index + 1;
+                }
+                // Nothing after a comment can start a new statement
+                '#' => break,
+                _ => {}
+            },
+        }
+    }
+    segments.push(line[start..].trim());
+
+    segments.retain(|segment| !segment.is_empty());
+    segments
+}
+
+/// Validate a single import statement, reporting where it is malformed
+///
+/// # Errors
+///
+/// Returns [`ImportParseError::InvalidStatement`] whose span is the byte range
+/// of the offending part within `statement` (an empty range where a keyword or
+/// name is missing).
+///
+/// # Examples
+///
+/// ```
+/// use py_import_helper::utils::parsing::validate_import_statement;
+///
+/// assert!(validate_import_statement("from typing import Any").is_ok());
+/// let error = validate_import_statement("from typing Any").unwrap_err();
+/// assert_eq!(error.span(), Some(15..15));
+/// ```
+pub fn validate_import_statement(statement: &str) -> Result<(), ImportParseError> {
+    let offset = |part: &str| part.as_ptr() as usize - statement.as_ptr() as usize;
+    let span_of = |part: &str| offset(part)..offset(part) + part.len();
+    let error = |message: &str, span: Range<usize>| ImportParseError::InvalidStatement {
+        message: message.to_string(),
+        span: Some(span),
+    };
+
+    let trimmed = statement.trim();
+    let end = offset(trimmed) + trimmed.len();
+
+    if let Some(from_part) = trimmed.strip_prefix("from ") {
+        let Some((package_part, items_part)) = from_part.split_once(" import") else {
+            return Err(error("expected `import` keyword", end..end));
+        };
+        let package = package_part.trim();
+        if !is_valid_module_path(package, true) {
+            let span = if package.is_empty() {
+                offset(package_part)..offset(package_part)
+            } else {
+                span_of(package)
+            };
+            return Err(error("expected a module path", span));
+        }
+
+        let mut has_items = false;
+        for piece in items_part.split(',') {
+            let item = piece.trim_matches(|c: char| c.is_whitespace() || c == '(' || c == ')');
+            if item.is_empty() {
+                continue;
+            }
+            has_items = true;
+            let normalized = item.split_whitespace().collect::<Vec<_>>().join(" ");
+            if !is_valid_import_item(&normalized) {
+                return Err(error("expected an identifier or `*`", span_of(item)));
+            }
+        }
+        if !has_items {
+            return Err(error("expected imported names", end..end));
+        }
+        return Ok(());
+    }
+
+    if let Some(modules) = trimmed.strip_prefix("import ") {
+        for piece in modules.split(',') {
+            let module = piece.trim();
+            let normalized = module.split_whitespace().collect::<Vec<_>>().join(" ");
+            let valid = match normalized.split_once(" as ") {
+                Some((path, alias)) => {
+                    is_valid_module_path(path, false) && is_valid_identifier(alias)
+                }
+                None => is_valid_module_path(&normalized, false),
+            };
+            if !valid {
+                let span = if module.is_empty() {
+                    offset(piece)..offset(piece)
+                } else {
+                    span_of(module)
+                };
+                return Err(error("expected a module path", span));
+            }
+        }
+        return Ok(());
+    }
+
+    let keyword = trimmed.split_whitespace().next().unwrap_or(trimmed);
+    Err(error("expected `import` or `from`", span_of(keyword)))
+}
+
+/// Check whether a statement segment is an import
+pub(crate) fn is_import_segment(segment: &str) -> bool {
+    segment.starts_with("import ") || segment.starts_with("from ")
+}
+
+/// Turn the original text of an import into a [`SourceImport`]
+///
+/// Top-level imports that cannot be fully structured are kept verbatim as
+/// [`SourceImport::Unparsed`] if they still lex as an import statement, and
+/// dropped otherwise; inside `TYPE_CHECKING` they are passed on as-is.
+fn classify_import(original: &str, type_checking: bool) -> Option<SourceImport> {
+    let (code, comment) = split_trailing_comment(original);
+    let statement = join_import_lines(code);
+    if type_checking || is_structured_import(&statement) {
+        let statement = match comment {
+            Some(comment) => format!("{statement}  # {comment}"),
+            None => statement,
+        };
+        Some(SourceImport::Statement {
+            statement,
+            type_checking,
+        })
+    } else {
+        lexes_as_import(&statement).then(|| SourceImport::Unparsed(original.to_string()))
+    }
+}
+
+/// Check whether a single-line statement has the shape of a Python import
+///
+/// Unlike [`is_structured_import`], several modules (`import os, sys`) and
+/// parenthesized items are accepted; prose such as `from here on` is not.
+fn lexes_as_import(statement: &str) -> bool {
+    let is_item = |item: &str, is_name: fn(&str) -> bool| match item
+        .split_whitespace()
+        .collect::<Vec<_>>()[..]
+    {
+        [name] => is_name(name),
+        [name, "as", alias] => is_name(name) && is_valid_identifier(alias),
+        _ => false,
+    };
+
+    if let Some(modules) = statement.strip_prefix("import ") {
+        return modules
+            .split(',')
+            .all(|module| is_item(module, |path| is_valid_module_path(path, false)));
+    }
+    let Some((package, items)) = statement
+        .strip_prefix("from ")
+        .and_then(|from_part| from_part.split_once(" import "))
+    else {
+        return false;
+    };
+    let items = items.trim();
+    let items = items
+        .strip_prefix('(')
+        .and_then(|inner| inner.strip_suffix(')'))
+        .unwrap_or(items);
+    let items = items.trim().strip_suffix(',').unwrap_or(items);
+    is_valid_module_path(package.trim(), true)
+        && (items.trim() == "*"
+            || items
+                .split(',')
+                .all(|item| is_item(item, is_valid_identifier)))
+}
+
+/// Get the delimiter of a triple-quoted string left open at the end of a line
+fn opened_string(line: &str) -> Option<&'static str> {
+    let (start, delimiter) = ["\"\"\"", "'''"]
+        .into_iter()
+        .filter_map(|delimiter| line.find(delimiter).map(|start| (start, delimiter)))
+        .min()?;
+    if line[..start].contains('#') {
+        return None;
+    }
+    (line[start..].matches(delimiter).count() % 2 == 1).then_some(delimiter)
+}
+
+/// Check whether a line opens a version-gated block (`if sys.version_info ...:`)
+fn is_version_guard(line: &str) -> bool {
+    line.starts_with("if sys.version_info") && line.ends_with(':')
+}
+
+/// Finalize a `# fmt: off` block, keeping it only if it contains an import
+///
+/// A fence left open at the end of the source runs to the end, as in Black.
+fn finish_fenced_block(block: Option<Vec<String>>) -> Option<SourceImport> {
+    let mut lines = block?;
+    while lines.last().is_some_and(String::is_empty) {
+        lines.pop();
+    }
+
+    lines
+        .iter()
+        .any(|line| is_import_segment(line.trim_start()))
+        .then_some(SourceImport::Fenced(lines))
+}
+
+/// Finalize a version-gated block, keeping it only if its body consists of imports
+///
+/// Blank lines inside the block are dropped, like those between other imports.
+fn finish_conditional_block(block: Option<Vec<String>>) -> Option<SourceImport> {
+    let mut lines = block?;
+    lines.retain(|line| !line.is_empty());
+
+    let imports_only = lines.iter().skip(1).all(|line| {
+        let trimmed = line.trim();
+        trimmed.is_empty()
+            || trimmed.starts_with('#')
+            || trimmed.starts_with("import ")
+            || trimmed.starts_with("from ")
+            || trimmed.starts_with("elif ")
+            || trimmed == "else:"
+            || trimmed == "pass"
+    });
+
+    imports_only.then_some(SourceImport::ConditionalBlock(lines))
+}
+
+/// Check whether a line opens an `if TYPE_CHECKING:` block
+fn is_type_checking_guard(line: &str) -> bool {
+    matches!(line, "if TYPE_CHECKING:" | "if typing.TYPE_CHECKING:")
+}
+
+/// Split the trailing `# comment` off a single-line import
+///
+/// Returns the statement without the comment and the comment text without the
+/// leading `#`. Multi-line imports are returned unchanged; comments on their item
+/// lines are dropped when parsing.
+///
+/// # Examples
+///
+/// ```
+/// use py_import_helper::utils::parsing::split_trailing_comment;
+///
+/// assert_eq!(
+///     split_trailing_comment("import os  # noqa: F401"),
+///     ("import os", Some("noqa: F401"))
+/// );
+/// assert_eq!(split_trailing_comment("import os"), ("import os", None));
+/// ```
+#[must_use]
+pub fn split_trailing_comment(statement: &str) -> (&str, Option<&str>) {
+    if statement.contains('\n') {
+        return (statement, None);
+    }
+    match statement.split_once('#') {
+        Some((code, comment)) => {
+            let comment = comment.trim();
+            (code.trim_end(), (!comment.is_empty()).then_some(comment))
+        }
+        None => (statement, None),
+    }
+}
+
+/// Remove `#` comments from the item lines of a parenthesized import
+///
+/// Comments would otherwise end up in item names. Single-line imports are
+/// returned unchanged so trailing comments (e.g. `# noqa`) are not lost.
+fn strip_item_comments(items_part: &str) -> String {
+    if !items_part.contains('\n') {
+        return items_part.to_string();
+    }
+    items_part
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Normalize a (possibly parenthesized) import into a single-line statement
+fn join_import_lines(statement: &str) -> String {
+    match statement.split_once(" import ") {
+        Some((head, items_part)) if head.starts_with("from ") => {
+            let items: Vec<String> = strip_item_comments(items_part)
+                .split(['(', ')', ','])
+                .map(|s| s.split_whitespace().collect::<Vec<_>>().join(" "))
+                .filter(|s| !s.is_empty())
+                .collect();
+            format!("{} import {}", head.trim(), items.join(", "))
+        }
+        _ => statement.split_whitespace().collect::<Vec<_>>().join(" "),
+    }
+}
+
+/// Check whether a name is a valid Python identifier
+///
+/// # Examples
+///
+/// ```
+/// use py_import_helper::utils::parsing::is_valid_identifier;
+///
+/// assert!(is_valid_identifier("BaseModel"));
+/// assert!(is_valid_identifier("_private"));
+/// assert!(!is_valid_identifier("1st"));
+/// assert!(!is_valid_identifier("Bad Item"));
+/// ```
+#[must_use]
+pub fn is_valid_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Check whether a module path is a valid dotted Python name
+///
+/// Leading dots (relative imports such as `.models` or `..`) are accepted
+/// when `allow_relative` is true.
+///
+/// # Examples
+///
+/// ```
+/// use py_import_helper::utils::parsing::is_valid_module_path;
+///
+/// assert!(is_valid_module_path("collections.abc", false));
+/// assert!(is_valid_module_path("..models", true));
+/// assert!(!is_valid_module_path("..models", false));
+/// assert!(!is_valid_module_path("my package", false));
+/// ```
+#[must_use]
+pub fn is_valid_module_path(path: &str, allow_relative: bool) -> bool {
+    let remainder = if allow_relative {
+        let stripped = path.trim_start_matches('.');
+        if stripped.is_empty() {
+            return !path.is_empty();
+        }
+        stripped
+    } else {
+        path
+    };
+
+    remainder.split('.').all(is_valid_identifier)
+}
+
+/// Check whether a single-line import can be fully structured by the parser
+///
+/// Accepts `import module`, `import module as alias`, and
+/// `from module import item, other as alias` forms with valid names. Valid but
+/// unusual forms such as `import os, sys` are rejected.
+///
+/// # Examples
+///
+/// ```
+/// use py_import_helper::utils::parsing::is_structured_import;
+///
+/// assert!(is_structured_import("import numpy as np"));
+/// assert!(is_structured_import("from .models import User as U"));
+/// assert!(!is_structured_import("import os, sys"));
+/// ```
+#[must_use]
+pub fn is_structured_import(import_statement: &str) -> bool {
+    if let Some(from_part) = import_statement.strip_prefix("from ") {
+        return match from_part.split_once(" import ") {
+            Some((package, _)) => {
+                let items = extract_items(import_statement);
+                is_valid_module_path(package.trim(), true)
+                    && !items.is_empty()
+                    && items.iter().all(|item| is_valid_import_item(item))
+            }
+            None => false,
+        };
+    }
+
+    match import_statement.strip_prefix("import ") {
+        Some(module) => match module.split_once(" as ") {
+            Some((path, alias)) => {
+                is_valid_module_path(path.trim(), false) && is_valid_identifier(alias.trim())
+            }
+            None => is_valid_module_path(module.trim(), false),
+        },
+        None => false,
+    }
+}
+
+/// Check whether an imported item is valid (`name`, `name as alias`, or `*`)
+#[must_use]
+pub fn is_valid_import_item(item: &str) -> bool {
+    if item == "*" {
+        return true;
+    }
+    match item.split_once(" as ") {
+        Some((name, alias)) => is_valid_identifier(name) && is_valid_identifier(alias),
+        None => is_valid_identifier(item),
+    }
+}
+
+/// Check whether a statement is an invalid module-level star import (`import *`)
+///
+/// Only `from module import *` is valid Python; a direct `import *` is a syntax error.
+///
+/// # Examples
+///
+/// ```
+/// use py_import_helper::utils::parsing::is_direct_star_import;
+///
+/// assert!(is_direct_star_import("import *"));
+/// assert!(!is_direct_star_import("from os import *"));
+/// ```
+#[must_use]
+pub fn is_direct_star_import(import_statement: &str) -> bool {
+    import_statement
+        .trim()
+        .strip_prefix("import ")
+        .is_some_and(|modules| modules.split(',').any(|module| module.trim() == "*"))
+}
+
 /// Parse an import statement and categorize it
 #[must_use]
 pub fn parse_import(import_statement: &str, category: ImportCategory) -> Option<ImportStatement> {
     let trimmed = import_statement.trim();
-    if trimmed.is_empty() {
+    if trimmed.is_empty() || is_direct_star_import(trimmed) {
         return None;
     }
 
+    let (trimmed, comment) = split_trailing_comment(trimmed);
     let import_type = if trimmed.starts_with("from ") {
         ImportType::From
     } else {
@@ -149,6 +1113,9 @@ pub fn parse_import(import_statement: &str, category: ImportCategory) -> Option<
 
     let package = extract_package(trimmed);
     let items = extract_items(trimmed);
+    if import_type == ImportType::From && items.is_empty() {
+        return None;
+    }
     let is_multiline = trimmed.contains('(') || trimmed.contains(')');
 
     // Reconstruct the statement with sorted items for from imports
@@ -165,6 +1132,8 @@ pub fn parse_import(import_statement: &str, category: ImportCategory) -> Option<
         package,
         items,
         is_multiline,
+        comment: comment.map(str::to_string),
+        auto_added: false,
     })
 }
 
@@ -197,4 +1166,306 @@ mod tests {
         items.sort_by(|a, b| custom_import_sort(a, b));
         assert_eq!(items, vec!["LITERAL", "TYPE_CHECKING", "Any", "Optional"]);
     }
+
+    #[test]
+    fn test_extract_items_with_alias() {
+        let items = extract_items("from numpy import array as arr, ndarray");
+        assert_eq!(items, vec!["array as arr", "ndarray"]);
+    }
+
+    #[test]
+    fn test_collect_import_statements_type_checking_block() {
+        let source = "from typing import TYPE_CHECKING\n\nif TYPE_CHECKING:\n    from httpx import (\n        Client,\n    )\n\ndef f():\n    import os\n";
+        let imports = collect_import_statements(source);
+        assert_eq!(
+            imports,
+            vec![
+                SourceImport::Statement {
+                    statement: "from typing import TYPE_CHECKING".to_string(),
+                    type_checking: false,
+                },
+                SourceImport::Statement {
+                    statement: "from httpx import Client".to_string(),
+                    type_checking: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_locate_import_block() {
+        let source = "#!/usr/bin/env python\n\"\"\"Module docs.\n\nMore.\n\"\"\"\n\nimport sys\nfrom typing import (\n    TYPE_CHECKING,\n    Any,\n)\n# models\nif TYPE_CHECKING:\n    from httpx import Client\n\n\n# code starts here\nx = 1\n";
+        let block = locate_import_block(source);
+        assert!(source[..block.start].ends_with("\"\"\"\n\n"));
+        assert!(source[block.clone()].starts_with("import sys\n"));
+        assert!(source[block.clone()].ends_with("    from httpx import Client\n"));
+        assert_eq!(&source[block.end..], "\n\n# code starts here\nx = 1\n");
+
+        // Without imports the block goes after the docstring or header comments
+        assert_eq!(
+            locate_import_block("# header\n\n'''Docs.'''\nx = 1\n"),
+            22..22
+        );
+        assert_eq!(locate_import_block("# header\nx = 1\n"), 9..9);
+        assert_eq!(locate_import_block("x = 1\nimport os\n"), 0..0);
+
+        // A fence closed right after the last import is part of the block
+        let source = "import os\n# fmt: off\nimport re, sys\n# fmt: on\n\nx = 1\n";
+        assert_eq!(locate_import_block(source), 0..46);
+        assert_eq!(locate_import_block(""), 0..0);
+    }
+
+    #[test]
+    fn test_collect_import_statements_fmt_off_block() {
+        let source = "import sys\n# fmt: off\nfrom typing import (Optional,\n    Any)\nimport  os\n# fmt: on\nimport abc\n# fmt: off\nx = [1,2]\n# fmt: on\n";
+        let imports = collect_import_statements(source);
+        assert_eq!(imports.len(), 3);
+        assert_eq!(
+            imports[1],
+            SourceImport::Fenced(vec![
+                "# fmt: off".to_string(),
+                "from typing import (Optional,".to_string(),
+                "    Any)".to_string(),
+                "import  os".to_string(),
+                "# fmt: on".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_collect_import_statements_version_block() {
+        let source = "import sys\n\nif sys.version_info >= (3, 11):\n    import tomllib\nelse:\n    import tomli as tomllib\n\nimport os\n";
+        let imports = collect_import_statements(source);
+        assert_eq!(imports.len(), 3);
+        assert_eq!(
+            imports[1],
+            SourceImport::ConditionalBlock(vec![
+                "if sys.version_info >= (3, 11):".to_string(),
+                "    import tomllib".to_string(),
+                "else:".to_string(),
+                "    import tomli as tomllib".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_collect_import_statements_item_comments() {
+        let source = "from typing import (\n    Any,  # common\n    Optional,  # see f()\n)\n";
+        assert_eq!(
+            collect_import_statements(source),
+            vec![SourceImport::Statement {
+                statement: "from typing import Any, Optional".to_string(),
+                type_checking: false,
+            }]
+        );
+        assert_eq!(
+            extract_items_in_order("from typing import (\n    Any,  # common\n    Optional,\n)"),
+            vec!["Any", "Optional"]
+        );
+    }
+
+    #[test]
+    fn test_validate_import_statement_spans() {
+        let span = |statement: &str| validate_import_statement(statement).unwrap_err().span();
+
+        assert!(validate_import_statement("from typing import (Any, Optional)").is_ok());
+        assert!(validate_import_statement("import numpy as np, os.path").is_ok());
+
+        assert_eq!(span("from typing Any"), Some(15..15));
+        assert_eq!(span("  improt os"), Some(2..8));
+        assert_eq!(span("from typing import Any, 1bad"), Some(24..28));
+        assert_eq!(span("import os, *"), Some(11..12));
+        assert_eq!(span("from  import Any"), Some(5..5));
+        assert_eq!(span("from typing import "), Some(18..18));
+    }
+
+    #[test]
+    fn test_parse_import_keeps_trailing_comment() {
+        let import = parse_import(
+            "from os import path  # noqa: F401",
+            ImportCategory::StandardLibrary,
+        )
+        .unwrap();
+        assert_eq!(import.items, vec!["path"]);
+        assert_eq!(import.statement, "from os import path");
+        assert_eq!(import.comment.as_deref(), Some("noqa: F401"));
+        assert!(import.has_noqa());
+
+        assert_eq!(
+            collect_import_statements("import os  # noqa\n"),
+            vec![SourceImport::Statement {
+                statement: "import os  # noqa".to_string(),
+                type_checking: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_split_statements() {
+        assert_eq!(
+            split_statements("import os; import sys; x = 1"),
+            vec!["import os", "import sys", "x = 1"]
+        );
+        assert_eq!(
+            split_statements("x = 'a;b'; import os"),
+            vec!["x = 'a;b'", "import os"]
+        );
+        assert_eq!(
+            split_statements("import os  # a; b"),
+            vec!["import os  # a; b"]
+        );
+        assert!(split_statements(" ; ").is_empty());
+    }
+
+    #[test]
+    fn test_collect_import_statements_unparsed() {
+        let imports = collect_import_statements("import os,  sys\nimport json\n");
+        assert_eq!(
+            imports[0],
+            SourceImport::Unparsed("import os,  sys".to_string())
+        );
+        assert!(matches!(imports[1], SourceImport::Statement { .. }));
+    }
+
+    #[test]
+    fn test_collect_import_statements_skips_strings() {
+        let source = "\"\"\"Module docs.\n\nfrom here on the API is stable;\nimport this carefully.\n\"\"\"\nimport os\nHELP = '''\nimport sys\n'''\nfrom . import (\n    a,\n    b,\n)\n";
+        let imports = collect_import_statements(source);
+        assert_eq!(
+            imports,
+            vec![
+                SourceImport::Statement {
+                    statement: "import os".to_string(),
+                    type_checking: false,
+                },
+                SourceImport::Statement {
+                    statement: "from . import a, b".to_string(),
+                    type_checking: false,
+                },
+            ]
+        );
+
+        // Text that only starts like an import is not kept as an unparsed import
+        assert!(collect_import_statements("from here on\nimport this carefully\n").is_empty());
+        assert!(lexes_as_import("from .models import (a, b as c,)"));
+        assert!(lexes_as_import("import os.path as p, sys"));
+        assert!(!lexes_as_import("import this carefully"));
+    }
+
+    #[test]
+    fn test_parse_import_rejects_direct_star() {
+        assert!(parse_import("import *", ImportCategory::StandardLibrary).is_none());
+        assert!(parse_import("from os import *", ImportCategory::StandardLibrary).is_some());
+    }
+
+    #[test]
+    fn test_parse_import_rejects_empty_from_import() {
+        assert!(parse_import("from x import", ImportCategory::Local).is_none());
+        assert!(parse_import("from x import ", ImportCategory::Local).is_none());
+        assert!(parse_import("from x import ()", ImportCategory::Local).is_none());
+        assert!(validate_import_statement("from x import").is_err());
+    }
+
+    #[test]
+    fn test_is_valid_import_item() {
+        assert!(is_valid_import_item("Any"));
+        assert!(is_valid_import_item("*"));
+        assert!(is_valid_import_item("numpy as np"));
+        assert!(!is_valid_import_item("Bad Item"));
+        assert!(!is_valid_import_item(""));
+    }
+
+    #[test]
+    fn test_collect_backslash_continuation() {
+        let source = "from typing import \\\n    Any, \\\n    Optional\n# note \\\nimport os\n";
+        assert_eq!(
+            collect_import_statements(source),
+            vec![
+                SourceImport::Statement {
+                    statement: "from typing import Any, Optional".to_string(),
+                    type_checking: false,
+                },
+                SourceImport::Statement {
+                    statement: "import os".to_string(),
+                    type_checking: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_natural_cmp() {
+        use std::cmp::Ordering;
+
+        assert_eq!(natural_cmp("module2", "module10"), Ordering::Less);
+        assert_eq!(natural_cmp("module10", "module10"), Ordering::Equal);
+        assert_eq!(natural_cmp("module010", "module9"), Ordering::Greater);
+        assert_eq!(natural_cmp("module", "module1"), Ordering::Less);
+        assert_eq!(natural_cmp("abc", "abd"), Ordering::Less);
+        assert_ne!(natural_cmp("m01", "m1"), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_parse_imports_from_reader() {
+        let source = concat!(
+            "\"\"\"Module docs.\"\"\"\n",
+            "from __future__ import annotations\n",
+            "import os; import sys; x = 1\n",
+            "from typing import (  # typing\n",
+            "    Any,\n",
+            "    Optional,\n",
+            ")\n",
+            "from collections import \\\n",
+            "    OrderedDict\n",
+            "from requests import get  # noqa: F401\n",
+            "from typing Any\n",
+            "from .models import User\n",
+            "\n",
+            "def main():\n",
+            "    import json\n",
+        );
+
+        let results: Vec<_> = parse_imports(std::io::Cursor::new(source)).collect();
+        assert_eq!(results.len(), 9);
+        assert!(results[6].is_err());
+
+        let imports: Vec<ImportStatement> = results.into_iter().filter_map(Result::ok).collect();
+        let summary: Vec<(&str, ImportCategory)> = imports
+            .iter()
+            .map(|import| (import.statement.as_str(), import.category))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("from __future__ import annotations", ImportCategory::Future),
+                ("import os", ImportCategory::StandardLibrary),
+                ("import sys", ImportCategory::StandardLibrary),
+                (
+                    "from typing import Any, Optional",
+                    ImportCategory::StandardLibrary
+                ),
+                (
+                    "from collections import OrderedDict",
+                    ImportCategory::StandardLibrary
+                ),
+                ("from requests import get", ImportCategory::ThirdParty),
+                ("from .models import User", ImportCategory::Local),
+                ("import json", ImportCategory::StandardLibrary),
+            ]
+        );
+        assert_eq!(imports[3].items, vec!["Any", "Optional"]);
+        assert_eq!(imports[5].comment.as_deref(), Some("noqa: F401"));
+
+        let unterminated: Vec<_> =
+            parse_imports(std::io::Cursor::new("from typing import (\n    Any,\n")).collect();
+        assert_eq!(unterminated.len(), 1);
+        assert!(unterminated[0].is_err());
+
+        // Submodules of standard library packages are standard library
+        let source = "import os.path\nfrom xml.etree import ElementTree\n";
+        let categories: Vec<ImportCategory> = parse_imports(std::io::Cursor::new(source))
+            .map(|import| import.unwrap().category)
+            .collect();
+        assert_eq!(categories, vec![ImportCategory::StandardLibrary; 2]);
+    }
 }
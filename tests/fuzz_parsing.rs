@@ -0,0 +1,162 @@
+//! Fuzz-style robustness tests for the parsing utilities
+//!
+//! Random strings assembled from import-like fragments, punctuation and
+//! multibyte characters are fed to the parsing entry points. None of them may
+//! panic, and their outputs must stay well-formed.
+
+use py_import_helper::utils::parsing::{
+    collect_import_statements, extract_items, extract_items_in_order, extract_package,
+    is_structured_import, locate_import_block, split_statements, split_trailing_comment,
+    validate_import_statement,
+};
+use py_import_helper::{normalize_statement, FormattingConfig, ImportHelper};
+
+/// Fragments that steer random input towards interesting parser paths
+const FRAGMENTS: &[&str] = &[
+    "from ",
+    "import ",
+    " import ",
+    " as ",
+    "typing",
+    "os.path",
+    ".",
+    "..",
+    "(",
+    ")",
+    ",",
+    ", ",
+    ";",
+    "#",
+    " ",
+    "  ",
+    "\n",
+    "\t",
+    "*",
+    "Any",
+    "_",
+    "1",
+    "é",
+    "ß",
+    "日本",
+    "🐍",
+    "\u{200b}",
+    "\"",
+    "'",
+    "\\",
+    "if TYPE_CHECKING:",
+    "    ",
+];
+
+/// Minimal xorshift generator so the tests are deterministic without extra dependencies
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    fn input(&mut self) -> String {
+        let len = self.below(12);
+        (0..len)
+            .map(|_| FRAGMENTS[self.below(FRAGMENTS.len())])
+            .collect()
+    }
+}
+
+fn random_inputs(seed: u64, count: usize) -> Vec<String> {
+    let mut rng = XorShift(seed);
+    (0..count).map(|_| rng.input()).collect()
+}
+
+#[test]
+fn test_extract_functions_never_panic() {
+    for input in random_inputs(0x5eed_1234, 5000) {
+        let package = extract_package(&input);
+        let items = extract_items(&input);
+        let ordered = extract_items_in_order(&input);
+
+        assert_eq!(items.len(), ordered.len(), "input: {input:?}");
+        if !input.contains('\n') && (input.starts_with("import ") || input.starts_with("from ")) {
+            assert!(!package.contains('\n'), "input: {input:?}");
+        }
+        if input.starts_with("from ") {
+            for item in &ordered {
+                assert!(!item.is_empty(), "input: {input:?}");
+                assert!(!item.contains([',', '(', ')']), "input: {input:?}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_validation_spans_are_in_bounds() {
+    for input in random_inputs(0xfeed_beef, 5000) {
+        let _ = is_structured_import(&input);
+
+        if let Err(error) = validate_import_statement(&input) {
+            let span = error.span().expect("statement errors carry a span");
+            assert!(span.start <= span.end, "input: {input:?}");
+            assert!(span.end <= input.len(), "input: {input:?}");
+            assert!(input.is_char_boundary(span.start), "input: {input:?}");
+            assert!(input.is_char_boundary(span.end), "input: {input:?}");
+        }
+    }
+}
+
+#[test]
+fn test_statement_splitting_never_panics() {
+    for input in random_inputs(0x0dd_ba11, 5000) {
+        for segment in split_statements(&input) {
+            assert!(!segment.is_empty(), "input: {input:?}");
+            assert_eq!(segment, segment.trim(), "input: {input:?}");
+        }
+
+        let (code, comment) = split_trailing_comment(&input);
+        assert!(input.starts_with(code), "input: {input:?}");
+        if let Some(comment) = comment {
+            assert!(!comment.is_empty(), "input: {input:?}");
+        }
+
+        let _ = collect_import_statements(&input);
+
+        let block = locate_import_block(&input);
+        assert!(
+            block.start <= block.end && block.end <= input.len(),
+            "input: {input:?}"
+        );
+        assert!(input.is_char_boundary(block.start), "input: {input:?}");
+        assert!(input.is_char_boundary(block.end), "input: {input:?}");
+    }
+}
+
+#[test]
+fn test_helper_accepts_arbitrary_input() {
+    let config = FormattingConfig::default();
+    let mut helper = ImportHelper::new();
+
+    for input in random_inputs(0xc0ff_ee00, 2000) {
+        helper.add_import_string(&input);
+        let _ = helper.try_add_import_string(&input);
+        helper.ingest(&input);
+
+        if let Some(normalized) = normalize_statement(&input, &config) {
+            assert!(
+                normalized.starts_with("import ") || normalized.starts_with("from "),
+                "input: {input:?}"
+            );
+        }
+    }
+
+    let rendered = helper.render_file();
+    for input in random_inputs(0xa11_0ca7e, 100) {
+        let _ = helper.apply_to_source(&input);
+    }
+    assert_eq!(helper.apply_to_source(""), rendered);
+}